@@ -1,12 +1,39 @@
 extern crate bindgen;
 
 use std::env;
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Lists `("<path below static>", include_bytes!("<absolute path>")),` for every
+// file below `dir`.
+fn embedded_assets(dir: &Path, prefix: &str, out: &mut String) {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .expect("Unable to read the asset directory")
+        .map(|entry| entry.expect("Unable to read the asset directory").path())
+        .collect();
+    paths.sort();
+    for path in paths {
+        let name = format!(
+            "{prefix}{}",
+            path.file_name()
+                .unwrap()
+                .to_str()
+                .expect("Asset names must be UTF-8")
+        );
+        if path.is_dir() {
+            embedded_assets(&path, &format!("{name}/"), out);
+        } else {
+            let path = fs::canonicalize(&path).unwrap();
+            out.push_str(&format!("    ({name:?}, include_bytes!({path:?})),\n"));
+        }
+    }
+}
 
 fn main() {
     println!("cargo:rustc-link-lib=raylib");
     println!("cargo:rustc-link-lib=OpenGL");
     println!("cargo:rerun-if-changed=raylib/raylib.h");
+    println!("cargo:rerun-if-changed=static");
 
     let bindings = bindgen::Builder::default()
         .header("raylib/raylib.h")
@@ -20,4 +47,14 @@ fn main() {
     bindings
         .write_to_file(out_path.join("raylib_bindings.rs"))
         .expect("Couldn't write bindings!");
+
+    let mut assets = String::new();
+    if env::var_os("CARGO_FEATURE_EMBED_ASSETS").is_some() {
+        embedded_assets(Path::new("static"), "", &mut assets);
+    }
+    fs::write(
+        out_path.join("embedded_assets.rs"),
+        format!("const EMBEDDED_ASSETS: &[(&str, &[u8])] = &[\n{assets}];\n"),
+    )
+    .expect("Couldn't write the embedded assets!");
 }
@@ -1,8 +1,280 @@
+pub mod headless_impl;
 pub mod raylib_impl;
 
-use std::path::Path;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
 
 use crate::faux_quicksilver::{Circle, Color, Rectangle, Transform, Vector};
+use crate::layout::Align;
+use crate::text;
+use crate::vfs::Vfs;
+
+macro_rules! keys {
+    ($($name:ident = $code:literal,)*) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Key {
+            $($name,)*
+        }
+
+        impl Key {
+            pub const ALL: &'static [Key] = &[$(Key::$name,)*];
+
+            /// The key code used by raylib (and GLFW).
+            pub fn code(self) -> u32 {
+                match self {
+                    $(Key::$name => $code,)*
+                }
+            }
+        }
+    };
+}
+
+// Printable keys use the ASCII value of their (upper case) character as code.
+keys! {
+    Space = 32,
+    Apostrophe = 39,
+    Comma = 44,
+    Minus = 45,
+    Period = 46,
+    Slash = 47,
+    Num0 = 48,
+    Num1 = 49,
+    Num2 = 50,
+    Num3 = 51,
+    Num4 = 52,
+    Num5 = 53,
+    Num6 = 54,
+    Num7 = 55,
+    Num8 = 56,
+    Num9 = 57,
+    Semicolon = 59,
+    Equal = 61,
+    A = 65,
+    B = 66,
+    C = 67,
+    D = 68,
+    E = 69,
+    F = 70,
+    G = 71,
+    H = 72,
+    I = 73,
+    J = 74,
+    K = 75,
+    L = 76,
+    M = 77,
+    N = 78,
+    O = 79,
+    P = 80,
+    Q = 81,
+    R = 82,
+    S = 83,
+    T = 84,
+    U = 85,
+    V = 86,
+    W = 87,
+    X = 88,
+    Y = 89,
+    Z = 90,
+    LeftBracket = 91,
+    Backslash = 92,
+    RightBracket = 93,
+    Grave = 96,
+    Escape = 256,
+    Enter = 257,
+    Tab = 258,
+    Backspace = 259,
+    Insert = 260,
+    Delete = 261,
+    Right = 262,
+    Left = 263,
+    Down = 264,
+    Up = 265,
+    PageUp = 266,
+    PageDown = 267,
+    Home = 268,
+    End = 269,
+    CapsLock = 280,
+    ScrollLock = 281,
+    NumLock = 282,
+    PrintScreen = 283,
+    Pause = 284,
+    F1 = 290,
+    F2 = 291,
+    F3 = 292,
+    F4 = 293,
+    F5 = 294,
+    F6 = 295,
+    F7 = 296,
+    F8 = 297,
+    F9 = 298,
+    F10 = 299,
+    F11 = 300,
+    F12 = 301,
+    Keypad0 = 320,
+    Keypad1 = 321,
+    Keypad2 = 322,
+    Keypad3 = 323,
+    Keypad4 = 324,
+    Keypad5 = 325,
+    Keypad6 = 326,
+    Keypad7 = 327,
+    Keypad8 = 328,
+    Keypad9 = 329,
+    KeypadDecimal = 330,
+    KeypadDivide = 331,
+    KeypadMultiply = 332,
+    KeypadSubtract = 333,
+    KeypadAdd = 334,
+    KeypadEnter = 335,
+    KeypadEqual = 336,
+    LeftShift = 340,
+    LeftControl = 341,
+    LeftAlt = 342,
+    LeftSuper = 343,
+    RightShift = 344,
+    RightControl = 345,
+    RightAlt = 346,
+    RightSuper = 347,
+    Menu = 348,
+}
+
+impl Key {
+    pub fn from_code(code: u32) -> Option<Key> {
+        Key::ALL.iter().copied().find(|key| key.code() == code)
+    }
+
+    pub fn from_char(c: char) -> Option<Key> {
+        Key::from_code(c.to_ascii_uppercase() as u32)
+    }
+
+    pub fn is_modifier(self) -> bool {
+        matches!(
+            self,
+            Key::LeftShift
+                | Key::LeftControl
+                | Key::LeftAlt
+                | Key::LeftSuper
+                | Key::RightShift
+                | Key::RightControl
+                | Key::RightAlt
+                | Key::RightSuper
+        )
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Key::Space => String::from("Space"),
+            key if key.code() < 128 => char::from(key.code() as u8).to_string(),
+            key => format!("{key:?}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub super_key: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+/// Mouse (or single touch) input of the current frame, in screen
+/// coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PointerEvent {
+    Moved { pos: Vector },
+    Pressed { button: MouseButton, pos: Vector },
+    Released { button: MouseButton, pos: Vector },
+    Wheel { delta: f32 },
+}
+
+/// A finger on a touch screen, in screen coordinates. The id stays the same
+/// while the finger is down.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TouchPoint {
+    pub id: u32,
+    pub pos: Vector,
+}
+
+/// Named after their position, like on an Xbox pad `FaceDown` is A and
+/// `FaceRight` is B.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    DpadUp,
+    DpadRight,
+    DpadDown,
+    DpadLeft,
+    FaceUp,
+    FaceRight,
+    FaceDown,
+    FaceLeft,
+    LeftBumper,
+    LeftTrigger,
+    RightBumper,
+    RightTrigger,
+    Select,
+    Guide,
+    Start,
+    LeftThumb,
+    RightThumb,
+}
+
+impl GamepadButton {
+    pub const ALL: [GamepadButton; 17] = [
+        GamepadButton::DpadUp,
+        GamepadButton::DpadRight,
+        GamepadButton::DpadDown,
+        GamepadButton::DpadLeft,
+        GamepadButton::FaceUp,
+        GamepadButton::FaceRight,
+        GamepadButton::FaceDown,
+        GamepadButton::FaceLeft,
+        GamepadButton::LeftBumper,
+        GamepadButton::LeftTrigger,
+        GamepadButton::RightBumper,
+        GamepadButton::RightTrigger,
+        GamepadButton::Select,
+        GamepadButton::Guide,
+        GamepadButton::Start,
+        GamepadButton::LeftThumb,
+        GamepadButton::RightThumb,
+    ];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadStick {
+    Left,
+    Right,
+}
+
+/// Ignores stick positions within `deadzone` of the center and rescales the
+/// rest, so moving out of the deadzone starts from zero instead of jumping.
+pub fn apply_deadzone(stick: Vector, deadzone: f32) -> Vector {
+    let len = (stick.x * stick.x + stick.y * stick.y).sqrt();
+    if len <= deadzone || deadzone >= 1.0 {
+        return Vector::new(0.0, 0.0);
+    }
+    let scale = ((len - deadzone) / (1.0 - deadzone)).min(1.0) / len;
+    Vector::new(stick.x * scale, stick.y * scale)
+}
 
 pub trait ImageInterface {
     fn draw(&mut self, x: f32, y: f32, color: Color) -> Result<(), String>;
@@ -35,6 +307,130 @@ pub trait ImageInterface {
 
 pub trait FontInterface {
     fn draw(&mut self, s: &str, size: u32, x: f32, y: f32, color: Color) -> Result<(), String>;
+    /// Width and height of `s` when drawn at `size`.
+    fn measure(&self, s: &str, size: u32) -> Result<Vector, String>;
+
+    /// Width and height of `s` when drawn with `draw_paragraph`.
+    fn measure_paragraph(
+        &self,
+        s: &str,
+        size: u32,
+        style: ParagraphStyle,
+    ) -> Result<Vector, String> {
+        let lines = text::wrap(s, style.max_width, &mut |line| {
+            Ok(self.measure(line, size)?.x)
+        })?;
+        let mut w: f32 = 0.0;
+        for line in &lines {
+            w = w.max(self.measure(line, size)?.x);
+        }
+        Ok(Vector::new(w, style.height(lines.len(), size)))
+    }
+
+    /// Draws `s` wrapped to lines of at most `style.max_width`, starting at
+    /// `x` and `y`. Returns the size of the paragraph.
+    fn draw_paragraph(
+        &mut self,
+        s: &str,
+        size: u32,
+        x: f32,
+        y: f32,
+        style: ParagraphStyle,
+        color: Color,
+    ) -> Result<Vector, String> {
+        let lines = text::wrap(s, style.max_width, &mut |line| {
+            Ok(self.measure(line, size)?.x)
+        })?;
+        let mut w: f32 = 0.0;
+        let mut line_y = y;
+        for line in &lines {
+            let line_w = self.measure(line, size)?.x;
+            self.draw(
+                line,
+                size,
+                x + style.align.offset(style.max_width, line_w),
+                line_y,
+                color,
+            )?;
+            w = w.max(line_w);
+            line_y += size as f32 + style.line_spacing;
+        }
+        Ok(Vector::new(w, style.height(lines.len(), size)))
+    }
+}
+
+/// Which glyphs of a font are rasterized when it is loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FontOptions {
+    /// Glyphs are rasterized at this size, text drawn larger gets blurry.
+    pub size: u32,
+    pub ranges: Vec<RangeInclusive<char>>,
+    /// Fonts that missing glyphs are drawn with, tried in order.
+    pub fallbacks: Vec<PathBuf>,
+}
+
+impl FontOptions {
+    pub const ASCII: RangeInclusive<char> = ' '..='~';
+    pub const LATIN_1: RangeInclusive<char> = '\u{A0}'..='\u{FF}';
+    pub const LATIN_EXTENDED_A: RangeInclusive<char> = '\u{100}'..='\u{17F}';
+    pub const GREEK: RangeInclusive<char> = '\u{370}'..='\u{3FF}';
+    pub const CYRILLIC: RangeInclusive<char> = '\u{400}'..='\u{4FF}';
+    pub const PUNCTUATION: RangeInclusive<char> = '\u{2000}'..='\u{206F}';
+
+    /// Only ASCII, like raylib's default.
+    pub fn new(size: u32) -> Self {
+        Self {
+            size,
+            ranges: vec![Self::ASCII],
+            fallbacks: Vec::new(),
+        }
+    }
+
+    pub fn with_range(mut self, range: RangeInclusive<char>) -> Self {
+        self.ranges.push(range);
+        self
+    }
+
+    pub fn with_fallback(mut self, path: impl Into<PathBuf>) -> Self {
+        self.fallbacks.push(path.into());
+        self
+    }
+
+    /// Every requested codepoint once, in ascending order.
+    pub fn codepoints(&self) -> Vec<i32> {
+        let mut codepoints: Vec<i32> = self
+            .ranges
+            .iter()
+            .flat_map(|range| range.clone().map(|c| c as i32))
+            .collect();
+        codepoints.sort_unstable();
+        codepoints.dedup();
+        codepoints
+    }
+}
+
+/// How `FontInterface::draw_paragraph` lays out its lines. Only left aligned
+/// paragraphs may have an infinite `max_width`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParagraphStyle {
+    pub max_width: f32,
+    pub align: Align,
+    /// Extra space between lines.
+    pub line_spacing: f32,
+}
+
+impl ParagraphStyle {
+    pub fn new(max_width: f32) -> Self {
+        Self {
+            max_width,
+            align: Align::Left,
+            line_spacing: 0.0,
+        }
+    }
+
+    fn height(&self, lines: usize, size: u32) -> f32 {
+        lines as f32 * size as f32 + lines.saturating_sub(1) as f32 * self.line_spacing
+    }
 }
 
 pub trait SoundInterface {
@@ -45,6 +441,7 @@ pub trait MusicInterface {
     fn play(&mut self, vol: f32) -> Result<(), String>;
     fn pause(&mut self) -> Result<(), String>;
     fn stop(&mut self) -> Result<(), String>;
+    fn set_volume(&mut self, vol: f32) -> Result<(), String>;
     fn set_loop(&mut self, loop_enable: bool) -> Result<(), String>;
     fn update(&mut self) -> Result<(), String>;
 }
@@ -52,27 +449,259 @@ pub trait MusicInterface {
 pub trait ShaderInterface {
     fn set_transform_uniform(&mut self, transform: Transform) -> Result<(), String>;
     fn set_origin_uniform(&mut self, origin: Vector) -> Result<(), String>;
-    fn set_camera_uniform(&mut self, camera: Vector) -> Result<(), String>;
+    fn set_camera_uniform(&mut self, view: CameraView) -> Result<(), String>;
     fn begin_draw_shader(&self) -> Result<(), String>;
     fn end_draw_shader(&self) -> Result<(), String>;
 }
 
+/// What a camera shows. `pos` is the world point at the top left of the
+/// screen, zoom scales from there and rotation (in radians) then turns the
+/// view around the screen center.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraView {
+    pub pos: Vector,
+    pub zoom: f32,
+    pub rotation: f32,
+}
+
+impl Default for CameraView {
+    fn default() -> Self {
+        Self {
+            pos: Vector::new(0.0, 0.0),
+            zoom: 1.0,
+            rotation: 0.0,
+        }
+    }
+}
+
+fn rotate_vec(vec: Vector, angle: f32) -> Vector {
+    let (sin, cos) = angle.sin_cos();
+    Vector::new(vec.x * cos - vec.y * sin, vec.x * sin + vec.y * cos)
+}
+
+impl CameraView {
+    /// Must match `to_screen()` in the camera shaders.
+    pub fn world_to_screen(&self, world: Vector, center: Vector) -> Vector {
+        rotate_vec((world - self.pos) * self.zoom - center, -self.rotation) + center
+    }
+
+    pub fn screen_to_world(&self, screen: Vector, center: Vector) -> Vector {
+        self.pos + (rotate_vec(screen - center, self.rotation) + center) * (1.0 / self.zoom)
+    }
+}
+
+/// How the game's virtual resolution is fitted into the window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScaleMode {
+    /// Scales the virtual resolution as large as it fits, with black bars
+    /// on the remaining sides.
+    Letterbox,
+    /// Scales the same way but grows the drawing area to fill the window,
+    /// so more of the world is visible.
+    Expand,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub virtual_size: Vector,
+    pub mode: ScaleMode,
+}
+
+/// Where a `Viewport` ends up in a window of a given size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewportFit {
+    /// Window pixels per virtual pixel.
+    pub scale: f32,
+    /// Window position of the virtual origin.
+    pub offset: Vector,
+    /// Size of the drawing area in virtual pixels.
+    pub size: Vector,
+}
+
+impl Viewport {
+    pub fn fit(&self, window_size: Vector) -> ViewportFit {
+        let scale = (window_size.x / self.virtual_size.x)
+            .min(window_size.y / self.virtual_size.y)
+            .max(f32::EPSILON);
+        match self.mode {
+            ScaleMode::Letterbox => ViewportFit {
+                scale,
+                offset: (window_size - self.virtual_size * scale) * 0.5,
+                size: self.virtual_size,
+            },
+            ScaleMode::Expand => ViewportFit {
+                scale,
+                offset: Vector::new(0.0, 0.0),
+                size: Vector::new(window_size.x / scale, window_size.y / scale),
+            },
+        }
+    }
+}
+
+impl ViewportFit {
+    pub fn to_virtual(&self, window_pos: Vector) -> Vector {
+        let pos = window_pos - self.offset;
+        Vector::new(pos.x / self.scale, pos.y / self.scale)
+    }
+}
+
 pub trait CameraInterface {
     fn get_view_xy(&self) -> Result<(f32, f32), String>;
     fn set_view_xy(&mut self, x: f32, y: f32) -> Result<(), String>;
+    fn get_zoom(&self) -> Result<f32, String>;
+    fn set_zoom(&mut self, zoom: f32) -> Result<(), String>;
+    fn get_rotation(&self) -> Result<f32, String>;
+    fn set_rotation(&mut self, rotation: f32) -> Result<(), String>;
+    fn get_view(&self) -> Result<CameraView, String> {
+        Ok(CameraView {
+            pos: self.get_view_xy()?.into(),
+            zoom: self.get_zoom()?,
+            rotation: self.get_rotation()?,
+        })
+    }
+}
+
+// Both backends use this as their camera.
+impl CameraInterface for CameraView {
+    fn get_view_xy(&self) -> Result<(f32, f32), String> {
+        Ok((self.pos.x, self.pos.y))
+    }
+
+    fn set_view_xy(&mut self, x: f32, y: f32) -> Result<(), String> {
+        self.pos = Vector::new(x, y);
+        Ok(())
+    }
+
+    fn get_zoom(&self) -> Result<f32, String> {
+        Ok(self.zoom)
+    }
+
+    fn set_zoom(&mut self, zoom: f32) -> Result<(), String> {
+        if zoom <= 0.0 || !zoom.is_finite() {
+            return Err(format!("Invalid camera zoom {zoom}!"));
+        }
+        self.zoom = zoom;
+        Ok(())
+    }
+
+    fn get_rotation(&self) -> Result<f32, String> {
+        Ok(self.rotation)
+    }
+
+    fn set_rotation(&mut self, rotation: f32) -> Result<(), String> {
+        if !rotation.is_finite() {
+            return Err(format!("Invalid camera rotation {rotation}!"));
+        }
+        self.rotation = rotation % std::f32::consts::TAU;
+        Ok(())
+    }
+}
+
+/// Resources a backend currently holds, to spot assets that are never unloaded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceReport {
+    pub images: usize,
+    /// Images that were uploaded to the GPU.
+    pub textures: usize,
+    pub fonts: usize,
+    pub sounds: usize,
+    pub music: usize,
+    pub shaders: usize,
+}
+
+impl fmt::Display for ResourceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} images ({} textures), {} fonts, {} shaders, {} sounds, {} music streams",
+            self.images, self.textures, self.fonts, self.shaders, self.sounds, self.music
+        )
+    }
 }
 
 pub trait GameInterface {
+    /// Size of the drawing area in virtual pixels. All drawing and input
+    /// positions use these.
     fn get_dimensions(&self) -> Result<(f32, f32), String>;
-    fn get_key_pressed(&mut self, key: char) -> Result<bool, String>;
+    fn set_viewport(&mut self, viewport: Viewport) -> Result<(), String>;
+    fn is_key_pressed(&mut self, key: Key) -> Result<bool, String>;
+    /// True when the key is held long enough to repeat, like when typing.
+    fn is_key_pressed_repeat(&mut self, key: Key) -> Result<bool, String>;
+    fn is_key_down(&mut self, key: Key) -> Result<bool, String>;
+    fn is_key_released(&mut self, key: Key) -> Result<bool, String>;
+    fn get_modifiers(&mut self) -> Result<Modifiers, String> {
+        Ok(Modifiers {
+            shift: self.is_key_down(Key::LeftShift)? || self.is_key_down(Key::RightShift)?,
+            ctrl: self.is_key_down(Key::LeftControl)? || self.is_key_down(Key::RightControl)?,
+            alt: self.is_key_down(Key::LeftAlt)? || self.is_key_down(Key::RightAlt)?,
+            super_key: self.is_key_down(Key::LeftSuper)? || self.is_key_down(Key::RightSuper)?,
+        })
+    }
+    /// Older char based version of `is_key_pressed`. Characters without a
+    /// key of their own are never pressed.
+    fn get_key_pressed(&mut self, key: char) -> Result<bool, String> {
+        match Key::from_char(key) {
+            Some(key) => self.is_key_pressed(key),
+            None => Ok(false),
+        }
+    }
     fn get_mouse_pressed(&mut self) -> Result<Option<(f32, f32)>, String>;
     fn get_mouse_released(&mut self) -> Result<bool, String>;
     fn get_mouse_down(&mut self) -> Result<Option<(f32, f32)>, String>;
+    fn get_mouse_button_pressed(&mut self, button: MouseButton) -> Result<bool, String>;
+    fn get_mouse_button_released(&mut self, button: MouseButton) -> Result<bool, String>;
+    fn get_mouse_button_down(&mut self, button: MouseButton) -> Result<bool, String>;
+    /// Positive when scrolling up (away from the user).
+    fn get_mouse_wheel(&mut self) -> Result<f32, String>;
+    fn get_pointer_events(&mut self) -> Result<Vec<PointerEvent>, String>;
+    fn get_touch_points(&mut self) -> Result<Vec<TouchPoint>, String>;
     fn get_mouse_xy(&self) -> Result<(f32, f32), String>;
+    /// Indices of the connected gamepads.
+    fn get_gamepads(&mut self) -> Result<Vec<usize>, String>;
+    fn is_gamepad_button_pressed(
+        &mut self,
+        pad: usize,
+        button: GamepadButton,
+    ) -> Result<bool, String>;
+    fn is_gamepad_button_down(&mut self, pad: usize, button: GamepadButton)
+        -> Result<bool, String>;
+    fn is_gamepad_button_released(
+        &mut self,
+        pad: usize,
+        button: GamepadButton,
+    ) -> Result<bool, String>;
+    /// Sticks range from -1.0 to 1.0, with positive y pointing down. Triggers
+    /// range from -1.0 when released to 1.0.
+    fn get_gamepad_axis(&mut self, pad: usize, axis: GamepadAxis) -> Result<f32, String>;
+    fn get_gamepad_stick(
+        &mut self,
+        pad: usize,
+        stick: GamepadStick,
+        deadzone: f32,
+    ) -> Result<Vector, String> {
+        let (x, y) = match stick {
+            GamepadStick::Left => (GamepadAxis::LeftX, GamepadAxis::LeftY),
+            GamepadStick::Right => (GamepadAxis::RightX, GamepadAxis::RightY),
+        };
+        Ok(apply_deadzone(
+            Vector::new(
+                self.get_gamepad_axis(pad, x)?,
+                self.get_gamepad_axis(pad, y)?,
+            ),
+            deadzone,
+        ))
+    }
     fn get_mouse_xy_vec(&self) -> Result<Vector, String>;
     fn xy_to_world(&self, x: f32, y: f32) -> Result<Vector, String>;
     fn vec_to_world(&self, vec: Vector) -> Result<Vector, String>;
+    fn world_to_screen(&self, world: Vector) -> Result<Vector, String>;
     fn get_delta_time(&self) -> f32;
+    fn is_audio_ready(&self) -> bool;
+    fn set_master_volume(&mut self, volume: f32) -> Result<(), String>;
+    fn set_fullscreen(&mut self, fullscreen: bool) -> Result<(), String>;
+    fn set_target_fps(&mut self, fps: u32) -> Result<(), String>;
+    /// Pressing the exit key closes the window, `None` disables it.
+    fn set_exit_key(&mut self, key: Option<Key>) -> Result<(), String>;
     fn clear_window(&mut self, color: Color) -> Result<(), String>;
     fn begin_drawing(&mut self) -> Result<(), String>;
     fn end_drawing(&mut self) -> Result<(), String>;
@@ -101,8 +730,18 @@ pub trait GameInterface {
         origin: Vector,
     ) -> Result<(), String>;
 
+    /// Where `load_*` read their files from.
+    fn vfs(&self) -> &Vfs;
+    /// Reads the images, fonts and shaders loaded from `path` again, in place
+    /// so existing handles use them. Returns whether anything was loaded from
+    /// it, on errors the old assets stay.
+    fn reload(&mut self, path: &Path) -> Result<bool, String>;
     fn load_image(&mut self, path: &Path) -> Result<Box<dyn ImageInterface>, String>;
-    fn load_font(&mut self, path: &Path) -> Result<Box<dyn FontInterface>, String>;
+    fn load_font(
+        &mut self,
+        path: &Path,
+        options: &FontOptions,
+    ) -> Result<Box<dyn FontInterface>, String>;
     fn load_sound(&mut self, path: &Path) -> Result<Box<dyn SoundInterface>, String>;
     fn load_music(&mut self, path: &Path) -> Result<Box<dyn MusicInterface>, String>;
     fn load_shader(
@@ -112,6 +751,15 @@ pub trait GameInterface {
         fs: &Path,
     ) -> Result<Box<dyn ShaderInterface>, String>;
 
+    // Loading the same path again shares what was loaded the first time. The
+    // backend only frees it on unload once every handle to it was dropped.
+    fn unload_image(&mut self, path: &Path) -> Result<(), String>;
+    fn unload_font(&mut self, path: &Path, options: &FontOptions) -> Result<(), String>;
+    fn unload_sound(&mut self, path: &Path) -> Result<(), String>;
+    fn unload_music(&mut self, path: &Path) -> Result<(), String>;
+    fn unload_shader(&mut self, name: &str) -> Result<(), String>;
+    fn resource_report(&self) -> ResourceReport;
+
     fn get_camera(&mut self) -> Result<Box<dyn CameraInterface>, String>;
     fn get_default_camera(&mut self) -> Result<Box<dyn CameraInterface>, String>;
     fn set_camera(&mut self, camera: &dyn CameraInterface) -> Result<(), String>;
@@ -0,0 +1,1417 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    path::Path,
+    rc::Rc,
+};
+
+use crate::faux_quicksilver::{Circle, Color, Rectangle, Transform, Vector};
+use crate::vfs::Vfs;
+
+use super::{
+    CameraInterface, CameraView, FontInterface, FontOptions, GameInterface, GamepadAxis,
+    GamepadButton, ImageInterface, Key, MouseButton, MusicInterface, PointerEvent, ResourceReport,
+    ScaleMode, ShaderInterface, SoundInterface, TouchPoint, Viewport, ViewportFit,
+};
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+fn path_to_string(path: &Path) -> Result<String, String> {
+    Ok(path
+        .to_str()
+        .ok_or_else(|| format!("Failed to convert \"{path:?}\" to str!"))?
+        .to_owned())
+}
+
+fn check_file(vfs: &Vfs, path: &Path) -> Result<String, String> {
+    let path_str = path_to_string(path)?;
+    if vfs.exists(path) {
+        Ok(path_str)
+    } else {
+        Err(format!("File \"{path_str}\" does not exist!"))
+    }
+}
+
+/// Reads the width and height out of the IHDR chunk of a PNG file.
+pub fn png_dimensions(data: &[u8]) -> Result<(usize, usize), String> {
+    if data.len() < 24 || data[0..8] != PNG_SIGNATURE || &data[12..16] != b"IHDR" {
+        return Err(String::from("Not a valid PNG header!"));
+    }
+    let width = u32::from_be_bytes(data[16..20].try_into().map_err(|_| "Invalid PNG width")?);
+    let height = u32::from_be_bytes(data[20..24].try_into().map_err(|_| "Invalid PNG height")?);
+
+    Ok((width as usize, height as usize))
+}
+
+#[derive(Debug, Default)]
+struct HeadlessInput {
+    mouse_xy: Vector,
+    buttons_down: HashSet<MouseButton>,
+    buttons_pressed: HashSet<MouseButton>,
+    buttons_released: HashSet<MouseButton>,
+    wheel: f32,
+    pointer_events: Vec<PointerEvent>,
+    touches: Vec<TouchPoint>,
+    keys_down: HashSet<Key>,
+    keys_pressed: HashSet<Key>,
+    keys_repeated: HashSet<Key>,
+    keys_released: HashSet<Key>,
+    gamepads: HashMap<usize, HeadlessGamepad>,
+}
+
+#[derive(Debug, Default)]
+struct HeadlessGamepad {
+    buttons_down: HashSet<GamepadButton>,
+    buttons_pressed: HashSet<GamepadButton>,
+    buttons_released: HashSet<GamepadButton>,
+    axes: HashMap<GamepadAxis, f32>,
+}
+
+#[derive(Debug)]
+struct HeadlessState {
+    // Of the window, input is injected in window coordinates too.
+    width: f32,
+    height: f32,
+    viewport: Viewport,
+    delta_time: f32,
+    audio_ready: bool,
+    master_volume: f32,
+    fullscreen: bool,
+    target_fps: u32,
+    exit_key: Option<Key>,
+    is_drawing: bool,
+    frame_count: u64,
+    draw_calls: u64,
+    input: HeadlessInput,
+    camera: CameraView,
+    images: HashMap<String, (usize, usize)>,
+    fonts: HashSet<String>,
+    sounds: HashMap<String, u32>,
+    music: HashMap<String, bool>,
+    // By name, with the paths of the vertex and fragment shader.
+    shaders: HashMap<String, [String; 2]>,
+    // Shared with every handle to an asset, like the raylib backend shares
+    // the loaded resource.
+    live: HashMap<String, Rc<()>>,
+}
+
+impl HeadlessState {
+    fn share(&mut self, key: String) -> Rc<()> {
+        self.live.entry(key).or_default().clone()
+    }
+
+    /// Whether `key` was released because no handle shares it anymore.
+    fn release(&mut self, key: &str) -> Result<bool, String> {
+        let live = self
+            .live
+            .get(key)
+            .ok_or_else(|| format!("\"{key}\" is not loaded!"))?;
+        if Rc::strong_count(live) > 1 {
+            return Ok(false);
+        }
+        self.live.remove(key);
+        Ok(true)
+    }
+
+    fn count_draw(&mut self) -> Result<(), String> {
+        if !self.is_drawing {
+            return Err(String::from("Drew outside of begin_drawing/end_drawing!"));
+        }
+        self.draw_calls += 1;
+        Ok(())
+    }
+
+    fn fit(&self) -> ViewportFit {
+        self.viewport.fit(Vector::new(self.width, self.height))
+    }
+
+    fn mouse_xy(&self) -> Vector {
+        self.fit().to_virtual(self.input.mouse_xy)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HeadlessImage {
+    state: Rc<RefCell<HeadlessState>>,
+    // The size is looked up, as the image may be reloaded.
+    path: String,
+    _live: Rc<()>,
+}
+
+impl HeadlessImage {
+    fn dimensions(&self) -> (usize, usize) {
+        self.state.borrow().images[&self.path]
+    }
+}
+
+impl ImageInterface for HeadlessImage {
+    fn draw(&mut self, _x: f32, _y: f32, _color: Color) -> Result<(), String> {
+        self.state.borrow_mut().count_draw()
+    }
+
+    fn draw_sub(
+        &mut self,
+        _sub_rect: Rectangle,
+        _dest_rect: Rectangle,
+        _color: Color,
+    ) -> Result<(), String> {
+        self.state.borrow_mut().count_draw()
+    }
+
+    fn draw_transform(
+        &mut self,
+        _x: f32,
+        _y: f32,
+        _color: Color,
+        _transform: Transform,
+        _origin: Vector,
+    ) -> Result<(), String> {
+        self.state.borrow_mut().count_draw()
+    }
+
+    fn draw_sub_transform(
+        &mut self,
+        _sub_rect: Rectangle,
+        _dest_rect: Rectangle,
+        _color: Color,
+        _transform: Transform,
+        _origin: Vector,
+    ) -> Result<(), String> {
+        self.state.borrow_mut().count_draw()
+    }
+
+    fn get_w(&self) -> usize {
+        self.dimensions().0
+    }
+
+    fn get_h(&self) -> usize {
+        self.dimensions().1
+    }
+
+    fn get_wh_rect(&self) -> Rectangle {
+        let (w, h) = self.dimensions();
+        Rectangle::new(0.0, 0.0, w as f32, h as f32)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HeadlessFont {
+    state: Rc<RefCell<HeadlessState>>,
+    // One for the font and each fallback.
+    _live: Vec<Rc<()>>,
+}
+
+impl FontInterface for HeadlessFont {
+    fn draw(
+        &mut self,
+        _s: &str,
+        _size: u32,
+        _x: f32,
+        _y: f32,
+        _color: Color,
+    ) -> Result<(), String> {
+        self.state.borrow_mut().count_draw()
+    }
+
+    // Every character is half as wide as it is high.
+    fn measure(&self, s: &str, size: u32) -> Result<Vector, String> {
+        let size = size as f32;
+        Ok(Vector::new(s.chars().count() as f32 * size / 2.0, size))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HeadlessSound {
+    state: Rc<RefCell<HeadlessState>>,
+    path: String,
+    _live: Rc<()>,
+}
+
+impl SoundInterface for HeadlessSound {
+    fn play(&mut self, _vol: f32) -> Result<(), String> {
+        let mut state = self.state.borrow_mut();
+        if state.audio_ready {
+            *state.sounds.entry(self.path.clone()).or_default() += 1;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HeadlessMusic {
+    state: Rc<RefCell<HeadlessState>>,
+    path: String,
+    looping: bool,
+    _live: Rc<()>,
+}
+
+impl HeadlessMusic {
+    fn set_playing(&mut self, playing: bool) {
+        let mut state = self.state.borrow_mut();
+        if state.audio_ready {
+            state.music.insert(self.path.clone(), playing);
+        }
+    }
+}
+
+impl MusicInterface for HeadlessMusic {
+    fn play(&mut self, _vol: f32) -> Result<(), String> {
+        self.set_playing(true);
+        Ok(())
+    }
+
+    fn pause(&mut self) -> Result<(), String> {
+        self.set_playing(false);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), String> {
+        self.set_playing(false);
+        Ok(())
+    }
+
+    fn set_volume(&mut self, _vol: f32) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn set_loop(&mut self, loop_enable: bool) -> Result<(), String> {
+        self.looping = loop_enable;
+        Ok(())
+    }
+
+    fn update(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct HeadlessShader {
+    transform: Transform,
+    origin: Vector,
+    camera: CameraView,
+    _live: Rc<()>,
+}
+
+impl ShaderInterface for HeadlessShader {
+    fn set_transform_uniform(&mut self, transform: Transform) -> Result<(), String> {
+        self.transform = transform;
+        Ok(())
+    }
+
+    fn set_origin_uniform(&mut self, origin: Vector) -> Result<(), String> {
+        self.origin = origin;
+        Ok(())
+    }
+
+    fn set_camera_uniform(&mut self, view: CameraView) -> Result<(), String> {
+        self.camera = view;
+        Ok(())
+    }
+
+    fn begin_draw_shader(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn end_draw_shader(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// A `GameInterface` that needs neither a window nor a GPU.
+///
+/// Input is injected and state is inspected through a `HeadlessController`
+/// obtained with `HeadlessGame::get_controller()`.
+pub struct HeadlessGame {
+    state: Rc<RefCell<HeadlessState>>,
+    vfs: Vfs,
+}
+
+/// Handle to a `HeadlessGame` that stays usable after the game has been boxed
+/// into a `Window`.
+///
+/// Pressed/released input is consumed at the next `end_drawing()`, just like
+/// a frame of the raylib backend.
+#[derive(Clone)]
+pub struct HeadlessController {
+    state: Rc<RefCell<HeadlessState>>,
+}
+
+impl HeadlessGame {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(HeadlessState {
+                width: width as f32,
+                height: height as f32,
+                viewport: Viewport {
+                    virtual_size: Vector::new(width as f32, height as f32),
+                    mode: ScaleMode::Letterbox,
+                },
+                delta_time: 1.0 / 60.0,
+                audio_ready: true,
+                master_volume: 1.0,
+                fullscreen: false,
+                target_fps: 60,
+                exit_key: Some(Key::Escape),
+                is_drawing: false,
+                frame_count: 0,
+                draw_calls: 0,
+                input: HeadlessInput::default(),
+                camera: CameraView::default(),
+                images: HashMap::new(),
+                fonts: HashSet::new(),
+                sounds: HashMap::new(),
+                music: HashMap::new(),
+                shaders: HashMap::new(),
+                live: HashMap::new(),
+            })),
+            vfs: Vfs::default(),
+        }
+    }
+
+    /// Assets are read from the working directory unless another `vfs` is
+    /// given.
+    pub fn with_vfs(mut self, vfs: Vfs) -> Self {
+        self.vfs = vfs;
+        self
+    }
+
+    pub fn new_boxed(width: u32, height: u32) -> (Box<dyn GameInterface>, HeadlessController) {
+        let game = Self::new(width, height);
+        let controller = game.get_controller();
+        (Box::new(game), controller)
+    }
+
+    pub fn get_controller(&self) -> HeadlessController {
+        HeadlessController {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl HeadlessController {
+    pub fn set_delta_time(&self, dt: f32) {
+        self.state.borrow_mut().delta_time = dt;
+    }
+
+    /// Resizes the window, like a user dragging its border.
+    pub fn set_dimensions(&self, width: f32, height: f32) {
+        let mut state = self.state.borrow_mut();
+        state.width = width;
+        state.height = height;
+    }
+
+    pub fn set_audio_ready(&self, ready: bool) {
+        self.state.borrow_mut().audio_ready = ready;
+    }
+
+    pub fn set_mouse_xy(&self, x: f32, y: f32) {
+        let mut state = self.state.borrow_mut();
+        let pos = Vector::new(x, y);
+        if state.input.mouse_xy != pos {
+            state.input.mouse_xy = pos;
+            state.input.pointer_events.push(PointerEvent::Moved { pos });
+        }
+    }
+
+    pub fn press_mouse(&self) {
+        self.press_mouse_button(MouseButton::Left);
+    }
+
+    pub fn release_mouse(&self) {
+        self.release_mouse_button(MouseButton::Left);
+    }
+
+    pub fn press_mouse_button(&self, button: MouseButton) {
+        let mut state = self.state.borrow_mut();
+        state.input.buttons_down.insert(button);
+        state.input.buttons_pressed.insert(button);
+        let pos = state.input.mouse_xy;
+        state
+            .input
+            .pointer_events
+            .push(PointerEvent::Pressed { button, pos });
+    }
+
+    pub fn release_mouse_button(&self, button: MouseButton) {
+        let mut state = self.state.borrow_mut();
+        state.input.buttons_down.remove(&button);
+        state.input.buttons_released.insert(button);
+        let pos = state.input.mouse_xy;
+        state
+            .input
+            .pointer_events
+            .push(PointerEvent::Released { button, pos });
+    }
+
+    pub fn scroll_wheel(&self, delta: f32) {
+        let mut state = self.state.borrow_mut();
+        state.input.wheel += delta;
+        state
+            .input
+            .pointer_events
+            .push(PointerEvent::Wheel { delta });
+    }
+
+    /// Puts a finger down, or moves it if it is already down.
+    pub fn touch(&self, id: u32, x: f32, y: f32) {
+        let touches = &mut self.state.borrow_mut().input.touches;
+        let pos = Vector::new(x, y);
+        match touches.iter_mut().find(|t| t.id == id) {
+            Some(touch) => touch.pos = pos,
+            None => touches.push(TouchPoint { id, pos }),
+        }
+    }
+
+    pub fn lift_touch(&self, id: u32) {
+        self.state.borrow_mut().input.touches.retain(|t| t.id != id);
+    }
+
+    pub fn connect_gamepad(&self, pad: usize) {
+        self.state
+            .borrow_mut()
+            .input
+            .gamepads
+            .entry(pad)
+            .or_default();
+    }
+
+    pub fn disconnect_gamepad(&self, pad: usize) {
+        self.state.borrow_mut().input.gamepads.remove(&pad);
+    }
+
+    /// Connects the gamepad if needed.
+    pub fn press_gamepad_button(&self, pad: usize, button: GamepadButton) {
+        let mut state = self.state.borrow_mut();
+        let gamepad = state.input.gamepads.entry(pad).or_default();
+        gamepad.buttons_down.insert(button);
+        gamepad.buttons_pressed.insert(button);
+    }
+
+    pub fn release_gamepad_button(&self, pad: usize, button: GamepadButton) {
+        let mut state = self.state.borrow_mut();
+        let gamepad = state.input.gamepads.entry(pad).or_default();
+        gamepad.buttons_down.remove(&button);
+        gamepad.buttons_released.insert(button);
+    }
+
+    pub fn set_gamepad_axis(&self, pad: usize, axis: GamepadAxis, value: f32) {
+        let mut state = self.state.borrow_mut();
+        let gamepad = state.input.gamepads.entry(pad).or_default();
+        gamepad.axes.insert(axis, value);
+    }
+
+    pub fn get_viewport_fit(&self) -> ViewportFit {
+        self.state.borrow().fit()
+    }
+
+    pub fn click(&self, x: f32, y: f32) {
+        self.set_mouse_xy(x, y);
+        self.press_mouse();
+    }
+
+    /// Presses the key of the given character and keeps it held.
+    pub fn press_key(&self, key: char) {
+        let key = Key::from_char(key).unwrap_or_else(|| panic!("No key for {key:?}"));
+        self.hold_key(key);
+    }
+
+    pub fn hold_key(&self, key: Key) {
+        let mut state = self.state.borrow_mut();
+        state.input.keys_down.insert(key);
+        state.input.keys_pressed.insert(key);
+    }
+
+    pub fn repeat_key(&self, key: Key) {
+        self.state.borrow_mut().input.keys_repeated.insert(key);
+    }
+
+    pub fn release_key(&self, key: Key) {
+        let mut state = self.state.borrow_mut();
+        state.input.keys_down.remove(&key);
+        state.input.keys_released.insert(key);
+    }
+
+    pub fn get_master_volume(&self) -> f32 {
+        self.state.borrow().master_volume
+    }
+
+    pub fn is_fullscreen(&self) -> bool {
+        self.state.borrow().fullscreen
+    }
+
+    pub fn get_target_fps(&self) -> u32 {
+        self.state.borrow().target_fps
+    }
+
+    pub fn get_exit_key(&self) -> Option<Key> {
+        self.state.borrow().exit_key
+    }
+
+    pub fn get_frame_count(&self) -> u64 {
+        self.state.borrow().frame_count
+    }
+
+    pub fn get_draw_calls(&self) -> u64 {
+        self.state.borrow().draw_calls
+    }
+
+    pub fn get_camera_xy(&self) -> (f32, f32) {
+        let state = self.state.borrow();
+        (state.camera.pos.x, state.camera.pos.y)
+    }
+
+    pub fn get_camera_zoom(&self) -> f32 {
+        self.state.borrow().camera.zoom
+    }
+
+    pub fn get_camera_rotation(&self) -> f32 {
+        self.state.borrow().camera.rotation
+    }
+
+    pub fn get_image_dimensions(&self, path: &str) -> Option<(usize, usize)> {
+        self.state.borrow().images.get(path).copied()
+    }
+
+    pub fn is_font_loaded(&self, path: &str) -> bool {
+        self.state.borrow().fonts.contains(path)
+    }
+
+    pub fn is_shader_loaded(&self, name: &str) -> bool {
+        self.state.borrow().shaders.contains_key(name)
+    }
+
+    pub fn is_sound_loaded(&self, path: &str) -> bool {
+        self.state.borrow().sounds.contains_key(path)
+    }
+
+    pub fn get_sound_play_count(&self, path: &str) -> u32 {
+        self.state
+            .borrow()
+            .sounds
+            .get(path)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn is_music_playing(&self, path: &str) -> bool {
+        self.state
+            .borrow()
+            .music
+            .get(path)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl GameInterface for HeadlessGame {
+    fn get_dimensions(&self) -> Result<(f32, f32), String> {
+        let size = self.state.borrow().fit().size;
+        Ok((size.x, size.y))
+    }
+
+    fn set_viewport(&mut self, viewport: Viewport) -> Result<(), String> {
+        if viewport.virtual_size.x <= 0.0 || viewport.virtual_size.y <= 0.0 {
+            return Err(format!("Invalid virtual size {:?}!", viewport.virtual_size));
+        }
+        self.state.borrow_mut().viewport = viewport;
+        Ok(())
+    }
+
+    fn is_key_pressed(&mut self, key: Key) -> Result<bool, String> {
+        Ok(self.state.borrow().input.keys_pressed.contains(&key))
+    }
+
+    fn is_key_pressed_repeat(&mut self, key: Key) -> Result<bool, String> {
+        Ok(self.state.borrow().input.keys_repeated.contains(&key))
+    }
+
+    fn is_key_down(&mut self, key: Key) -> Result<bool, String> {
+        Ok(self.state.borrow().input.keys_down.contains(&key))
+    }
+
+    fn is_key_released(&mut self, key: Key) -> Result<bool, String> {
+        Ok(self.state.borrow().input.keys_released.contains(&key))
+    }
+
+    fn get_mouse_pressed(&mut self) -> Result<Option<(f32, f32)>, String> {
+        let state = self.state.borrow();
+        if state.input.buttons_pressed.contains(&MouseButton::Left) {
+            let pos = state.mouse_xy();
+            Ok(Some((pos.x, pos.y)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn get_mouse_released(&mut self) -> Result<bool, String> {
+        Ok(self
+            .state
+            .borrow()
+            .input
+            .buttons_released
+            .contains(&MouseButton::Left))
+    }
+
+    fn get_mouse_down(&mut self) -> Result<Option<(f32, f32)>, String> {
+        let state = self.state.borrow();
+        if state.input.buttons_down.contains(&MouseButton::Left) {
+            let pos = state.mouse_xy();
+            Ok(Some((pos.x, pos.y)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn get_mouse_button_pressed(&mut self, button: MouseButton) -> Result<bool, String> {
+        Ok(self.state.borrow().input.buttons_pressed.contains(&button))
+    }
+
+    fn get_mouse_button_released(&mut self, button: MouseButton) -> Result<bool, String> {
+        Ok(self.state.borrow().input.buttons_released.contains(&button))
+    }
+
+    fn get_mouse_button_down(&mut self, button: MouseButton) -> Result<bool, String> {
+        Ok(self.state.borrow().input.buttons_down.contains(&button))
+    }
+
+    fn get_mouse_wheel(&mut self) -> Result<f32, String> {
+        Ok(self.state.borrow().input.wheel)
+    }
+
+    fn get_pointer_events(&mut self) -> Result<Vec<PointerEvent>, String> {
+        let state = self.state.borrow();
+        let fit = state.fit();
+        Ok(state
+            .input
+            .pointer_events
+            .iter()
+            .map(|event| match *event {
+                PointerEvent::Moved { pos } => PointerEvent::Moved {
+                    pos: fit.to_virtual(pos),
+                },
+                PointerEvent::Pressed { button, pos } => PointerEvent::Pressed {
+                    button,
+                    pos: fit.to_virtual(pos),
+                },
+                PointerEvent::Released { button, pos } => PointerEvent::Released {
+                    button,
+                    pos: fit.to_virtual(pos),
+                },
+                PointerEvent::Wheel { delta } => PointerEvent::Wheel { delta },
+            })
+            .collect())
+    }
+
+    fn get_touch_points(&mut self) -> Result<Vec<TouchPoint>, String> {
+        let state = self.state.borrow();
+        let fit = state.fit();
+        Ok(state
+            .input
+            .touches
+            .iter()
+            .map(|touch| TouchPoint {
+                id: touch.id,
+                pos: fit.to_virtual(touch.pos),
+            })
+            .collect())
+    }
+
+    fn get_gamepads(&mut self) -> Result<Vec<usize>, String> {
+        let mut pads: Vec<usize> = self.state.borrow().input.gamepads.keys().copied().collect();
+        pads.sort_unstable();
+        Ok(pads)
+    }
+
+    fn is_gamepad_button_pressed(
+        &mut self,
+        pad: usize,
+        button: GamepadButton,
+    ) -> Result<bool, String> {
+        Ok(self
+            .state
+            .borrow()
+            .input
+            .gamepads
+            .get(&pad)
+            .is_some_and(|gamepad| gamepad.buttons_pressed.contains(&button)))
+    }
+
+    fn is_gamepad_button_down(
+        &mut self,
+        pad: usize,
+        button: GamepadButton,
+    ) -> Result<bool, String> {
+        Ok(self
+            .state
+            .borrow()
+            .input
+            .gamepads
+            .get(&pad)
+            .is_some_and(|gamepad| gamepad.buttons_down.contains(&button)))
+    }
+
+    fn is_gamepad_button_released(
+        &mut self,
+        pad: usize,
+        button: GamepadButton,
+    ) -> Result<bool, String> {
+        Ok(self
+            .state
+            .borrow()
+            .input
+            .gamepads
+            .get(&pad)
+            .is_some_and(|gamepad| gamepad.buttons_released.contains(&button)))
+    }
+
+    fn get_gamepad_axis(&mut self, pad: usize, axis: GamepadAxis) -> Result<f32, String> {
+        Ok(self
+            .state
+            .borrow()
+            .input
+            .gamepads
+            .get(&pad)
+            .and_then(|gamepad| gamepad.axes.get(&axis).copied())
+            .unwrap_or(0.0))
+    }
+
+    fn get_mouse_xy(&self) -> Result<(f32, f32), String> {
+        let pos = self.state.borrow().mouse_xy();
+        Ok((pos.x, pos.y))
+    }
+
+    fn get_mouse_xy_vec(&self) -> Result<Vector, String> {
+        Ok(self.state.borrow().mouse_xy())
+    }
+
+    fn xy_to_world(&self, x: f32, y: f32) -> Result<Vector, String> {
+        let state = self.state.borrow();
+        let center = state.fit().size * 0.5;
+        Ok(state.camera.screen_to_world(Vector::new(x, y), center))
+    }
+
+    fn vec_to_world(&self, vec: Vector) -> Result<Vector, String> {
+        self.xy_to_world(vec.x, vec.y)
+    }
+
+    fn world_to_screen(&self, world: Vector) -> Result<Vector, String> {
+        let state = self.state.borrow();
+        let center = state.fit().size * 0.5;
+        Ok(state.camera.world_to_screen(world, center))
+    }
+
+    fn get_delta_time(&self) -> f32 {
+        self.state.borrow().delta_time
+    }
+
+    fn is_audio_ready(&self) -> bool {
+        self.state.borrow().audio_ready
+    }
+
+    fn set_master_volume(&mut self, volume: f32) -> Result<(), String> {
+        self.state.borrow_mut().master_volume = volume.clamp(0.0, 1.0);
+        Ok(())
+    }
+
+    fn set_fullscreen(&mut self, fullscreen: bool) -> Result<(), String> {
+        self.state.borrow_mut().fullscreen = fullscreen;
+        Ok(())
+    }
+
+    fn set_target_fps(&mut self, fps: u32) -> Result<(), String> {
+        self.state.borrow_mut().target_fps = fps;
+        Ok(())
+    }
+
+    fn set_exit_key(&mut self, key: Option<Key>) -> Result<(), String> {
+        self.state.borrow_mut().exit_key = key;
+        Ok(())
+    }
+
+    fn clear_window(&mut self, _color: Color) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn begin_drawing(&mut self) -> Result<(), String> {
+        self.state.borrow_mut().is_drawing = true;
+        Ok(())
+    }
+
+    fn end_drawing(&mut self) -> Result<(), String> {
+        let mut state = self.state.borrow_mut();
+        state.is_drawing = false;
+        state.frame_count += 1;
+        state.input.buttons_pressed.clear();
+        state.input.buttons_released.clear();
+        state.input.wheel = 0.0;
+        state.input.pointer_events.clear();
+        state.input.keys_pressed.clear();
+        state.input.keys_repeated.clear();
+        state.input.keys_released.clear();
+        for gamepad in state.input.gamepads.values_mut() {
+            gamepad.buttons_pressed.clear();
+            gamepad.buttons_released.clear();
+        }
+        Ok(())
+    }
+
+    fn draw_circle(&mut self, _circle: Circle, _color: Color) -> Result<(), String> {
+        self.state.borrow_mut().count_draw()
+    }
+
+    fn draw_circle_transform(
+        &mut self,
+        _circle: Circle,
+        _color: Color,
+        _transform: Transform,
+        _origin: Vector,
+    ) -> Result<(), String> {
+        self.state.borrow_mut().count_draw()
+    }
+
+    fn draw_rect(&mut self, _rect: Rectangle, _color: Color) -> Result<(), String> {
+        self.state.borrow_mut().count_draw()
+    }
+
+    fn draw_rect_ex(
+        &mut self,
+        _rect: Rectangle,
+        _color: Color,
+        _origin: Vector,
+        _rot: f32,
+    ) -> Result<(), String> {
+        self.state.borrow_mut().count_draw()
+    }
+
+    fn draw_rect_transform(
+        &mut self,
+        _rect: Rectangle,
+        _color: Color,
+        _transform: Transform,
+        _origin: Vector,
+    ) -> Result<(), String> {
+        self.state.borrow_mut().count_draw()
+    }
+
+    fn load_image(&mut self, path: &Path) -> Result<Box<dyn ImageInterface>, String> {
+        let path_str = path_to_string(path)?;
+        let data = self.vfs.read(path)?;
+        let (w, h) =
+            png_dimensions(&data).map_err(|e| format!("Failed to load \"{path_str}\": {e}"))?;
+        let mut state = self.state.borrow_mut();
+        let live = state.share(format!("image:{path_str}"));
+        state.images.insert(path_str.clone(), (w, h));
+        Ok(Box::new(HeadlessImage {
+            state: self.state.clone(),
+            path: path_str,
+            _live: live,
+        }))
+    }
+
+    fn load_font(
+        &mut self,
+        path: &Path,
+        options: &FontOptions,
+    ) -> Result<Box<dyn FontInterface>, String> {
+        // Nothing is registered unless every file exists.
+        let paths = std::iter::once(path)
+            .chain(options.fallbacks.iter().map(|p| p.as_path()))
+            .map(|path| check_file(&self.vfs, path))
+            .collect::<Result<Vec<_>, String>>()?;
+        let mut state = self.state.borrow_mut();
+        let mut live = Vec::new();
+        for path_str in paths {
+            live.push(state.share(format!("font:{path_str}")));
+            state.fonts.insert(path_str);
+        }
+        Ok(Box::new(HeadlessFont {
+            state: self.state.clone(),
+            _live: live,
+        }))
+    }
+
+    fn load_sound(&mut self, path: &Path) -> Result<Box<dyn SoundInterface>, String> {
+        let path_str = check_file(&self.vfs, path)?;
+        let mut state = self.state.borrow_mut();
+        let live = state.share(format!("sound:{path_str}"));
+        state.sounds.entry(path_str.clone()).or_insert(0);
+        Ok(Box::new(HeadlessSound {
+            state: self.state.clone(),
+            path: path_str,
+            _live: live,
+        }))
+    }
+
+    fn load_music(&mut self, path: &Path) -> Result<Box<dyn MusicInterface>, String> {
+        let path_str = check_file(&self.vfs, path)?;
+        let mut state = self.state.borrow_mut();
+        let live = state.share(format!("music:{path_str}"));
+        state.music.entry(path_str.clone()).or_insert(false);
+        Ok(Box::new(HeadlessMusic {
+            state: self.state.clone(),
+            path: path_str,
+            looping: false,
+            _live: live,
+        }))
+    }
+
+    fn load_shader(
+        &mut self,
+        name: String,
+        vs: &Path,
+        fs: &Path,
+    ) -> Result<Box<dyn ShaderInterface>, String> {
+        let paths = [check_file(&self.vfs, vs)?, check_file(&self.vfs, fs)?];
+        let mut state = self.state.borrow_mut();
+        let live = state.share(format!("shader:{name}"));
+        state.shaders.insert(name, paths);
+        Ok(Box::new(HeadlessShader {
+            transform: Transform::IDENTITY,
+            origin: Vector::new(0.0, 0.0),
+            camera: CameraView::default(),
+            _live: live,
+        }))
+    }
+
+    fn unload_image(&mut self, path: &Path) -> Result<(), String> {
+        let path_str = path_to_string(path)?;
+        let mut state = self.state.borrow_mut();
+        if state.release(&format!("image:{path_str}"))? {
+            state.images.remove(&path_str);
+        }
+        Ok(())
+    }
+
+    fn unload_font(&mut self, path: &Path, options: &FontOptions) -> Result<(), String> {
+        let mut state = self.state.borrow_mut();
+        for path in std::iter::once(path).chain(options.fallbacks.iter().map(|p| p.as_path())) {
+            let path_str = path_to_string(path)?;
+            if state.release(&format!("font:{path_str}"))? {
+                state.fonts.remove(&path_str);
+            }
+        }
+        Ok(())
+    }
+
+    fn unload_sound(&mut self, path: &Path) -> Result<(), String> {
+        let path_str = path_to_string(path)?;
+        let mut state = self.state.borrow_mut();
+        if state.release(&format!("sound:{path_str}"))? {
+            state.sounds.remove(&path_str);
+        }
+        Ok(())
+    }
+
+    fn unload_music(&mut self, path: &Path) -> Result<(), String> {
+        let path_str = path_to_string(path)?;
+        let mut state = self.state.borrow_mut();
+        if state.release(&format!("music:{path_str}"))? {
+            state.music.remove(&path_str);
+        }
+        Ok(())
+    }
+
+    fn unload_shader(&mut self, name: &str) -> Result<(), String> {
+        let mut state = self.state.borrow_mut();
+        if state.release(&format!("shader:{name}"))? {
+            state.shaders.remove(name);
+        }
+        Ok(())
+    }
+
+    fn vfs(&self) -> &Vfs {
+        &self.vfs
+    }
+
+    fn reload(&mut self, path: &Path) -> Result<bool, String> {
+        let path_str = path_to_string(path)?;
+        let mut state = self.state.borrow_mut();
+        if let Some(dimensions) = state.images.get_mut(&path_str) {
+            let data = self.vfs.read(path)?;
+            *dimensions =
+                png_dimensions(&data).map_err(|e| format!("Failed to load \"{path_str}\": {e}"))?;
+            return Ok(true);
+        }
+        let loaded = state.fonts.contains(&path_str)
+            || state
+                .shaders
+                .values()
+                .any(|paths| paths.contains(&path_str));
+        if loaded {
+            check_file(&self.vfs, path)?;
+        }
+        Ok(loaded)
+    }
+
+    fn resource_report(&self) -> ResourceReport {
+        let state = self.state.borrow();
+        ResourceReport {
+            images: state.images.len(),
+            textures: state.images.len(),
+            fonts: state.fonts.len(),
+            sounds: state.sounds.len(),
+            music: state.music.len(),
+            shaders: state.shaders.len(),
+        }
+    }
+
+    fn get_camera(&mut self) -> Result<Box<dyn CameraInterface>, String> {
+        Ok(Box::new(self.state.borrow().camera))
+    }
+
+    fn get_default_camera(&mut self) -> Result<Box<dyn CameraInterface>, String> {
+        Ok(Box::<CameraView>::default())
+    }
+
+    fn set_camera(&mut self, camera: &dyn CameraInterface) -> Result<(), String> {
+        self.state.borrow_mut().camera = camera.get_view()?;
+        Ok(())
+    }
+
+    fn set_camera_xy(&mut self, x: f32, y: f32) -> Result<(), String> {
+        self.state.borrow_mut().camera.pos = Vector { x, y };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agnostic_interface::{Modifiers, ParagraphStyle, ResourceReport};
+    use crate::layout::Align;
+    use crate::vfs::Source;
+
+    #[test]
+    fn test_png_dimensions() {
+        let data = std::fs::read("static/fish.png").expect("fish.png should be readable");
+        assert_eq!(png_dimensions(&data), Ok((48, 16)));
+        assert!(png_dimensions(&data[0..20]).is_err());
+        assert!(png_dimensions(b"definitely not a png file").is_err());
+    }
+
+    #[test]
+    fn test_input_is_consumed_per_frame() {
+        let (mut gi, controller) = HeadlessGame::new_boxed(800, 600);
+        controller.click(10.0, 20.0);
+        controller.press_key('s');
+        assert_eq!(gi.get_mouse_pressed(), Ok(Some((10.0, 20.0))));
+        assert_eq!(gi.get_mouse_down(), Ok(Some((10.0, 20.0))));
+        assert_eq!(gi.get_key_pressed('S'), Ok(true));
+
+        gi.begin_drawing().unwrap();
+        gi.draw_rect(Rectangle::default(), Color::WHITE).unwrap();
+        gi.end_drawing().unwrap();
+        assert_eq!(controller.get_frame_count(), 1);
+        assert_eq!(controller.get_draw_calls(), 1);
+        assert_eq!(gi.get_mouse_pressed(), Ok(None));
+        assert_eq!(gi.get_mouse_down(), Ok(Some((10.0, 20.0))));
+        assert_eq!(gi.get_key_pressed('s'), Ok(false));
+        assert_eq!(gi.is_key_down(Key::S), Ok(true));
+
+        assert!(gi.draw_rect(Rectangle::default(), Color::WHITE).is_err());
+    }
+
+    #[test]
+    fn test_keys_and_modifiers() {
+        let (mut gi, controller) = HeadlessGame::new_boxed(800, 600);
+        assert_eq!(Key::from_char('s'), Some(Key::S));
+        assert_eq!(Key::from_char(';'), Some(Key::Semicolon));
+        assert_eq!(Key::from_char('!'), None);
+        assert_eq!(Key::from_code(Key::F5.code()), Some(Key::F5));
+        assert_eq!(Key::Escape.name(), "Escape");
+        assert_eq!(gi.get_key_pressed('!'), Ok(false));
+
+        controller.hold_key(Key::LeftControl);
+        controller.hold_key(Key::S);
+        assert_eq!(gi.is_key_pressed(Key::S), Ok(true));
+        assert_eq!(
+            gi.get_modifiers(),
+            Ok(Modifiers {
+                ctrl: true,
+                ..Default::default()
+            })
+        );
+
+        gi.begin_drawing().unwrap();
+        gi.end_drawing().unwrap();
+        controller.repeat_key(Key::S);
+        controller.release_key(Key::LeftControl);
+        assert_eq!(gi.is_key_pressed(Key::S), Ok(false));
+        assert_eq!(gi.is_key_pressed_repeat(Key::S), Ok(true));
+        assert_eq!(gi.is_key_released(Key::LeftControl), Ok(true));
+        assert_eq!(gi.get_modifiers(), Ok(Modifiers::default()));
+    }
+
+    #[test]
+    fn test_gamepad() {
+        use crate::agnostic_interface::{apply_deadzone, GamepadStick};
+
+        let (mut gi, controller) = HeadlessGame::new_boxed(800, 600);
+        assert_eq!(gi.get_gamepads(), Ok(vec![]));
+        controller.connect_gamepad(1);
+        controller.press_gamepad_button(0, GamepadButton::FaceDown);
+        controller.set_gamepad_axis(1, GamepadAxis::LeftY, 1.0);
+        assert_eq!(gi.get_gamepads(), Ok(vec![0, 1]));
+        assert_eq!(
+            gi.is_gamepad_button_pressed(0, GamepadButton::FaceDown),
+            Ok(true)
+        );
+        assert_eq!(
+            gi.is_gamepad_button_pressed(1, GamepadButton::FaceDown),
+            Ok(false)
+        );
+        assert_eq!(
+            gi.get_gamepad_stick(1, GamepadStick::Left, 0.2)
+                .map(|v| (v.x, v.y)),
+            Ok((0.0, 1.0))
+        );
+
+        gi.begin_drawing().unwrap();
+        gi.end_drawing().unwrap();
+        assert_eq!(
+            gi.is_gamepad_button_pressed(0, GamepadButton::FaceDown),
+            Ok(false)
+        );
+        assert_eq!(
+            gi.is_gamepad_button_down(0, GamepadButton::FaceDown),
+            Ok(true)
+        );
+
+        assert_eq!(
+            apply_deadzone(Vector::new(0.1, 0.1), 0.2),
+            Vector::new(0.0, 0.0)
+        );
+        let v = apply_deadzone(Vector::new(0.6, 0.0), 0.2);
+        assert!((v.x - 0.5).abs() < 0.0001 && v.y == 0.0);
+    }
+
+    #[test]
+    fn test_camera_to_world() {
+        let (mut gi, controller) = HeadlessGame::new_boxed(800, 600);
+        gi.set_camera_xy(100.0, -50.0).unwrap();
+        assert_eq!(controller.get_camera_xy(), (100.0, -50.0));
+        assert_eq!(gi.xy_to_world(1.0, 2.0), Ok(Vector::new(101.0, -48.0)));
+
+        let mut camera = gi.get_camera().unwrap();
+        camera.set_zoom(2.0).unwrap();
+        assert!(camera.set_zoom(0.0).is_err());
+        gi.set_camera(camera.as_ref()).unwrap();
+        assert_eq!(controller.get_camera_zoom(), 2.0);
+        assert_eq!(gi.xy_to_world(10.0, 20.0), Ok(Vector::new(105.0, -40.0)));
+        assert_eq!(
+            gi.world_to_screen(Vector::new(105.0, -40.0)),
+            Ok(Vector::new(10.0, 20.0))
+        );
+
+        // a quarter turn around the screen center
+        let mut camera = gi.get_default_camera().unwrap();
+        camera.set_rotation(std::f32::consts::FRAC_PI_2).unwrap();
+        assert!(camera.set_rotation(f32::NAN).is_err());
+        gi.set_camera(camera.as_ref()).unwrap();
+        assert_eq!(
+            controller.get_camera_rotation(),
+            std::f32::consts::FRAC_PI_2
+        );
+        let world = gi.xy_to_world(500.0, 300.0).unwrap();
+        assert!((world.x - 400.0).abs() < 1e-3 && (world.y - 400.0).abs() < 1e-3);
+        let screen = gi.world_to_screen(world).unwrap();
+        assert!((screen.x - 500.0).abs() < 1e-3 && (screen.y - 300.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_viewport() {
+        let (mut gi, controller) = HeadlessGame::new_boxed(800, 600);
+        controller.set_dimensions(1600.0, 900.0);
+        assert_eq!(gi.get_dimensions(), Ok((800.0, 600.0)));
+        let fit = controller.get_viewport_fit();
+        assert_eq!(fit.scale, 1.5);
+        assert_eq!(fit.offset, Vector::new(200.0, 0.0));
+        controller.click(650.0, 150.0);
+        assert_eq!(gi.get_mouse_xy(), Ok((300.0, 100.0)));
+        assert_eq!(gi.get_mouse_pressed(), Ok(Some((300.0, 100.0))));
+        assert_eq!(
+            gi.get_pointer_events().unwrap()[0],
+            PointerEvent::Moved {
+                pos: Vector::new(300.0, 100.0)
+            }
+        );
+
+        gi.set_viewport(Viewport {
+            virtual_size: Vector::new(800.0, 600.0),
+            mode: ScaleMode::Expand,
+        })
+        .unwrap();
+        assert_eq!(gi.get_dimensions(), Ok((1600.0 / 1.5, 600.0)));
+        assert_eq!(gi.get_mouse_xy(), Ok((650.0 / 1.5, 100.0)));
+        assert!(gi
+            .set_viewport(Viewport {
+                virtual_size: Vector::new(0.0, 600.0),
+                mode: ScaleMode::Letterbox,
+            })
+            .is_err());
+    }
+
+    #[test]
+    fn test_font_fallbacks() {
+        let (mut gi, controller) = HeadlessGame::new_boxed(800, 600);
+        let options = FontOptions::new(32).with_fallback("static/missing.ttf");
+        assert!(gi
+            .load_font(Path::new("static/ClearSans-Regular.ttf"), &options)
+            .is_err());
+        assert!(!controller.is_font_loaded("static/missing.ttf"));
+        assert!(!controller.is_font_loaded("static/ClearSans-Regular.ttf"));
+        assert_eq!(gi.resource_report().fonts, 0);
+
+        let options = FontOptions::new(32)
+            .with_range(FontOptions::CYRILLIC)
+            .with_range('а'..='я')
+            .with_fallback("static/ClearSans-Regular.ttf");
+        assert_eq!(options.codepoints().len(), 95 + 256);
+        gi.load_font(Path::new("static/ClearSans-Regular.ttf"), &options)
+            .unwrap();
+        assert!(controller.is_font_loaded("static/ClearSans-Regular.ttf"));
+    }
+
+    #[test]
+    fn test_load_from_pack() {
+        let mut vfs = Vfs::default();
+        let pack = crate::vfs::write_pack(Path::new("static")).unwrap();
+        vfs.mount("packed", Source::read_pack(&pack).unwrap());
+        let game = HeadlessGame::new(800, 600).with_vfs(vfs);
+        let controller = game.get_controller();
+        let mut gi: Box<dyn GameInterface> = Box::new(game);
+
+        gi.load_image(Path::new("packed/fish.png")).unwrap();
+        assert!(controller.get_image_dimensions("packed/fish.png").is_some());
+        gi.load_font(
+            Path::new("packed/ClearSans-Regular.ttf"),
+            &FontOptions::new(32),
+        )
+        .unwrap();
+        assert!(gi.load_sound(Path::new("packed/missing.mp3")).is_err());
+    }
+
+    #[test]
+    fn test_reload_in_place() {
+        let png = |w: u32, h: u32| {
+            let mut data = PNG_SIGNATURE.to_vec();
+            data.extend(13u32.to_be_bytes());
+            data.extend(b"IHDR");
+            data.extend(w.to_be_bytes());
+            data.extend(h.to_be_bytes());
+            data
+        };
+        let path = std::env::temp_dir().join(format!("ld45_reload_{}.png", std::process::id()));
+        std::fs::write(&path, png(2, 3)).unwrap();
+
+        let (mut gi, _controller) = HeadlessGame::new_boxed(800, 600);
+        let image = gi.load_image(&path).unwrap();
+        assert_eq!((image.get_w(), image.get_h()), (2, 3));
+        std::fs::write(&path, png(4, 5)).unwrap();
+        assert_eq!(gi.reload(&path), Ok(true));
+        assert_eq!((image.get_w(), image.get_h()), (4, 5));
+
+        // a broken file keeps the old image
+        std::fs::write(&path, b"half written").unwrap();
+        assert!(gi.reload(&path).is_err());
+        assert_eq!((image.get_w(), image.get_h()), (4, 5));
+        assert_eq!(gi.reload(Path::new("static/star.png")), Ok(false));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_unload_after_last_handle() {
+        let (mut gi, controller) = HeadlessGame::new_boxed(800, 600);
+        let star = Path::new("static/star.png");
+        let first = gi.load_image(star).unwrap();
+        let second = gi.load_image(star).unwrap();
+        let music = gi.load_music(Path::new("static/music2.mp3")).unwrap();
+        assert_eq!(
+            gi.resource_report(),
+            ResourceReport {
+                images: 1,
+                textures: 1,
+                music: 1,
+                ..ResourceReport::default()
+            }
+        );
+
+        // the second handle still uses the image
+        drop(first);
+        gi.unload_image(star).unwrap();
+        assert!(controller.get_image_dimensions("static/star.png").is_some());
+        drop(second);
+        gi.unload_image(star).unwrap();
+        assert!(controller.get_image_dimensions("static/star.png").is_none());
+        assert!(gi.unload_image(star).is_err());
+
+        drop(music);
+        gi.unload_music(Path::new("static/music2.mp3")).unwrap();
+        assert_eq!(gi.resource_report(), ResourceReport::default());
+    }
+
+    #[test]
+    fn test_paragraph() {
+        let (mut gi, controller) = HeadlessGame::new_boxed(800, 600);
+        let mut font = gi
+            .load_font(
+                Path::new("static/ClearSans-Regular.ttf"),
+                &FontOptions::new(20),
+            )
+            .unwrap();
+        // characters are 10 wide at size 20, so two words fit on a line
+        let style = ParagraphStyle {
+            max_width: 100.0,
+            align: Align::Right,
+            line_spacing: 4.0,
+        };
+        let text = "one two three four five";
+        assert_eq!(font.measure(text, 20), Ok(Vector::new(230.0, 20.0)));
+        assert_eq!(
+            font.measure_paragraph(text, 20, style),
+            Ok(Vector::new(100.0, 68.0))
+        );
+        assert_eq!(
+            font.measure_paragraph(text, 20, ParagraphStyle::new(f32::INFINITY)),
+            Ok(Vector::new(230.0, 20.0))
+        );
+
+        gi.begin_drawing().unwrap();
+        assert_eq!(
+            font.draw_paragraph(text, 20, 0.0, 0.0, style, Color::WHITE),
+            Ok(Vector::new(100.0, 68.0))
+        );
+        gi.end_drawing().unwrap();
+        assert_eq!(controller.get_draw_calls(), 3);
+    }
+
+    #[test]
+    fn test_pointer_events() {
+        let (mut gi, controller) = HeadlessGame::new_boxed(800, 600);
+        controller.set_mouse_xy(5.0, 6.0);
+        controller.press_mouse_button(MouseButton::Right);
+        controller.scroll_wheel(-1.0);
+        let pos = Vector::new(5.0, 6.0);
+        assert_eq!(
+            gi.get_pointer_events(),
+            Ok(vec![
+                PointerEvent::Moved { pos },
+                PointerEvent::Pressed {
+                    button: MouseButton::Right,
+                    pos
+                },
+                PointerEvent::Wheel { delta: -1.0 },
+            ])
+        );
+        assert_eq!(gi.get_mouse_wheel(), Ok(-1.0));
+        assert_eq!(gi.get_mouse_button_down(MouseButton::Right), Ok(true));
+        assert_eq!(gi.get_mouse_down(), Ok(None));
+
+        gi.begin_drawing().unwrap();
+        gi.end_drawing().unwrap();
+        assert_eq!(gi.get_pointer_events(), Ok(vec![]));
+        assert_eq!(gi.get_mouse_wheel(), Ok(0.0));
+        assert_eq!(gi.get_mouse_button_down(MouseButton::Right), Ok(true));
+    }
+}
@@ -9,22 +9,72 @@ pub mod ffi {
 }
 
 use std::{
-    cell::RefCell,
-    collections::HashMap,
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
     ffi::CString,
     os::raw::{c_char, c_int},
     path::{Path, PathBuf},
     rc::Rc,
-    str::FromStr,
 };
 
 use crate::faux_quicksilver::{Transform, Vector};
 
+use crate::text;
+use crate::vfs::Vfs;
+
 use super::{
-    CameraInterface, FontInterface, GameInterface, ImageInterface, MusicInterface, ShaderInterface,
-    SoundInterface,
+    CameraView, FontInterface, FontOptions, GameInterface, GamepadAxis, GamepadButton,
+    ImageInterface, Key, MouseButton, MusicInterface, PointerEvent, ResourceReport, ScaleMode,
+    ShaderInterface, SoundInterface, TouchPoint, Viewport, ViewportFit,
 };
 
+// raylib keeps track of this many gamepads.
+const MAX_GAMEPADS: usize = 4;
+const MOUSE_BUTTONS: [MouseButton; 3] =
+    [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+// raylib tells file formats apart by their extension, e.g. ".png".
+fn file_type(path: &Path) -> Result<CString, String> {
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    CString::new(format!(".{extension}")).map_err(|e| format!("Failed to load {path:?}: {e}"))
+}
+
+fn path_to_str(path: &Path) -> Result<&str, String> {
+    path.to_str()
+        .ok_or_else(|| format!("Failed to convert \"{path:?}\" to str!"))
+}
+
+/// Takes `key` out of `map` if no handle shares it anymore.
+fn take_unused<T>(map: &mut HashMap<String, Rc<T>>, key: &str) -> Result<Option<Rc<T>>, String> {
+    let value = map
+        .get(key)
+        .ok_or_else(|| format!("\"{key}\" is not loaded!"))?;
+    if Rc::strong_count(value) > 1 {
+        return Ok(None);
+    }
+    Ok(map.remove(key))
+}
+
+thread_local! {
+    // raylib has a single window, so it is fitted the same everywhere.
+    static VIEWPORT: Cell<Viewport> = const {
+        Cell::new(Viewport {
+            virtual_size: Vector { x: 800.0, y: 600.0 },
+            mode: ScaleMode::Letterbox,
+        })
+    };
+}
+
+fn viewport_fit() -> ViewportFit {
+    let window_size =
+        unsafe { Vector::new(ffi::GetScreenWidth() as f32, ffi::GetScreenHeight() as f32) };
+    VIEWPORT.with(|viewport| viewport.get().fit(window_size))
+}
+
+fn to_virtual(x: c_int, y: c_int) -> Vector {
+    viewport_fit().to_virtual(Vector::new(x as f32, y as f32))
+}
+
 fn fqcolor_to_color(c: crate::faux_quicksilver::Color) -> ffi::Color {
     ffi::Color {
         r: c.r,
@@ -43,6 +93,47 @@ fn fqrect_to_rect(r: crate::faux_quicksilver::Rectangle) -> ffi::Rectangle {
     }
 }
 
+fn mouse_button_to_c_int(button: MouseButton) -> c_int {
+    match button {
+        MouseButton::Left => 0,
+        MouseButton::Right => 1,
+        MouseButton::Middle => 2,
+    }
+}
+
+fn gamepad_button_to_c_int(button: GamepadButton) -> c_int {
+    match button {
+        GamepadButton::DpadUp => 1,
+        GamepadButton::DpadRight => 2,
+        GamepadButton::DpadDown => 3,
+        GamepadButton::DpadLeft => 4,
+        GamepadButton::FaceUp => 5,
+        GamepadButton::FaceRight => 6,
+        GamepadButton::FaceDown => 7,
+        GamepadButton::FaceLeft => 8,
+        GamepadButton::LeftBumper => 9,
+        GamepadButton::LeftTrigger => 10,
+        GamepadButton::RightBumper => 11,
+        GamepadButton::RightTrigger => 12,
+        GamepadButton::Select => 13,
+        GamepadButton::Guide => 14,
+        GamepadButton::Start => 15,
+        GamepadButton::LeftThumb => 16,
+        GamepadButton::RightThumb => 17,
+    }
+}
+
+fn gamepad_axis_to_c_int(axis: GamepadAxis) -> c_int {
+    match axis {
+        GamepadAxis::LeftX => 0,
+        GamepadAxis::LeftY => 1,
+        GamepadAxis::RightX => 2,
+        GamepadAxis::RightY => 3,
+        GamepadAxis::LeftTrigger => 4,
+        GamepadAxis::RightTrigger => 5,
+    }
+}
+
 fn fqvector_to_vector2(v: crate::faux_quicksilver::Vector) -> ffi::Vector2 {
     ffi::Vector2 { x: v.x, y: v.y }
 }
@@ -50,9 +141,40 @@ fn fqvector_to_vector2(v: crate::faux_quicksilver::Vector) -> ffi::Vector2 {
 #[derive(Clone, Debug)]
 pub struct RaylibShader {
     shader: ffi::Shader,
+    // Kept to recompile the shader when either file changes.
+    vs: PathBuf,
+    fs: PathBuf,
+}
+
+fn compile_shader(vfs: &Vfs, vs: &Path, fs: &Path) -> Result<ffi::Shader, String> {
+    let code = |path: &Path| {
+        CString::new(vfs.read_to_string(path)?).map_err(|e| format!("Failed to load {path:?}: {e}"))
+    };
+    let (vs_code, fs_code) = (code(vs)?, code(fs)?);
+    unsafe {
+        let shader = ffi::LoadShaderFromMemory(vs_code.as_ptr(), fs_code.as_ptr());
+        // raylib logs the compile errors and falls back to its default shader
+        if shader.id == ffi::rlGetShaderIdDefault() {
+            return Err(format!("Failed to compile the shader of {vs:?} and {fs:?}"));
+        }
+        Ok(shader)
+    }
 }
 
 impl RaylibShader {
+    fn uses(&self, path: &Path) -> bool {
+        self.vs == path || self.fs == path
+    }
+
+    /// Uniform locations are looked up on every use, so they stay valid.
+    fn reload(&mut self, vfs: &Vfs) -> Result<(), String> {
+        let shader = compile_shader(vfs, &self.vs, &self.fs)?;
+        unsafe {
+            ffi::UnloadShader(std::mem::replace(&mut self.shader, shader));
+        }
+        Ok(())
+    }
+
     pub fn get_shader_id(&self) -> ::std::os::raw::c_uint {
         self.shader.id as ::std::os::raw::c_uint
     }
@@ -99,15 +221,39 @@ impl RaylibShader {
         Ok(())
     }
 
-    fn set_camera_uniform(&mut self, camera: Vector) -> Result<(), String> {
-        let camera_cstr = CString::new("camera")
-            .map_err(|_| String::from("Failed to create \"camera\" CString!"))?;
-        let uniform_location = unsafe { ffi::GetShaderLocation(self.shader, camera_cstr.as_ptr()) };
-        let vec2: ffi::Vector2 = camera.into();
+    fn set_camera_uniform(&mut self, view: CameraView) -> Result<(), String> {
+        self.set_float_uniform("zoom", view.zoom)?;
+        self.set_float_uniform("rotation", view.rotation)?;
+        self.set_vec2_uniform("camera", view.pos)?;
+        self.set_vec2_uniform("center", screen_center())
+    }
+}
+
+impl RaylibShader {
+    fn set_float_uniform(&mut self, name: &str, value: f32) -> Result<(), String> {
+        let cstr =
+            CString::new(name).map_err(|_| format!("Failed to create \"{name}\" CString!"))?;
+        unsafe {
+            let location = ffi::GetShaderLocation(self.shader, cstr.as_ptr());
+            ffi::SetShaderValue(
+                self.shader,
+                location,
+                &value as *const f32 as *const std::ffi::c_void,
+                ffi::rlShaderUniformDataType_RL_SHADER_UNIFORM_FLOAT as std::ffi::c_int,
+            );
+        }
+        Ok(())
+    }
+
+    fn set_vec2_uniform(&mut self, name: &str, value: Vector) -> Result<(), String> {
+        let cstr =
+            CString::new(name).map_err(|_| format!("Failed to create \"{name}\" CString!"))?;
+        let vec2: ffi::Vector2 = value.into();
         unsafe {
+            let location = ffi::GetShaderLocation(self.shader, cstr.as_ptr());
             ffi::SetShaderValueV(
                 self.shader,
-                uniform_location,
+                location,
                 &vec2 as *const ffi::Vector2 as *const std::ffi::c_void,
                 ffi::rlShaderUniformDataType_RL_SHADER_UNIFORM_VEC2 as std::ffi::c_int,
                 1,
@@ -117,6 +263,10 @@ impl RaylibShader {
     }
 }
 
+fn screen_center() -> Vector {
+    viewport_fit().size * 0.5
+}
+
 #[derive(Clone, Debug)]
 struct RaylibShaderHandler {
     shader: Rc<RefCell<RaylibShader>>,
@@ -139,32 +289,20 @@ impl ShaderInterface for RaylibShaderHandler {
         self.shader.borrow_mut().set_origin_uniform(origin)
     }
 
-    fn set_camera_uniform(&mut self, camera: Vector) -> Result<(), String> {
-        self.shader.borrow_mut().set_camera_uniform(camera)
+    fn set_camera_uniform(&mut self, view: CameraView) -> Result<(), String> {
+        self.shader.borrow_mut().set_camera_uniform(view)
     }
 }
 
 impl RaylibShaderHandler {
-    pub fn load_shader(vs: &Path, fs: &Path) -> Result<Self, String> {
-        unsafe {
-            let vs_cstr: CString = CString::from_vec_unchecked(
-                vs.to_str()
-                    .ok_or_else(|| format!("Cannot convert path \"{vs:?}\" to str!"))?
-                    .as_bytes()
-                    .to_owned(),
-            );
-            let fs_cstr: CString = CString::from_vec_unchecked(
-                fs.to_str()
-                    .ok_or_else(|| format!("Cannot convert path \"{fs:?}\" to str!"))?
-                    .as_bytes()
-                    .to_owned(),
-            );
-            Ok(Self {
-                shader: Rc::new(RefCell::new(RaylibShader {
-                    shader: ffi::LoadShader(vs_cstr.as_ptr(), fs_cstr.as_ptr()),
-                })),
-            })
-        }
+    pub fn load_shader(vfs: &Vfs, vs: &Path, fs: &Path) -> Result<Self, String> {
+        Ok(Self {
+            shader: Rc::new(RefCell::new(RaylibShader {
+                shader: compile_shader(vfs, vs, fs)?,
+                vs: vs.to_owned(),
+                fs: fs.to_owned(),
+            })),
+        })
     }
 }
 
@@ -174,12 +312,46 @@ struct RaylibImage {
     texture: Option<ffi::Texture>,
 }
 
+fn load_image_file(vfs: &Vfs, path: &Path) -> Result<ffi::Image, String> {
+    let data = vfs.read(path)?;
+    let image = unsafe {
+        ffi::LoadImageFromMemory(
+            file_type(path)?.as_ptr(),
+            data.as_ptr(),
+            data.len() as c_int,
+        )
+    };
+    if image.data.is_null() {
+        return Err(format!("Failed to load image {path:?}"));
+    }
+    Ok(image)
+}
+
+impl RaylibImage {
+    /// The texture is uploaded again on the next draw.
+    fn reload(&mut self, vfs: &Vfs, path: &Path) -> Result<(), String> {
+        let image = load_image_file(vfs, path)?;
+        self.unload();
+        self.image = image;
+        Ok(())
+    }
+
+    fn unload(&mut self) {
+        unsafe {
+            if let Some(texture) = self.texture.take() {
+                ffi::UnloadTexture(texture);
+            }
+            ffi::UnloadImage(self.image);
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct RaylibImageHandler {
     image: Rc<RefCell<RaylibImage>>,
     tr_or_cam_shader: Rc<RefCell<Option<RaylibShaderHandler>>>,
     cam_shader: Rc<RefCell<Option<RaylibShaderHandler>>>,
-    camera: Rc<RefCell<Camera>>,
+    camera: Rc<RefCell<CameraView>>,
 }
 
 impl RaylibImageHandler {
@@ -203,7 +375,7 @@ impl ImageInterface for RaylibImageHandler {
     ) -> Result<(), String> {
         self.image_to_texture()?;
         if let Some(cam_shader) = self.cam_shader.borrow_mut().as_mut() {
-            cam_shader.set_camera_uniform(self.camera.borrow().pos)?;
+            cam_shader.set_camera_uniform(*self.camera.borrow())?;
             cam_shader.begin_draw_shader()?;
             unsafe {
                 ffi::DrawTexture(
@@ -245,7 +417,7 @@ impl ImageInterface for RaylibImageHandler {
     ) -> Result<(), String> {
         self.image_to_texture()?;
         if let Some(cam_shader) = self.cam_shader.borrow_mut().as_mut() {
-            cam_shader.set_camera_uniform(self.camera.borrow().pos)?;
+            cam_shader.set_camera_uniform(*self.camera.borrow())?;
             cam_shader.begin_draw_shader()?;
             unsafe {
                 ffi::DrawTexturePro(
@@ -295,7 +467,7 @@ impl ImageInterface for RaylibImageHandler {
         if let Some(shader) = self.tr_or_cam_shader.borrow_mut().as_mut() {
             shader.set_origin_uniform(origin)?;
             shader.set_transform_uniform(transform)?;
-            shader.set_camera_uniform(self.camera.borrow().pos)?;
+            shader.set_camera_uniform(*self.camera.borrow())?;
             shader.begin_draw_shader()?;
             unsafe {
                 ffi::DrawTexture(
@@ -312,7 +484,7 @@ impl ImageInterface for RaylibImageHandler {
             }
             shader.end_draw_shader()?;
         } else if let Some(cam_shader) = self.cam_shader.borrow_mut().as_mut() {
-            cam_shader.set_camera_uniform(self.camera.borrow().pos)?;
+            cam_shader.set_camera_uniform(*self.camera.borrow())?;
             cam_shader.begin_draw_shader()?;
             unsafe {
                 ffi::DrawTexture(
@@ -358,7 +530,7 @@ impl ImageInterface for RaylibImageHandler {
         if let Some(shader) = self.tr_or_cam_shader.borrow_mut().as_mut() {
             shader.set_origin_uniform(origin)?;
             shader.set_transform_uniform(transform)?;
-            shader.set_camera_uniform(self.camera.borrow().pos)?;
+            shader.set_camera_uniform(*self.camera.borrow())?;
             shader.begin_draw_shader()?;
             unsafe {
                 ffi::DrawTexturePro(
@@ -377,7 +549,7 @@ impl ImageInterface for RaylibImageHandler {
             }
             shader.end_draw_shader()?;
         } else if let Some(cam_shader) = self.cam_shader.borrow_mut().as_mut() {
-            cam_shader.set_camera_uniform(self.camera.borrow().pos)?;
+            cam_shader.set_camera_uniform(*self.camera.borrow())?;
             cam_shader.begin_draw_shader()?;
             unsafe {
                 ffi::DrawTexturePro(
@@ -435,12 +607,95 @@ impl ImageInterface for RaylibImageHandler {
 
 #[derive(Clone, Debug)]
 struct RaylibFont {
-    font: ffi::Font,
+    // Replaced when the file is reloaded.
+    font: Cell<ffi::Font>,
+    // The codepoints `font` has glyphs for, looked up for every character.
+    glyphs: RefCell<HashSet<c_int>>,
+    path: PathBuf,
+    size: u32,
+    codepoints: Vec<c_int>,
+}
+
+fn load_font_file(
+    vfs: &Vfs,
+    path: &Path,
+    size: u32,
+    codepoints: &[c_int],
+) -> Result<ffi::Font, String> {
+    let file_type = file_type(path)?;
+    let data = vfs.read(path)?;
+    let mut codepoints = codepoints.to_vec();
+    unsafe {
+        let font = ffi::LoadFontFromMemory(
+            file_type.as_ptr(),
+            data.as_ptr(),
+            data.len() as c_int,
+            size as c_int,
+            codepoints.as_mut_ptr(),
+            codepoints.len() as c_int,
+        );
+        if font.glyphs.is_null() {
+            return Err(format!("Failed to load font {path:?}"));
+        }
+        ffi::SetTextureFilter(
+            font.texture,
+            ffi::TextureFilter_TEXTURE_FILTER_BILINEAR as ::std::os::raw::c_int,
+        );
+        Ok(font)
+    }
+}
+
+fn glyph_codepoints(font: ffi::Font) -> HashSet<c_int> {
+    if font.glyphs.is_null() {
+        return HashSet::new();
+    }
+    let glyphs = unsafe { std::slice::from_raw_parts(font.glyphs, font.glyphCount as usize) };
+    glyphs.iter().map(|glyph| glyph.value).collect()
+}
+
+impl RaylibFont {
+    fn has_glyph(&self, c: char) -> bool {
+        self.glyphs.borrow().contains(&(c as c_int))
+    }
+
+    fn reload(&self, vfs: &Vfs) -> Result<(), String> {
+        let font = load_font_file(vfs, &self.path, self.size, &self.codepoints)?;
+        self.glyphs.replace(glyph_codepoints(font));
+        unsafe {
+            ffi::UnloadFont(self.font.replace(font));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug)]
 struct RaylibFontHandler {
     font: Rc<RaylibFont>,
+    // Only used for glyphs that `font` does not have.
+    fallbacks: Vec<Rc<RaylibFont>>,
+}
+
+fn measure_text(font: ffi::Font, s: &str, size: u32) -> Result<ffi::Vector2, String> {
+    let cstring = CString::new(s).map_err(|e| format!("Failed to measure {s:?}: {e}"))?;
+    Ok(unsafe { ffi::MeasureTextEx(font, cstring.as_ptr(), size as f32, (size / 10) as f32) })
+}
+
+impl RaylibFontHandler {
+    /// Splits `s` into runs drawn with the same font.
+    fn runs<'a>(&self, s: &'a str) -> Vec<(ffi::Font, &'a str)> {
+        if self.fallbacks.is_empty() {
+            return vec![(self.font.font.get(), s)];
+        }
+        let fonts: Vec<&Rc<RaylibFont>> =
+            std::iter::once(&self.font).chain(&self.fallbacks).collect();
+        text::split_runs(s, &mut |c| {
+            // without any font having it, the main font draws its placeholder
+            fonts.iter().position(|font| font.has_glyph(c)).unwrap_or(0)
+        })
+        .into_iter()
+        .map(|(i, run)| (fonts[i].font.get(), run))
+        .collect()
+    }
 }
 
 impl FontInterface for RaylibFontHandler {
@@ -452,19 +707,38 @@ impl FontInterface for RaylibFontHandler {
         y: f32,
         color: crate::faux_quicksilver::Color,
     ) -> Result<(), String> {
-        unsafe {
-            let cstring = CString::from_vec_unchecked(s.as_bytes().into());
-            ffi::DrawTextEx(
-                self.font.font,
-                cstring.as_ptr(),
-                ffi::Vector2 { x, y },
-                size as f32,
-                (size / 10) as f32,
-                fqcolor_to_color(color),
-            );
+        let spacing = (size / 10) as f32;
+        let mut x = x;
+        for (font, run) in self.runs(s) {
+            let cstring = CString::new(run).map_err(|e| format!("Failed to draw {s:?}: {e}"))?;
+            unsafe {
+                ffi::DrawTextEx(
+                    font,
+                    cstring.as_ptr(),
+                    ffi::Vector2 { x, y },
+                    size as f32,
+                    spacing,
+                    fqcolor_to_color(color),
+                );
+            }
+            x += measure_text(font, run, size)?.x + spacing;
         }
         Ok(())
     }
+
+    fn measure(&self, s: &str, size: u32) -> Result<Vector, String> {
+        let spacing = (size / 10) as f32;
+        let mut total = Vector::new(0.0, 0.0);
+        for (i, (font, run)) in self.runs(s).into_iter().enumerate() {
+            let v = measure_text(font, run, size)?;
+            if i > 0 {
+                total.x += spacing;
+            }
+            total.x += v.x;
+            total.y = total.y.max(v.y);
+        }
+        Ok(total)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -489,9 +763,11 @@ impl SoundInterface for RaylibSoundHandler {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 struct RaylibMusic {
     music: ffi::Music,
+    // Streamed from while the music plays.
+    _data: Vec<u8>,
 }
 
 impl RaylibMusic {
@@ -538,6 +814,15 @@ impl MusicInterface for RaylibMusicHandler {
         Ok(())
     }
 
+    fn set_volume(&mut self, vol: f32) -> Result<(), String> {
+        unsafe {
+            if ffi::IsAudioDeviceReady() {
+                ffi::SetMusicVolume(self.music.borrow().music, vol);
+            }
+        }
+        Ok(())
+    }
+
     fn set_loop(&mut self, loop_enable: bool) -> Result<(), String> {
         self.music.borrow_mut().music.looping = loop_enable;
         Ok(())
@@ -549,38 +834,16 @@ impl MusicInterface for RaylibMusicHandler {
     }
 }
 
-#[derive(Debug, Clone)]
-struct Camera {
-    pos: Vector,
-}
-
-impl Default for Camera {
-    fn default() -> Self {
-        Self {
-            pos: Vector { x: 0.0, y: 0.0 },
-        }
-    }
-}
-
-impl CameraInterface for Camera {
-    fn get_view_xy(&self) -> Result<(f32, f32), String> {
-        Ok((self.pos.x, self.pos.y))
-    }
-
-    fn set_view_xy(&mut self, x: f32, y: f32) -> Result<(), String> {
-        self.pos.x = x;
-        self.pos.y = y;
-        Ok(())
-    }
-}
-
 pub struct RaylibGame {
     images: HashMap<String, Rc<RefCell<RaylibImage>>>,
     fonts: HashMap<String, Rc<RaylibFont>>,
     sounds: HashMap<String, Rc<RaylibSound>>,
     music: HashMap<String, Rc<RefCell<RaylibMusic>>>,
     shaders: HashMap<String, Rc<RefCell<RaylibShader>>>,
-    camera: Rc<RefCell<Camera>>,
+    camera: Rc<RefCell<CameraView>>,
+    // Whether the current frame clips to the letterbox.
+    scissor: bool,
+    vfs: Vfs,
 }
 
 impl RaylibGame {
@@ -606,15 +869,30 @@ impl RaylibGame {
     #[cfg(target_arch = "wasm32")]
     fn audio_setup() {}
 
-    pub fn new_boxed(width: u32, height: u32) -> Box<dyn GameInterface> {
+    pub fn new_boxed(
+        width: u32,
+        height: u32,
+        asset_root: &Path,
+        vfs: Vfs,
+    ) -> Box<dyn GameInterface> {
         unsafe {
             let string = "One and All LD45\0";
+            ffi::SetConfigFlags(
+                ffi::ConfigFlags_FLAG_WINDOW_RESIZABLE | ffi::ConfigFlags_FLAG_WINDOW_HIGHDPI,
+            );
             ffi::InitWindow(
                 width as c_int,
                 height as c_int,
                 string.as_ptr() as *const c_char,
             );
+            ffi::SetWindowMinSize(width as c_int / 4, height as c_int / 4);
         }
+        VIEWPORT.with(|viewport| {
+            viewport.set(Viewport {
+                virtual_size: Vector::new(width as f32, height as f32),
+                mode: ScaleMode::Letterbox,
+            })
+        });
 
         Self::native_setup();
         Self::audio_setup();
@@ -625,16 +903,50 @@ impl RaylibGame {
             sounds: HashMap::new(),
             music: HashMap::new(),
             shaders: HashMap::new(),
-            camera: Rc::new(RefCell::new(Camera::default())),
+            camera: Rc::new(RefCell::new(CameraView::default())),
+            scissor: false,
+            vfs,
         };
-        if let Err(e) = self_unboxed.load_transform_origin_shader() {
+        if let Err(e) = self_unboxed.load_transform_origin_shader(asset_root) {
             println!("WARNING: {e:?}");
         }
-        if let Err(e) = self_unboxed.load_camera_shader() {
+        if let Err(e) = self_unboxed.load_camera_shader(asset_root) {
             println!("WARNING: {e:?}");
         }
         Box::new(self_unboxed)
     }
+
+    fn load_raylib_font(
+        &mut self,
+        path: &Path,
+        options: &FontOptions,
+    ) -> Result<Rc<RaylibFont>, String> {
+        let key = font_key(path, options)?;
+        if let Some(font) = self.fonts.get(&key) {
+            return Ok(font.clone());
+        }
+        let codepoints = options.codepoints();
+        let loaded = load_font_file(&self.vfs, path, options.size, &codepoints)?;
+        let font = Rc::new(RaylibFont {
+            font: Cell::new(loaded),
+            glyphs: RefCell::new(glyph_codepoints(loaded)),
+            path: path.to_owned(),
+            size: options.size,
+            codepoints,
+        });
+        self.fonts.insert(key, font.clone());
+        Ok(font)
+    }
+}
+
+// The same file may be loaded at several sizes or with other glyphs.
+fn font_key(path: &Path, options: &FontOptions) -> Result<String, String> {
+    Ok(format!(
+        "{}:{}:{:?}",
+        path_to_str(path)?,
+        options.size,
+        options.ranges
+    ))
 }
 
 impl Drop for RaylibGame {
@@ -644,13 +956,10 @@ impl Drop for RaylibGame {
                 ffi::UnloadShader(shader.borrow().shader);
             }
             for image in self.images.values() {
-                if let Some(texture) = image.borrow_mut().texture.take() {
-                    ffi::UnloadTexture(texture);
-                }
-                ffi::UnloadImage(image.borrow().image);
+                image.borrow_mut().unload();
             }
             for font in self.fonts.values() {
-                ffi::UnloadFont(font.font);
+                ffi::UnloadFont(font.font.get());
             }
             for sound in self.sounds.values() {
                 ffi::UnloadSound(sound.sound);
@@ -665,17 +974,39 @@ impl Drop for RaylibGame {
 
 impl GameInterface for RaylibGame {
     fn get_dimensions(&self) -> Result<(f32, f32), String> {
-        unsafe { Ok((ffi::GetScreenWidth() as f32, ffi::GetScreenHeight() as f32)) }
+        let size = viewport_fit().size;
+        Ok((size.x, size.y))
+    }
+
+    fn set_viewport(&mut self, viewport: Viewport) -> Result<(), String> {
+        if viewport.virtual_size.x <= 0.0 || viewport.virtual_size.y <= 0.0 {
+            return Err(format!("Invalid virtual size {:?}!", viewport.virtual_size));
+        }
+        VIEWPORT.with(|v| v.set(viewport));
+        Ok(())
+    }
+
+    fn is_key_pressed(&mut self, key: Key) -> Result<bool, String> {
+        unsafe { Ok(ffi::IsKeyPressed(key.code() as c_int)) }
     }
 
-    fn get_key_pressed(&mut self, key: char) -> Result<bool, String> {
-        unsafe { Ok(ffi::IsKeyPressed(key.to_ascii_uppercase() as c_int)) }
+    fn is_key_pressed_repeat(&mut self, key: Key) -> Result<bool, String> {
+        unsafe { Ok(ffi::IsKeyPressedRepeat(key.code() as c_int)) }
+    }
+
+    fn is_key_down(&mut self, key: Key) -> Result<bool, String> {
+        unsafe { Ok(ffi::IsKeyDown(key.code() as c_int)) }
+    }
+
+    fn is_key_released(&mut self, key: Key) -> Result<bool, String> {
+        unsafe { Ok(ffi::IsKeyReleased(key.code() as c_int)) }
     }
 
     fn get_mouse_pressed(&mut self) -> Result<Option<(f32, f32)>, String> {
         unsafe {
             if ffi::IsMouseButtonPressed(0) {
-                Ok(Some((ffi::GetTouchX() as f32, ffi::GetTouchY() as f32)))
+                let pos = to_virtual(ffi::GetTouchX(), ffi::GetTouchY());
+                Ok(Some((pos.x, pos.y)))
             } else {
                 Ok(None)
             }
@@ -695,30 +1026,187 @@ impl GameInterface for RaylibGame {
     fn get_mouse_down(&mut self) -> Result<Option<(f32, f32)>, String> {
         unsafe {
             if ffi::IsMouseButtonDown(0) {
-                Ok(Some((ffi::GetTouchX() as f32, ffi::GetTouchY() as f32)))
+                let pos = to_virtual(ffi::GetTouchX(), ffi::GetTouchY());
+                Ok(Some((pos.x, pos.y)))
             } else {
                 Ok(None)
             }
         }
     }
 
-    fn get_mouse_xy(&self) -> Result<(f32, f32), String> {
-        unsafe { Ok((ffi::GetTouchX() as f32, ffi::GetTouchY() as f32)) }
+    fn get_mouse_button_pressed(&mut self, button: MouseButton) -> Result<bool, String> {
+        unsafe { Ok(ffi::IsMouseButtonPressed(mouse_button_to_c_int(button))) }
     }
 
-    fn get_mouse_xy_vec(&self) -> Result<crate::faux_quicksilver::Vector, String> {
+    fn get_mouse_button_released(&mut self, button: MouseButton) -> Result<bool, String> {
+        unsafe { Ok(ffi::IsMouseButtonReleased(mouse_button_to_c_int(button))) }
+    }
+
+    fn get_gamepads(&mut self) -> Result<Vec<usize>, String> {
         unsafe {
-            Ok(crate::faux_quicksilver::Vector {
-                x: ffi::GetTouchX() as f32,
-                y: ffi::GetTouchY() as f32,
-            })
+            Ok((0..MAX_GAMEPADS)
+                .filter(|pad| ffi::IsGamepadAvailable(*pad as c_int))
+                .collect())
         }
     }
 
+    fn is_gamepad_button_pressed(
+        &mut self,
+        pad: usize,
+        button: GamepadButton,
+    ) -> Result<bool, String> {
+        unsafe {
+            Ok(ffi::IsGamepadButtonPressed(
+                pad as c_int,
+                gamepad_button_to_c_int(button),
+            ))
+        }
+    }
+
+    fn is_gamepad_button_down(
+        &mut self,
+        pad: usize,
+        button: GamepadButton,
+    ) -> Result<bool, String> {
+        unsafe {
+            Ok(ffi::IsGamepadButtonDown(
+                pad as c_int,
+                gamepad_button_to_c_int(button),
+            ))
+        }
+    }
+
+    fn is_gamepad_button_released(
+        &mut self,
+        pad: usize,
+        button: GamepadButton,
+    ) -> Result<bool, String> {
+        unsafe {
+            Ok(ffi::IsGamepadButtonReleased(
+                pad as c_int,
+                gamepad_button_to_c_int(button),
+            ))
+        }
+    }
+
+    fn get_gamepad_axis(&mut self, pad: usize, axis: GamepadAxis) -> Result<f32, String> {
+        unsafe {
+            Ok(ffi::GetGamepadAxisMovement(
+                pad as c_int,
+                gamepad_axis_to_c_int(axis),
+            ))
+        }
+    }
+
+    fn get_mouse_button_down(&mut self, button: MouseButton) -> Result<bool, String> {
+        unsafe { Ok(ffi::IsMouseButtonDown(mouse_button_to_c_int(button))) }
+    }
+
+    fn get_mouse_wheel(&mut self) -> Result<f32, String> {
+        unsafe { Ok(ffi::GetMouseWheelMove()) }
+    }
+
+    // raylib only keeps the state of the current frame, so events are
+    // rebuilt from it and come in a fixed order.
+    fn get_pointer_events(&mut self) -> Result<Vec<PointerEvent>, String> {
+        let mut events = Vec::new();
+        let pos = self.get_mouse_xy_vec()?;
+        let delta = unsafe { ffi::GetMouseDelta() };
+        if delta.x != 0.0 || delta.y != 0.0 {
+            events.push(PointerEvent::Moved { pos });
+        }
+        for button in MOUSE_BUTTONS {
+            if self.get_mouse_button_pressed(button)? {
+                events.push(PointerEvent::Pressed { button, pos });
+            }
+            if self.get_mouse_button_released(button)? {
+                events.push(PointerEvent::Released { button, pos });
+            }
+        }
+        let delta = self.get_mouse_wheel()?;
+        if delta != 0.0 {
+            events.push(PointerEvent::Wheel { delta });
+        }
+        Ok(events)
+    }
+
+    // Desktop raylib reports the left mouse button as a touch point, which
+    // would be handled twice, so touches are only read on the web.
+    #[cfg(target_family = "wasm")]
+    fn get_touch_points(&mut self) -> Result<Vec<TouchPoint>, String> {
+        unsafe {
+            Ok((0..ffi::GetTouchPointCount())
+                .map(|index| {
+                    let pos = ffi::GetTouchPosition(index);
+                    TouchPoint {
+                        id: ffi::GetTouchPointId(index) as u32,
+                        pos: viewport_fit().to_virtual(Vector::new(pos.x, pos.y)),
+                    }
+                })
+                .collect())
+        }
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn get_touch_points(&mut self) -> Result<Vec<TouchPoint>, String> {
+        Ok(Vec::new())
+    }
+
+    fn get_mouse_xy(&self) -> Result<(f32, f32), String> {
+        let pos = unsafe { to_virtual(ffi::GetTouchX(), ffi::GetTouchY()) };
+        Ok((pos.x, pos.y))
+    }
+
+    fn get_mouse_xy_vec(&self) -> Result<crate::faux_quicksilver::Vector, String> {
+        unsafe { Ok(to_virtual(ffi::GetTouchX(), ffi::GetTouchY())) }
+    }
+
     fn get_delta_time(&self) -> f32 {
         unsafe { ffi::GetFrameTime() }
     }
 
+    fn is_audio_ready(&self) -> bool {
+        unsafe { ffi::IsAudioDeviceReady() }
+    }
+
+    fn set_master_volume(&mut self, volume: f32) -> Result<(), String> {
+        unsafe {
+            ffi::SetMasterVolume(volume.clamp(0.0, 1.0));
+        }
+        Ok(())
+    }
+
+    fn set_fullscreen(&mut self, fullscreen: bool) -> Result<(), String> {
+        unsafe {
+            if ffi::IsWindowFullscreen() != fullscreen {
+                ffi::ToggleFullscreen();
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn set_target_fps(&mut self, fps: u32) -> Result<(), String> {
+        unsafe {
+            ffi::SetTargetFPS(fps as c_int);
+        }
+        Ok(())
+    }
+
+    // The browser decides the frame rate.
+    #[cfg(target_arch = "wasm32")]
+    fn set_target_fps(&mut self, _fps: u32) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn set_exit_key(&mut self, key: Option<Key>) -> Result<(), String> {
+        unsafe {
+            // KEY_NULL
+            ffi::SetExitKey(key.map(|key| key.code() as c_int).unwrap_or(0));
+        }
+        Ok(())
+    }
+
     fn clear_window(&mut self, color: crate::faux_quicksilver::Color) -> Result<(), String> {
         unsafe {
             ffi::ClearBackground(fqcolor_to_color(color));
@@ -727,14 +1215,41 @@ impl GameInterface for RaylibGame {
     }
 
     fn begin_drawing(&mut self) -> Result<(), String> {
+        let fit = viewport_fit();
+        self.scissor = VIEWPORT.with(|viewport| viewport.get().mode) == ScaleMode::Letterbox;
         unsafe {
             ffi::BeginDrawing();
+            // also clears the bars, later clears only reach the letterbox
+            ffi::ClearBackground(ffi::Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 255,
+            });
+            ffi::BeginMode2D(ffi::Camera2D {
+                offset: fit.offset.into(),
+                target: ffi::Vector2 { x: 0.0, y: 0.0 },
+                rotation: 0.0,
+                zoom: fit.scale,
+            });
+            if self.scissor {
+                ffi::BeginScissorMode(
+                    fit.offset.x.round() as c_int,
+                    fit.offset.y.round() as c_int,
+                    (fit.size.x * fit.scale).round() as c_int,
+                    (fit.size.y * fit.scale).round() as c_int,
+                );
+            }
         }
         Ok(())
     }
 
     fn end_drawing(&mut self) -> Result<(), String> {
         unsafe {
+            if self.scissor {
+                ffi::EndScissorMode();
+            }
+            ffi::EndMode2D();
             ffi::EndDrawing();
         }
         Ok(())
@@ -748,7 +1263,7 @@ impl GameInterface for RaylibGame {
         if let Some(cam_shader) = self.shaders.get_mut("camera") {
             cam_shader
                 .borrow_mut()
-                .set_camera_uniform(self.camera.borrow().pos)?;
+                .set_camera_uniform(*self.camera.borrow())?;
             cam_shader.borrow().begin_draw_shader()?;
             unsafe {
                 ffi::DrawCircle(
@@ -784,7 +1299,7 @@ impl GameInterface for RaylibGame {
             shader.borrow_mut().set_transform_uniform(transform)?;
             shader
                 .borrow_mut()
-                .set_camera_uniform(self.camera.borrow().pos)?;
+                .set_camera_uniform(*self.camera.borrow())?;
             shader.borrow().begin_draw_shader()?;
             unsafe {
                 ffi::DrawCircle(
@@ -798,7 +1313,7 @@ impl GameInterface for RaylibGame {
         } else if let Some(cam_shader) = self.shaders.get_mut("camera") {
             cam_shader
                 .borrow_mut()
-                .set_camera_uniform(self.camera.borrow().pos)?;
+                .set_camera_uniform(*self.camera.borrow())?;
             cam_shader.borrow().begin_draw_shader()?;
             unsafe {
                 ffi::DrawCircle(
@@ -830,7 +1345,7 @@ impl GameInterface for RaylibGame {
         if let Some(cam_shader) = self.shaders.get_mut("camera") {
             cam_shader
                 .borrow_mut()
-                .set_camera_uniform(self.camera.borrow().pos)?;
+                .set_camera_uniform(*self.camera.borrow())?;
             cam_shader.borrow().begin_draw_shader()?;
             unsafe {
                 ffi::DrawRectangle(
@@ -866,7 +1381,7 @@ impl GameInterface for RaylibGame {
         if let Some(cam_shader) = self.shaders.get_mut("camera") {
             cam_shader
                 .borrow_mut()
-                .set_camera_uniform(self.camera.borrow().pos)?;
+                .set_camera_uniform(*self.camera.borrow())?;
             cam_shader.borrow().begin_draw_shader()?;
             unsafe {
                 ffi::DrawRectanglePro(
@@ -902,7 +1417,7 @@ impl GameInterface for RaylibGame {
             shader.borrow_mut().set_transform_uniform(transform)?;
             shader
                 .borrow_mut()
-                .set_camera_uniform(self.camera.borrow().pos)?;
+                .set_camera_uniform(*self.camera.borrow())?;
             shader.borrow().begin_draw_shader()?;
             unsafe {
                 ffi::DrawRectangle(
@@ -917,7 +1432,7 @@ impl GameInterface for RaylibGame {
         } else if let Some(cam_shader) = self.shaders.get_mut("camera") {
             cam_shader
                 .borrow_mut()
-                .set_camera_uniform(self.camera.borrow().pos)?;
+                .set_camera_uniform(*self.camera.borrow())?;
             cam_shader.borrow().begin_draw_shader()?;
             unsafe {
                 ffi::DrawRectangle(
@@ -947,74 +1462,74 @@ impl GameInterface for RaylibGame {
         &mut self,
         path: &std::path::Path,
     ) -> Result<Box<dyn super::ImageInterface>, String> {
-        unsafe {
-            let path_str = path
-                .to_str()
-                .ok_or_else(|| format!("Failed to convert \"{path:?}\" to str!"))?;
-            let path_buf: Vec<u8> = path_str.as_bytes().into();
-            let cstring: CString = CString::from_vec_unchecked(path_buf);
-            let image = ffi::LoadImage(cstring.as_ptr());
-            let tr_or_cam_shader: Option<RaylibShaderHandler> = self
-                .shaders
-                .get("transform_origin")
+        let path_str = path_to_str(path)?;
+        let image = match self.images.get(path_str) {
+            Some(image) => image.clone(),
+            None => {
+                let image = Rc::new(RefCell::new(RaylibImage {
+                    image: load_image_file(&self.vfs, path)?,
+                    texture: None,
+                }));
+                self.images.insert(path_str.to_owned(), image.clone());
+                image
+            }
+        };
+        let tr_or_cam_shader: Option<RaylibShaderHandler> = self
+            .shaders
+            .get("transform_origin")
+            .map(|shader| RaylibShaderHandler {
+                shader: shader.clone(),
+            });
+        let cam_shader: Option<RaylibShaderHandler> =
+            self.shaders
+                .get("camera")
                 .map(|shader| RaylibShaderHandler {
                     shader: shader.clone(),
                 });
-            let cam_shader: Option<RaylibShaderHandler> =
-                self.shaders
-                    .get("camera")
-                    .map(|shader| RaylibShaderHandler {
-                        shader: shader.clone(),
-                    });
-            let raylib_image_handler = RaylibImageHandler {
-                image: Rc::new(RefCell::new(RaylibImage {
-                    image,
-                    texture: None,
-                })),
-                tr_or_cam_shader: Rc::new(RefCell::new(tr_or_cam_shader)),
-                cam_shader: Rc::new(RefCell::new(cam_shader)),
-                camera: self.camera.clone(),
-            };
-            self.images
-                .insert(path_str.to_owned(), raylib_image_handler.image.clone());
-            Ok(Box::new(raylib_image_handler))
-        }
+        Ok(Box::new(RaylibImageHandler {
+            image,
+            tr_or_cam_shader: Rc::new(RefCell::new(tr_or_cam_shader)),
+            cam_shader: Rc::new(RefCell::new(cam_shader)),
+            camera: self.camera.clone(),
+        }))
     }
 
     fn load_font(
         &mut self,
         path: &std::path::Path,
+        options: &FontOptions,
     ) -> Result<Box<dyn super::FontInterface>, String> {
-        unsafe {
-            let path_str = path
-                .to_str()
-                .ok_or_else(|| format!("Failed to convert \"{path:?}\" to str!"))?;
-            let path_buf: Vec<u8> = path_str.as_bytes().into();
-            let cstring: CString = CString::from_vec_unchecked(path_buf);
-            let font = ffi::LoadFont(cstring.as_ptr());
-            ffi::SetTextureFilter(
-                font.texture,
-                ffi::TextureFilter_TEXTURE_FILTER_BILINEAR as ::std::os::raw::c_int,
-            );
-            let raylib_font_handler = RaylibFontHandler {
-                font: Rc::new(RaylibFont { font }),
-            };
-            self.fonts
-                .insert(path_str.to_owned(), raylib_font_handler.font.clone());
-            Ok(Box::new(raylib_font_handler))
-        }
+        let font = self.load_raylib_font(path, options)?;
+        let fallbacks = options
+            .fallbacks
+            .iter()
+            .map(|fallback| self.load_raylib_font(fallback, options))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Box::new(RaylibFontHandler { font, fallbacks }))
     }
 
     fn load_sound(
         &mut self,
         path: &std::path::Path,
     ) -> Result<Box<dyn super::SoundInterface>, String> {
+        let path_str = path_to_str(path)?;
+        if let Some(sound) = self.sounds.get(path_str) {
+            return Ok(Box::new(RaylibSoundHandler {
+                sound: sound.clone(),
+            }));
+        }
+        let data = self.vfs.read(path)?;
         unsafe {
-            let path_str = path
-                .to_str()
-                .ok_or_else(|| format!("Failed to convert \"{path:?}\" to str!"))?;
-            let cstring: CString = CString::from_vec_unchecked(path_str.as_bytes().into());
-            let sound = ffi::LoadSound(cstring.as_ptr());
+            let wave = ffi::LoadWaveFromMemory(
+                file_type(path)?.as_ptr(),
+                data.as_ptr(),
+                data.len() as c_int,
+            );
+            if wave.data.is_null() {
+                return Err(format!("Failed to load sound {path:?}"));
+            }
+            let sound = ffi::LoadSoundFromWave(wave);
+            ffi::UnloadWave(wave);
             let raylib_sound_handler = RaylibSoundHandler {
                 sound: Rc::new(RaylibSound { sound }),
             };
@@ -1028,15 +1543,24 @@ impl GameInterface for RaylibGame {
         &mut self,
         path: &std::path::Path,
     ) -> Result<Box<dyn super::MusicInterface>, String> {
+        let path_str = path_to_str(path)?;
+        if let Some(music) = self.music.get(path_str) {
+            return Ok(Box::new(RaylibMusicHandler {
+                music: music.clone(),
+            }));
+        }
+        let data = self.vfs.read(path)?.into_owned();
         unsafe {
-            let path_str = path
-                .to_str()
-                .ok_or_else(|| format!("Failed to convert \"{path:?}\" to str!"))?;
-            let path_buf: Vec<u8> = path_str.as_bytes().into();
-            let cstring: CString = CString::from_vec_unchecked(path_buf);
-            let music = ffi::LoadMusicStream(cstring.as_ptr());
+            let music = ffi::LoadMusicStreamFromMemory(
+                file_type(path)?.as_ptr(),
+                data.as_ptr(),
+                data.len() as c_int,
+            );
+            if music.ctxData.is_null() {
+                return Err(format!("Failed to load music {path:?}"));
+            }
             let raylib_music_handler = RaylibMusicHandler {
-                music: Rc::new(RefCell::new(RaylibMusic { music })),
+                music: Rc::new(RefCell::new(RaylibMusic { music, _data: data })),
             };
             self.music
                 .insert(path_str.to_owned(), raylib_music_handler.music.clone());
@@ -1050,21 +1574,121 @@ impl GameInterface for RaylibGame {
         vs: &Path,
         fs: &Path,
     ) -> Result<Box<dyn ShaderInterface>, String> {
-        let raylib_shader = RaylibShaderHandler::load_shader(vs, fs)?;
+        let raylib_shader = RaylibShaderHandler::load_shader(&self.vfs, vs, fs)?;
+        if let Some(shader) = self.shaders.get(&name) {
+            // swapped in place, so every handle to the old one uses the new one
+            let new = raylib_shader.shader.borrow().clone();
+            let old = std::mem::replace(&mut *shader.borrow_mut(), new);
+            unsafe {
+                ffi::UnloadShader(old.shader);
+            }
+            return Ok(Box::new(RaylibShaderHandler {
+                shader: shader.clone(),
+            }));
+        }
         self.shaders.insert(name, raylib_shader.shader.clone());
         Ok(Box::new(raylib_shader))
     }
 
+    fn unload_image(&mut self, path: &Path) -> Result<(), String> {
+        if let Some(image) = take_unused(&mut self.images, path_to_str(path)?)? {
+            image.borrow_mut().unload();
+        }
+        Ok(())
+    }
+
+    fn unload_font(&mut self, path: &Path, options: &FontOptions) -> Result<(), String> {
+        for path in std::iter::once(path).chain(options.fallbacks.iter().map(|p| p.as_path())) {
+            if let Some(font) = take_unused(&mut self.fonts, &font_key(path, options)?)? {
+                unsafe {
+                    ffi::UnloadFont(font.font.get());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn unload_sound(&mut self, path: &Path) -> Result<(), String> {
+        if let Some(sound) = take_unused(&mut self.sounds, path_to_str(path)?)? {
+            unsafe {
+                ffi::UnloadSound(sound.sound);
+            }
+        }
+        Ok(())
+    }
+
+    fn unload_music(&mut self, path: &Path) -> Result<(), String> {
+        if let Some(music) = take_unused(&mut self.music, path_to_str(path)?)? {
+            unsafe {
+                ffi::UnloadMusicStream(music.borrow().music);
+            }
+        }
+        Ok(())
+    }
+
+    fn unload_shader(&mut self, name: &str) -> Result<(), String> {
+        if let Some(shader) = take_unused(&mut self.shaders, name)? {
+            unsafe {
+                ffi::UnloadShader(shader.borrow().shader);
+            }
+        }
+        Ok(())
+    }
+
+    fn vfs(&self) -> &Vfs {
+        &self.vfs
+    }
+
+    fn reload(&mut self, path: &Path) -> Result<bool, String> {
+        let mut reloaded = false;
+        let mut errors = Vec::new();
+        let mut reload = |result: Result<(), String>| match result {
+            Ok(()) => reloaded = true,
+            Err(e) => errors.push(e),
+        };
+        if let Some(image) = self.images.get(path_to_str(path)?) {
+            reload(image.borrow_mut().reload(&self.vfs, path));
+        }
+        for font in self.fonts.values().filter(|font| font.path == path) {
+            reload(font.reload(&self.vfs));
+        }
+        for shader in self.shaders.values() {
+            if shader.borrow().uses(path) {
+                reload(shader.borrow_mut().reload(&self.vfs));
+            }
+        }
+        if errors.is_empty() {
+            Ok(reloaded)
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    fn resource_report(&self) -> ResourceReport {
+        ResourceReport {
+            images: self.images.len(),
+            textures: self
+                .images
+                .values()
+                .filter(|image| image.borrow().texture.is_some())
+                .count(),
+            fonts: self.fonts.len(),
+            sounds: self.sounds.len(),
+            music: self.music.len(),
+            shaders: self.shaders.len(),
+        }
+    }
+
     fn get_camera(&mut self) -> Result<Box<dyn super::CameraInterface>, String> {
-        Ok(Box::new(self.camera.borrow().clone()))
+        Ok(Box::new(*self.camera.borrow()))
     }
 
     fn get_default_camera(&mut self) -> Result<Box<dyn super::CameraInterface>, String> {
-        Ok(Box::<Camera>::default())
+        Ok(Box::<CameraView>::default())
     }
 
     fn set_camera(&mut self, camera: &dyn super::CameraInterface) -> Result<(), String> {
-        self.camera.borrow_mut().pos = camera.get_view_xy()?.into();
+        *self.camera.borrow_mut() = camera.get_view()?;
         Ok(())
     }
 
@@ -1074,38 +1698,41 @@ impl GameInterface for RaylibGame {
     }
 
     fn xy_to_world(&self, x: f32, y: f32) -> Result<Vector, String> {
-        Ok(Vector {
-            x: x + self.camera.borrow().pos.x,
-            y: y + self.camera.borrow().pos.y,
-        })
+        Ok(self
+            .camera
+            .borrow()
+            .screen_to_world(Vector::new(x, y), screen_center()))
     }
 
     fn vec_to_world(&self, vec: Vector) -> Result<Vector, String> {
-        Ok(Vector {
-            x: vec.x + self.camera.borrow().pos.x,
-            y: vec.y + self.camera.borrow().pos.y,
-        })
+        self.xy_to_world(vec.x, vec.y)
+    }
+
+    fn world_to_screen(&self, world: Vector) -> Result<Vector, String> {
+        Ok(self.camera.borrow().world_to_screen(world, screen_center()))
     }
 }
 
 impl RaylibGame {
-    fn load_transform_origin_shader(&mut self) -> Result<Box<dyn ShaderInterface>, String> {
+    fn load_transform_origin_shader(
+        &mut self,
+        asset_root: &Path,
+    ) -> Result<Box<dyn ShaderInterface>, String> {
         self.load_shader(
             String::from("transform_origin"),
-            &PathBuf::from_str("static/transform.vs")
-                .map_err(|_| String::from("Failed to convert \"static/transform.vs\" to path!"))?,
-            &PathBuf::from_str("static/simple.fs")
-                .map_err(|_| String::from("Failed to convert \"static/simple.fs\" to path!"))?,
+            &asset_root.join("transform.vs"),
+            &asset_root.join("simple.fs"),
         )
     }
 
-    fn load_camera_shader(&mut self) -> Result<Box<dyn ShaderInterface>, String> {
+    fn load_camera_shader(
+        &mut self,
+        asset_root: &Path,
+    ) -> Result<Box<dyn ShaderInterface>, String> {
         self.load_shader(
             String::from("camera"),
-            &PathBuf::from_str("static/camera.vs")
-                .map_err(|_| String::from("Failed to convert \"static/camera.vs\" to path!"))?,
-            &PathBuf::from_str("static/simple.fs")
-                .map_err(|_| String::from("Failed to convert \"static/camera.vs\" to path!"))?,
+            &asset_root.join("camera.vs"),
+            &asset_root.join("simple.fs"),
         )
     }
 }
@@ -1,11 +1,41 @@
 use ld45_lib::agnostic_interface;
+use ld45_lib::launch_options::{LaunchOptions, USAGE};
+use ld45_lib::vfs;
 
 fn will_close() -> bool {
     unsafe { agnostic_interface::raylib_impl::ffi::WindowShouldClose() }
 }
 
 fn main() {
-    let state_ptr = ld45_lib::ld45_initialize();
+    let options = match LaunchOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(1);
+        }
+    };
+    if options.help {
+        println!("{USAGE}");
+        return;
+    }
+    if let Some(out) = &options.write_pack {
+        if let Err(e) = vfs::write_pack(&options.asset_root).and_then(|pack| {
+            std::fs::write(out, pack)
+                .map_err(|e| format!("Failed to write \"{}\": {e}", out.display()))
+        }) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let state_ptr = match ld45_lib::ld45_initialize_with_options(&options) {
+        Ok(state_ptr) => state_ptr,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
 
     while !will_close() {
         ld45_lib::ld45_iterate(state_ptr);
@@ -0,0 +1,208 @@
+use std::ffi::OsString;
+use std::io::Result as IOResult;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+
+pub const DATA_DIR_ENV: &str = "LD45_DATA_DIR";
+const DATA_DIR_NAME: &str = "LudumDare45_OneAndAll";
+
+/// Resolves the data directory and creates it if missing. Falls back to the
+/// current directory if it cannot be created.
+pub fn open(override_dir: Option<PathBuf>) -> PathBuf {
+    let dir = resolve(
+        override_dir,
+        std::env::var_os(DATA_DIR_ENV),
+        std::env::var_os("XDG_DATA_HOME"),
+        std::env::var_os("HOME"),
+    );
+    match std::fs::create_dir_all(&dir) {
+        Ok(()) => dir,
+        Err(e) => {
+            eprintln!("WARNING: Failed to create data dir {}: {e}", dir.display());
+            PathBuf::from(".")
+        }
+    }
+}
+
+pub fn resolve(
+    override_dir: Option<PathBuf>,
+    env_dir: Option<OsString>,
+    xdg_data_home: Option<OsString>,
+    home: Option<OsString>,
+) -> PathBuf {
+    if let Some(dir) = override_dir {
+        return dir;
+    }
+    if let Some(dir) = env_dir.filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir);
+    }
+
+    // Relative paths in $XDG_DATA_HOME are invalid and should be ignored.
+    let base = xdg_data_home
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| {
+            home.filter(|h| !h.is_empty())
+                .map(|h| PathBuf::from(h).join(".local").join("share"))
+        });
+    match base {
+        Some(base) => base.join(DATA_DIR_NAME),
+        None => PathBuf::from("."),
+    }
+}
+
+// Writes to a temporary file first so a crash mid-write never leaves a
+// truncated file behind.
+pub fn write_atomic(path: &Path, data: &[u8]) -> IOResult<()> {
+    use std::io::Write;
+
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    {
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+    }
+    std::fs::rename(tmp_path, path)
+}
+
+/// Keeps the previous versions of a file as `<path>.1` (the newest) up to
+/// `<path>.<count>` before it is overwritten.
+pub fn rotate_backups(path: &Path, count: usize) -> IOResult<()> {
+    if count == 0 || !path.is_file() {
+        return Ok(());
+    }
+    let backup_path = |n: usize| {
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(format!(".{n}"));
+        PathBuf::from(backup)
+    };
+    for n in (1..count).rev() {
+        let from = backup_path(n);
+        if from.is_file() {
+            std::fs::rename(from, backup_path(n + 1))?;
+        }
+    }
+    // Copied so the file itself stays in place until it is replaced.
+    std::fs::copy(path, backup_path(1))?;
+    Ok(())
+}
+
+pub fn read_file(path: &Path) -> IOResult<Receiver<Vec<u8>>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    match std::fs::read(path) {
+        Ok(bytes) => tx.send(bytes).ok(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => tx.send(Vec::new()).ok(),
+        Err(e) => return Err(e),
+    };
+    Ok(rx)
+}
+
+/// Moves files from `from_dir` that do not exist yet in `to_dir`.
+pub fn migrate(from_dir: &Path, to_dir: &Path, filenames: &[String]) -> IOResult<()> {
+    if from_dir.canonicalize()? == to_dir.canonicalize()? {
+        return Ok(());
+    }
+    for filename in filenames {
+        let from = from_dir.join(filename);
+        let to = to_dir.join(filename);
+        if !from.is_file() || to.exists() {
+            continue;
+        }
+        // Renaming fails across filesystems.
+        if std::fs::rename(&from, &to).is_err() {
+            std::fs::copy(&from, &to)?;
+            std::fs::remove_file(&from)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(
+            resolve(
+                Some(PathBuf::from("cli")),
+                Some("env".into()),
+                Some("/xdg".into()),
+                Some("/home/user".into())
+            ),
+            PathBuf::from("cli")
+        );
+        assert_eq!(
+            resolve(
+                None,
+                Some("env".into()),
+                Some("/xdg".into()),
+                Some("/home/user".into())
+            ),
+            PathBuf::from("env")
+        );
+        assert_eq!(
+            resolve(
+                None,
+                Some("".into()),
+                Some("/xdg".into()),
+                Some("/home/user".into())
+            ),
+            PathBuf::from("/xdg").join(DATA_DIR_NAME)
+        );
+        assert_eq!(
+            resolve(
+                None,
+                None,
+                Some("relative".into()),
+                Some("/home/user".into())
+            ),
+            PathBuf::from("/home/user/.local/share").join(DATA_DIR_NAME)
+        );
+        assert_eq!(resolve(None, None, None, None), PathBuf::from("."));
+    }
+
+    #[test]
+    fn test_migrate() {
+        let base = std::env::temp_dir().join(format!("ld45_migrate_{}", std::process::id()));
+        let from_dir = base.join("from");
+        let to_dir = base.join("to");
+        std::fs::create_dir_all(&from_dir).unwrap();
+        std::fs::create_dir_all(&to_dir).unwrap();
+        std::fs::write(from_dir.join("a.bin"), [1]).unwrap();
+        std::fs::write(from_dir.join("b.bin"), [2]).unwrap();
+        std::fs::write(to_dir.join("b.bin"), [3]).unwrap();
+
+        let filenames = ["a.bin", "b.bin", "c.bin"].map(String::from);
+        migrate(&from_dir, &to_dir, &filenames).unwrap();
+        assert_eq!(std::fs::read(to_dir.join("a.bin")).unwrap(), [1]);
+        assert!(!from_dir.join("a.bin").exists());
+        // Never overwrite saves already in the data directory.
+        assert_eq!(std::fs::read(to_dir.join("b.bin")).unwrap(), [3]);
+        assert_eq!(std::fs::read(from_dir.join("b.bin")).unwrap(), [2]);
+        assert!(!to_dir.join("c.bin").exists());
+
+        std::fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_backups() {
+        let dir = std::env::temp_dir().join(format!("ld45_backups_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("save.bin");
+        rotate_backups(&path, 2).unwrap();
+        assert!(!dir.join("save.bin.1").exists());
+
+        for data in 1..=4u8 {
+            rotate_backups(&path, 2).unwrap();
+            write_atomic(&path, &[data]).unwrap();
+        }
+        assert_eq!(std::fs::read(&path).unwrap(), [4]);
+        assert_eq!(std::fs::read(dir.join("save.bin.1")).unwrap(), [3]);
+        assert_eq!(std::fs::read(dir.join("save.bin.2")).unwrap(), [2]);
+        assert!(!dir.join("save.bin.3").exists());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
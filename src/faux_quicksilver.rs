@@ -3,8 +3,12 @@ use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
 use std::path::Path;
 
 use crate::agnostic_interface::{
-    FontInterface, GameInterface, ImageInterface, MusicInterface, SoundInterface,
+    FontInterface, FontOptions, GameInterface, ImageInterface, MusicInterface, ShaderInterface,
+    SoundInterface,
 };
+use crate::manifest::{AssetKind, Manifest};
+use crate::serialization::{Decode, DecodeError, Encode, Reader, Writer};
+use crate::slot_map::{Handle, SlotMap};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Color {
@@ -45,24 +49,27 @@ impl Color {
     pub fn from_rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
         Self { r, g, b, a }
     }
+}
 
-    pub fn deserialize(data: &[u8], offset: usize) -> Result<(Color, usize), ()> {
-        if data.len() < offset + 4 {
-            Err(())
-        } else {
-            let mut color = Color::WHITE;
-
-            color.r = data[offset];
-            color.g = data[offset + 1];
-            color.b = data[offset + 2];
-            color.a = data[offset + 3];
-
-            Ok((color, 4))
-        }
+impl Encode for Color {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.r);
+        w.write(&self.g);
+        w.write(&self.b);
+        w.write(&self.a);
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        vec![self.r, self.g, self.b, self.a]
+impl Decode for Color {
+    const MIN_SIZE: usize = 4;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            r: r.read("r")?,
+            g: r.read("g")?,
+            b: r.read("b")?,
+            a: r.read("a")?,
+        })
     }
 }
 
@@ -83,60 +90,27 @@ impl Rectangle {
         self.x += v.x;
         self.y += v.y;
     }
+}
 
-    pub fn deserialize(data: &[u8], offset: usize) -> Result<(Rectangle, usize), ()> {
-        if data.len() < offset + std::mem::size_of::<f32>() * 4 {
-            return Err(());
-        }
-
-        let mut idx: usize = 0;
-        let mut rect = Rectangle::new(0.0, 0.0, 0.0, 0.0);
-
-        rect.x = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-        rect.y = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-        rect.w = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-        rect.h = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        Ok((rect, idx))
+impl Encode for Rectangle {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.x);
+        w.write(&self.y);
+        w.write(&self.w);
+        w.write(&self.h);
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-
-        for byte in self.x.to_be_bytes() {
-            bytes.push(byte);
-        }
-        for byte in self.y.to_be_bytes() {
-            bytes.push(byte);
-        }
-        for byte in self.w.to_be_bytes() {
-            bytes.push(byte);
-        }
-        for byte in self.h.to_be_bytes() {
-            bytes.push(byte);
-        }
+impl Decode for Rectangle {
+    const MIN_SIZE: usize = 4 * f32::MIN_SIZE;
 
-        bytes
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            x: r.read("x")?,
+            y: r.read("y")?,
+            w: r.read("w")?,
+            h: r.read("h")?,
+        })
     }
 }
 
@@ -177,53 +151,25 @@ impl Circle {
         self.x += v.x;
         self.y += v.y;
     }
+}
 
-    pub fn deserialize(data: &[u8], offset: usize) -> Result<(Circle, usize), ()> {
-        if data.len() < 12 + offset {
-            return Err(());
-        }
-
-        let mut idx: usize = 0;
-        let mut circle = Circle::new(0.0, 0.0, 1.0);
-
-        circle.x = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        circle.y = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        circle.r = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        Ok((circle, idx))
+impl Encode for Circle {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.x);
+        w.write(&self.y);
+        w.write(&self.r);
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-
-        for byte in self.x.to_be_bytes() {
-            bytes.push(byte);
-        }
-        for byte in self.y.to_be_bytes() {
-            bytes.push(byte);
-        }
-        for byte in self.r.to_be_bytes() {
-            bytes.push(byte);
-        }
+impl Decode for Circle {
+    const MIN_SIZE: usize = 3 * f32::MIN_SIZE;
 
-        bytes
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            x: r.read("x")?,
+            y: r.read("y")?,
+            r: r.read("r")?,
+        })
     }
 }
 
@@ -290,42 +236,23 @@ impl Vector {
     pub fn new(x: f32, y: f32) -> Self {
         Self { x, y }
     }
+}
 
-    pub fn deserialize(data: &[u8], offset: usize) -> Result<(Vector, usize), ()> {
-        let mut idx: usize = 0;
-        let mut vector = Vector::new(0.0, 0.0);
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        vector.x = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        vector.y = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        Ok((vector, idx))
+impl Encode for Vector {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.x);
+        w.write(&self.y);
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-
-        for byte in self.x.to_be_bytes() {
-            bytes.push(byte);
-        }
-        for byte in self.y.to_be_bytes() {
-            bytes.push(byte);
-        }
+impl Decode for Vector {
+    const MIN_SIZE: usize = 2 * f32::MIN_SIZE;
 
-        bytes
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            x: r.read("x")?,
+            y: r.read("y")?,
+        })
     }
 }
 
@@ -439,22 +366,65 @@ impl From<Transform> for crate::agnostic_interface::raylib_impl::ffi::Matrix {
     }
 }
 
+pub type ImageHandle = Handle<Box<dyn ImageInterface>>;
+pub type FontHandle = Handle<Box<dyn FontInterface>>;
+pub type SoundHandle = Handle<Box<dyn SoundInterface>>;
+pub type MusicHandle = Handle<Box<dyn MusicInterface>>;
+pub type ShaderHandle = Handle<Box<dyn ShaderInterface>>;
+
+/// An asset loaded from a manifest, looked up by the name it has there.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AssetHandle {
+    Image(ImageHandle),
+    Font(FontHandle),
+    Sound(SoundHandle),
+    Music(MusicHandle),
+    Shader(ShaderHandle),
+}
+
+macro_rules! asset_handle_from {
+    ($($variant:ident($handle:ty),)*) => {
+        $(impl From<$handle> for AssetHandle {
+            fn from(handle: $handle) -> Self {
+                AssetHandle::$variant(handle)
+            }
+        })*
+    };
+}
+
+asset_handle_from! {
+    Image(ImageHandle),
+    Font(FontHandle),
+    Sound(SoundHandle),
+    Music(MusicHandle),
+    Shader(ShaderHandle),
+}
+
+// Tells the backend that the handle to an asset was dropped.
+type Unload = Box<dyn FnOnce(&mut dyn GameInterface) -> Result<(), String>>;
+
 pub struct Window {
     gi: Box<dyn GameInterface>,
-    images: HashMap<String, Box<dyn ImageInterface>>,
-    fonts: HashMap<String, Box<dyn FontInterface>>,
-    sounds: HashMap<String, Box<dyn SoundInterface>>,
-    music: HashMap<String, Box<dyn MusicInterface>>,
+    images: SlotMap<Box<dyn ImageInterface>>,
+    fonts: SlotMap<Box<dyn FontInterface>>,
+    sounds: SlotMap<Box<dyn SoundInterface>>,
+    music: SlotMap<Box<dyn MusicInterface>>,
+    shaders: SlotMap<Box<dyn ShaderInterface>>,
+    names: HashMap<String, AssetHandle>,
+    unloads: HashMap<AssetHandle, Unload>,
 }
 
 impl Window {
     pub fn new(gi: Box<dyn GameInterface>) -> Self {
         Self {
             gi,
-            images: HashMap::new(),
-            fonts: HashMap::new(),
-            sounds: HashMap::new(),
-            music: HashMap::new(),
+            images: SlotMap::new(),
+            fonts: SlotMap::new(),
+            sounds: SlotMap::new(),
+            music: SlotMap::new(),
+            shaders: SlotMap::new(),
+            names: HashMap::new(),
+            unloads: HashMap::new(),
         }
     }
 
@@ -466,96 +436,202 @@ impl Window {
         self.gi.as_mut()
     }
 
-    pub fn load_image(&mut self, path: &Path, name: String) -> Result<(), String> {
-        self.images.insert(name, self.gi.load_image(path)?);
-
-        Ok(())
+    pub fn load_image(&mut self, path: &Path) -> Result<ImageHandle, String> {
+        let image = self.images.insert(self.gi.load_image(path)?);
+        let path = path.to_owned();
+        self.unloads
+            .insert(image.into(), Box::new(move |gi| gi.unload_image(&path)));
+        Ok(image)
     }
 
-    pub fn load_font(&mut self, path: &Path, name: String) -> Result<(), String> {
-        self.fonts.insert(name, self.gi.load_font(path)?);
-
-        Ok(())
+    pub fn load_font(&mut self, path: &Path, options: &FontOptions) -> Result<FontHandle, String> {
+        let font = self.fonts.insert(self.gi.load_font(path, options)?);
+        let (path, options) = (path.to_owned(), options.clone());
+        self.unloads.insert(
+            font.into(),
+            Box::new(move |gi| gi.unload_font(&path, &options)),
+        );
+        Ok(font)
+    }
+
+    pub fn load_sound(&mut self, path: &Path) -> Result<SoundHandle, String> {
+        let sound = self.sounds.insert(self.gi.load_sound(path)?);
+        let path = path.to_owned();
+        self.unloads
+            .insert(sound.into(), Box::new(move |gi| gi.unload_sound(&path)));
+        Ok(sound)
+    }
+
+    pub fn load_music(&mut self, path: &Path) -> Result<MusicHandle, String> {
+        let music = self.music.insert(self.gi.load_music(path)?);
+        let path = path.to_owned();
+        self.unloads
+            .insert(music.into(), Box::new(move |gi| gi.unload_music(&path)));
+        Ok(music)
+    }
+
+    pub fn load_shader(
+        &mut self,
+        name: String,
+        vs: &Path,
+        fs: &Path,
+    ) -> Result<ShaderHandle, String> {
+        let shader = self
+            .shaders
+            .insert(self.gi.load_shader(name.clone(), vs, fs)?);
+        self.unloads
+            .insert(shader.into(), Box::new(move |gi| gi.unload_shader(&name)));
+        Ok(shader)
+    }
+
+    /// Drops the asset and any manifest name of it, the backend frees it once
+    /// no other handle loaded from the same file is left. The handle must not
+    /// be used afterwards.
+    pub fn unload(&mut self, asset: impl Into<AssetHandle>) -> Result<(), String> {
+        let asset = asset.into();
+        let removed = match asset {
+            AssetHandle::Image(image) => self.images.remove(image).is_some(),
+            AssetHandle::Font(font) => self.fonts.remove(font).is_some(),
+            AssetHandle::Sound(sound) => self.sounds.remove(sound).is_some(),
+            AssetHandle::Music(music) => self.music.remove(music).is_some(),
+            AssetHandle::Shader(shader) => self.shaders.remove(shader).is_some(),
+        };
+        if !removed {
+            return Err(format!("{asset:?} is not loaded"));
+        }
+        self.names.retain(|_, handle| *handle != asset);
+        let unload = self
+            .unloads
+            .remove(&asset)
+            .ok_or_else(|| format!("{asset:?} is not loaded"))?;
+        unload(self.gi.as_mut())
+    }
+
+    /// Loads the audio or the other entries of `manifest`, the audio has to
+    /// wait until the audio device is ready. Fails listing every asset that
+    /// could not be loaded.
+    pub fn load_manifest(
+        &mut self,
+        manifest: &Manifest,
+        asset_root: &Path,
+        audio: bool,
+    ) -> Result<(), String> {
+        let mut errors = Vec::new();
+        for entry in &manifest.entries {
+            if entry.kind.is_audio() != audio {
+                continue;
+            }
+            let path = asset_root.join(&entry.path);
+            let handle = match &entry.kind {
+                AssetKind::Image => self.load_image(&path).map(AssetHandle::Image),
+                AssetKind::Font(options) => {
+                    let mut options = options.clone();
+                    for fallback in &mut options.fallbacks {
+                        *fallback = asset_root.join(&fallback);
+                    }
+                    self.load_font(&path, &options).map(AssetHandle::Font)
+                }
+                AssetKind::Sound => self.load_sound(&path).map(AssetHandle::Sound),
+                AssetKind::Music { looping } => self.load_music(&path).and_then(|music| {
+                    self.get_music_mut(music)?.set_loop(*looping)?;
+                    Ok(AssetHandle::Music(music))
+                }),
+                AssetKind::Shader { fragment } => self
+                    .load_shader(entry.name.clone(), &path, &asset_root.join(fragment))
+                    .map(AssetHandle::Shader),
+            };
+            match handle {
+                Ok(handle) => {
+                    // loading a manifest again swaps in the new assets
+                    if let Some(old) = self.names.insert(entry.name.clone(), handle) {
+                        if let Err(e) = self.unload(old) {
+                            errors.push(e);
+                        }
+                    }
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Failed to load assets:\n{}", errors.join("\n")))
+        }
     }
 
-    pub fn load_sound(&mut self, path: &Path, name: String) -> Result<(), String> {
-        self.sounds.insert(name, self.gi.load_sound(path)?);
-
-        Ok(())
+    /// The handle of an asset loaded by `load_manifest`.
+    pub fn asset(&self, name: &str) -> Result<AssetHandle, String> {
+        self.names
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("Asset \"{name}\" not found"))
     }
 
-    pub fn load_music(&mut self, path: &Path, name: String) -> Result<(), String> {
-        self.music.insert(name, self.gi.load_music(path)?);
-
-        Ok(())
+    pub fn image(&self, name: &str) -> Result<ImageHandle, String> {
+        match self.asset(name)? {
+            AssetHandle::Image(image) => Ok(image),
+            _ => Err(format!("Asset \"{name}\" is not an image")),
+        }
     }
 
-    pub fn get_image(&self, name: &str) -> Result<&dyn ImageInterface, String> {
-        Ok(self
-            .images
-            .get(name)
-            .ok_or_else(|| format!("Image \"{name}\" not found"))?
-            .as_ref())
+    pub fn font(&self, name: &str) -> Result<FontHandle, String> {
+        match self.asset(name)? {
+            AssetHandle::Font(font) => Ok(font),
+            _ => Err(format!("Asset \"{name}\" is not a font")),
+        }
     }
 
-    pub fn get_image_mut(&mut self, name: &str) -> Result<&mut dyn ImageInterface, String> {
-        Ok(self
-            .images
-            .get_mut(name)
-            .ok_or_else(|| format!("Image \"{name}\" not found"))?
-            .as_mut())
+    pub fn sound(&self, name: &str) -> Result<SoundHandle, String> {
+        match self.asset(name)? {
+            AssetHandle::Sound(sound) => Ok(sound),
+            _ => Err(format!("Asset \"{name}\" is not a sound")),
+        }
     }
 
-    pub fn get_font(&self, name: &str) -> Result<&dyn FontInterface, String> {
-        Ok(self
-            .fonts
-            .get(name)
-            .ok_or_else(|| format!("Font \"{name}\" not found"))?
-            .as_ref())
+    pub fn music(&self, name: &str) -> Result<MusicHandle, String> {
+        match self.asset(name)? {
+            AssetHandle::Music(music) => Ok(music),
+            _ => Err(format!("Asset \"{name}\" is not music")),
+        }
     }
 
-    pub fn get_font_mut(&mut self, name: &str) -> Result<&mut dyn FontInterface, String> {
-        Ok(self
-            .fonts
-            .get_mut(name)
-            .ok_or_else(|| format!("Font \"{name}\" not found"))?
-            .as_mut())
+    pub fn get_image_mut(&mut self, image: ImageHandle) -> Result<&mut dyn ImageInterface, String> {
+        match self.images.get_mut(image) {
+            Some(image) => Ok(image.as_mut()),
+            None => Err(format!("{image:?} is not loaded")),
+        }
     }
 
-    pub fn get_sound(&self, name: &str) -> Result<&dyn SoundInterface, String> {
-        Ok(self
-            .sounds
-            .get(name)
-            .ok_or_else(|| format!("Sound \"{name}\" not found"))?
-            .as_ref())
+    pub fn get_font(&self, font: FontHandle) -> Result<&dyn FontInterface, String> {
+        match self.fonts.get(font) {
+            Some(font) => Ok(font.as_ref()),
+            None => Err(format!("{font:?} is not loaded")),
+        }
     }
 
-    pub fn get_sound_mut(&mut self, name: &str) -> Result<&mut dyn SoundInterface, String> {
-        Ok(self
-            .sounds
-            .get_mut(name)
-            .ok_or_else(|| format!("Sound \"{name}\" not found"))?
-            .as_mut())
+    pub fn get_font_mut(&mut self, font: FontHandle) -> Result<&mut dyn FontInterface, String> {
+        match self.fonts.get_mut(font) {
+            Some(font) => Ok(font.as_mut()),
+            None => Err(format!("{font:?} is not loaded")),
+        }
     }
 
-    pub fn get_music(&self, name: &str) -> Result<&dyn MusicInterface, String> {
-        Ok(self
-            .music
-            .get(name)
-            .ok_or_else(|| format!("Music \"{name}\" not found"))?
-            .as_ref())
+    pub fn get_sound_mut(&mut self, sound: SoundHandle) -> Result<&mut dyn SoundInterface, String> {
+        match self.sounds.get_mut(sound) {
+            Some(sound) => Ok(sound.as_mut()),
+            None => Err(format!("{sound:?} is not loaded")),
+        }
     }
 
-    pub fn get_music_mut(&mut self, name: &str) -> Result<&mut dyn MusicInterface, String> {
-        Ok(self
-            .music
-            .get_mut(name)
-            .ok_or_else(|| format!("Music \"{name}\" not found"))?
-            .as_mut())
+    pub fn get_music_mut(&mut self, music: MusicHandle) -> Result<&mut dyn MusicInterface, String> {
+        match self.music.get_mut(music) {
+            Some(music) => Ok(music.as_mut()),
+            None => Err(format!("{music:?} is not loaded")),
+        }
     }
 
     pub fn update_music(&mut self) -> Result<(), String> {
-        for music in self.music.values_mut() {
+        for music in self.music.iter_mut() {
             music.update()?;
         }
         Ok(())
@@ -565,13 +641,42 @@ impl Window {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agnostic_interface::headless_impl::HeadlessGame;
+    use crate::manifest::Manifest;
+    use crate::serialization::{from_bytes, to_bytes};
+
+    #[test]
+    fn test_unload() {
+        let (gi, _controller) = HeadlessGame::new_boxed(800, 600);
+        let mut window = Window::new(gi);
+        let manifest = Manifest::parse("music theme music2.mp3 loop\nsound boom boom.mp3").unwrap();
+        window
+            .load_manifest(&manifest, Path::new("static"), true)
+            .unwrap();
+        let theme = window.music("theme").unwrap();
+
+        // swapping the theme frees the old track
+        let manifest = Manifest::parse("music theme get.mp3\nsound boom boom.mp3").unwrap();
+        window
+            .load_manifest(&manifest, Path::new("static"), true)
+            .unwrap();
+        assert_ne!(window.music("theme"), Ok(theme));
+        assert!(window.unload(theme).is_err());
+        assert!(window.get_music_mut(theme).is_err());
+        assert_eq!(window.get_gi().resource_report().music, 1);
+        assert_eq!(window.get_gi().resource_report().sounds, 1);
+
+        window.unload(window.music("theme").unwrap()).unwrap();
+        assert!(window.music("theme").is_err());
+        assert_eq!(window.get_gi().resource_report().music, 0);
+    }
 
     #[test]
     fn test_de_serialize_color() {
         let color = Color::from_rgba(1, 2, 3, 4);
-        let bytes = color.serialize();
+        let bytes = to_bytes(&color);
         let (des_color, size) =
-            Color::deserialize(&bytes, 0).expect("Should be able to deserialize Color!");
+            from_bytes::<Color>(&bytes).expect("Should be able to deserialize Color!");
         assert_eq!(color, des_color);
         assert_eq!(bytes.len(), size);
     }
@@ -579,9 +684,9 @@ mod tests {
     #[test]
     fn test_de_serialize_rectangle() {
         let rect = Rectangle::new(1.0, 2.0, 3.0, 4.0);
-        let bytes = rect.serialize();
+        let bytes = to_bytes(&rect);
         let (des_rect, size) =
-            Rectangle::deserialize(&bytes, 0).expect("Should be able to deserialize Rectangle!");
+            from_bytes::<Rectangle>(&bytes).expect("Should be able to deserialize Rectangle!");
         assert_eq!(rect, des_rect);
         assert_eq!(bytes.len(), size);
     }
@@ -589,9 +694,9 @@ mod tests {
     #[test]
     fn test_de_serialize_circle() {
         let circle = Circle::new(1.0, 2.0, 3.0);
-        let bytes = circle.serialize();
+        let bytes = to_bytes(&circle);
         let (des_circle, size) =
-            Circle::deserialize(&bytes, 0).expect("Should be able to deserialize Circle!");
+            from_bytes::<Circle>(&bytes).expect("Should be able to deserialize Circle!");
         assert_eq!(circle, des_circle);
         assert_eq!(bytes.len(), size);
     }
@@ -599,9 +704,9 @@ mod tests {
     #[test]
     fn test_de_serialize_vector() {
         let vect = Vector::new(1.0, 2.0);
-        let bytes = vect.serialize();
+        let bytes = to_bytes(&vect);
         let (des_vect, size) =
-            Vector::deserialize(&bytes, 0).expect("Should be able to deserialize Vector!");
+            from_bytes::<Vector>(&bytes).expect("Should be able to deserialize Vector!");
         assert_eq!(vect, des_vect);
         assert_eq!(bytes.len(), size);
     }
@@ -0,0 +1,257 @@
+use crate::agnostic_interface::TouchPoint;
+use crate::faux_quicksilver::Vector;
+
+const TAP_MAX_TIME: f32 = 0.3;
+// A finger that moves further than this is not tapping or long pressing.
+const TAP_MAX_DISTANCE: f32 = 10.0;
+const DOUBLE_TAP_DISTANCE: f32 = 40.0;
+const LONG_PRESS_TIME: f32 = 0.6;
+
+/// Positions are in screen coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Gesture {
+    Tap {
+        pos: Vector,
+    },
+    /// Comes instead of the second `Tap`, the first one is still reported.
+    DoubleTap {
+        pos: Vector,
+    },
+    LongPress {
+        pos: Vector,
+    },
+    /// Movement of the point between two fingers since the last update.
+    Pan {
+        delta: Vector,
+    },
+    /// Distance between two fingers relative to the last update.
+    Pinch {
+        center: Vector,
+        scale: f32,
+    },
+}
+
+#[derive(Debug)]
+struct Press {
+    start: Vector,
+    pos: Vector,
+    time: f32,
+    moved: bool,
+    long_pressed: bool,
+}
+
+/// Turns the touch points of each frame into gestures.
+#[derive(Debug)]
+pub struct GestureRecognizer {
+    double_tap_time: f32,
+    touches: Vec<TouchPoint>,
+    // Only while a single finger is down and no other joined it since.
+    press: Option<Press>,
+    multi_touch: bool,
+    last_tap: Option<(Vector, f32)>,
+}
+
+fn distance(a: Vector, b: Vector) -> f32 {
+    let d = a - b;
+    (d.x * d.x + d.y * d.y).sqrt()
+}
+
+fn midpoint(a: Vector, b: Vector) -> Vector {
+    (a + b) * 0.5
+}
+
+impl GestureRecognizer {
+    pub fn new(double_tap_time: f32) -> Self {
+        Self {
+            double_tap_time,
+            touches: Vec::new(),
+            press: None,
+            multi_touch: false,
+            last_tap: None,
+        }
+    }
+
+    pub fn set_double_tap_time(&mut self, double_tap_time: f32) {
+        self.double_tap_time = double_tap_time;
+    }
+
+    /// True while any finger is down.
+    pub fn is_tracking(&self) -> bool {
+        !self.touches.is_empty()
+    }
+
+    pub fn update(&mut self, dt: f32, touches: &[TouchPoint]) -> Vec<Gesture> {
+        let mut gestures = Vec::new();
+
+        if let Some((_, t)) = &mut self.last_tap {
+            *t += dt;
+            if *t > self.double_tap_time {
+                self.last_tap = None;
+            }
+        }
+
+        if let [a, b] = touches {
+            let prev_a = self.touches.iter().find(|t| t.id == a.id);
+            let prev_b = self.touches.iter().find(|t| t.id == b.id);
+            if let (Some(prev_a), Some(prev_b)) = (prev_a, prev_b) {
+                let center = midpoint(a.pos, b.pos);
+                let delta = center - midpoint(prev_a.pos, prev_b.pos);
+                if delta.x != 0.0 || delta.y != 0.0 {
+                    gestures.push(Gesture::Pan { delta });
+                }
+                let dist = distance(a.pos, b.pos);
+                let prev_dist = distance(prev_a.pos, prev_b.pos);
+                if prev_dist > 0.0 && dist != prev_dist {
+                    gestures.push(Gesture::Pinch {
+                        center,
+                        scale: dist / prev_dist,
+                    });
+                }
+            }
+        }
+
+        match touches {
+            [] => {
+                if let Some(press) = self.press.take() {
+                    if !press.moved && !press.long_pressed && press.time <= TAP_MAX_TIME {
+                        let pos = press.pos;
+                        match self.last_tap.take() {
+                            Some((last, _)) if distance(last, pos) <= DOUBLE_TAP_DISTANCE => {
+                                gestures.push(Gesture::DoubleTap { pos });
+                            }
+                            _ => {
+                                gestures.push(Gesture::Tap { pos });
+                                self.last_tap = Some((pos, 0.0));
+                            }
+                        }
+                    }
+                }
+                self.multi_touch = false;
+            }
+            [touch] if !self.multi_touch => {
+                if let Some(press) = &mut self.press {
+                    press.time += dt;
+                }
+                let press = self.press.get_or_insert(Press {
+                    start: touch.pos,
+                    pos: touch.pos,
+                    time: 0.0,
+                    moved: false,
+                    long_pressed: false,
+                });
+                press.pos = touch.pos;
+                if distance(press.start, press.pos) > TAP_MAX_DISTANCE {
+                    press.moved = true;
+                }
+                if !press.moved && !press.long_pressed && press.time >= LONG_PRESS_TIME {
+                    press.long_pressed = true;
+                    gestures.push(Gesture::LongPress { pos: press.pos });
+                }
+            }
+            _ => {
+                self.multi_touch = true;
+                self.press = None;
+            }
+        }
+
+        self.touches = touches.to_vec();
+        gestures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn touch(id: u32, x: f32, y: f32) -> TouchPoint {
+        TouchPoint {
+            id,
+            pos: Vector::new(x, y),
+        }
+    }
+
+    #[test]
+    fn test_taps() {
+        let mut recognizer = GestureRecognizer::new(0.3);
+        assert_eq!(recognizer.update(DT, &[touch(0, 10.0, 10.0)]), vec![]);
+        assert!(recognizer.is_tracking());
+        assert_eq!(
+            recognizer.update(DT, &[]),
+            vec![Gesture::Tap {
+                pos: Vector::new(10.0, 10.0)
+            }]
+        );
+        recognizer.update(DT, &[touch(1, 15.0, 12.0)]);
+        assert_eq!(
+            recognizer.update(DT, &[]),
+            vec![Gesture::DoubleTap {
+                pos: Vector::new(15.0, 12.0)
+            }]
+        );
+
+        // too late for a double tap
+        recognizer.update(DT, &[touch(2, 10.0, 10.0)]);
+        recognizer.update(DT, &[]);
+        for _ in 0..30 {
+            assert_eq!(recognizer.update(DT, &[]), vec![]);
+        }
+        recognizer.update(DT, &[touch(3, 10.0, 10.0)]);
+        assert_eq!(
+            recognizer.update(DT, &[]),
+            vec![Gesture::Tap {
+                pos: Vector::new(10.0, 10.0)
+            }]
+        );
+
+        // moving is not tapping
+        recognizer.update(DT, &[touch(4, 100.0, 100.0)]);
+        recognizer.update(DT, &[touch(4, 150.0, 100.0)]);
+        assert_eq!(recognizer.update(DT, &[]), vec![]);
+        assert!(!recognizer.is_tracking());
+    }
+
+    #[test]
+    fn test_long_press() {
+        let mut recognizer = GestureRecognizer::new(0.3);
+        let mut gestures = Vec::new();
+        for _ in 0..60 {
+            gestures.extend(recognizer.update(DT, &[touch(0, 20.0, 30.0)]));
+        }
+        assert_eq!(
+            gestures,
+            vec![Gesture::LongPress {
+                pos: Vector::new(20.0, 30.0)
+            }]
+        );
+        assert_eq!(recognizer.update(DT, &[]), vec![]);
+    }
+
+    #[test]
+    fn test_pan_and_pinch() {
+        let mut recognizer = GestureRecognizer::new(0.3);
+        recognizer.update(DT, &[touch(0, 0.0, 0.0)]);
+        assert_eq!(
+            recognizer.update(DT, &[touch(0, 0.0, 0.0), touch(1, 100.0, 0.0)]),
+            vec![]
+        );
+        assert_eq!(
+            recognizer.update(DT, &[touch(0, 10.0, 20.0), touch(1, 110.0, 20.0)]),
+            vec![Gesture::Pan {
+                delta: Vector::new(10.0, 20.0)
+            }]
+        );
+        assert_eq!(
+            recognizer.update(DT, &[touch(0, -40.0, 20.0), touch(1, 160.0, 20.0)]),
+            vec![Gesture::Pinch {
+                center: Vector::new(60.0, 20.0),
+                scale: 2.0
+            }]
+        );
+
+        // lifting the fingers one by one is no tap
+        assert_eq!(recognizer.update(DT, &[touch(1, 160.0, 20.0)]), vec![]);
+        assert_eq!(recognizer.update(DT, &[]), vec![]);
+    }
+}
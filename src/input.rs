@@ -0,0 +1,416 @@
+use crate::agnostic_interface::{GameInterface, GamepadButton, Key, Modifiers, MouseButton};
+use crate::serialization::{Decode, DecodeError, DecodeErrorKind, Encode, Reader, Writer};
+
+pub const ACTION_COUNT: usize = 7;
+const MAX_BINDINGS_PER_ACTION: usize = 8;
+
+const BINDABLE_BUTTONS: [MouseButton; 3] =
+    [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Save,
+    Load,
+    Reset,
+    Settings,
+    Create,
+    MoveTo,
+    Confirm,
+}
+
+impl Action {
+    pub const ALL: [Action; ACTION_COUNT] = [
+        Action::Save,
+        Action::Load,
+        Action::Reset,
+        Action::Settings,
+        Action::Create,
+        Action::MoveTo,
+        Action::Confirm,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Save => "Save",
+            Action::Load => "Load",
+            Action::Reset => "Reset",
+            Action::Settings => "Settings",
+            Action::Create => "Create",
+            Action::MoveTo => "Move",
+            Action::Confirm => "Confirm",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binding {
+    /// Only while exactly these modifiers are held, so shortcuts like Ctrl+R
+    /// don't trigger the binding of R.
+    Key(Key, Modifiers),
+    Mouse(MouseButton),
+    /// On any connected gamepad.
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    /// The key without any modifiers.
+    pub fn key(key: Key) -> Self {
+        Binding::Key(key, Modifiers::default())
+    }
+
+    fn pressed(self, gi: &mut dyn GameInterface) -> Result<bool, String> {
+        match self {
+            Binding::Key(key, modifiers) => {
+                Ok(gi.is_key_pressed(key)? && gi.get_modifiers()? == modifiers)
+            }
+            Binding::Mouse(button) => gi.get_mouse_button_pressed(button),
+            Binding::Gamepad(button) => {
+                for pad in gi.get_gamepads()? {
+                    if gi.is_gamepad_button_pressed(pad, button)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    fn released(self, gi: &mut dyn GameInterface) -> Result<bool, String> {
+        match self {
+            Binding::Key(key, modifiers) => {
+                Ok(gi.is_key_released(key)? && gi.get_modifiers()? == modifiers)
+            }
+            Binding::Mouse(button) => gi.get_mouse_button_released(button),
+            Binding::Gamepad(button) => {
+                for pad in gi.get_gamepads()? {
+                    if gi.is_gamepad_button_released(pad, button)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Binding::Key(key, modifiers) => {
+                let mut name = String::new();
+                for (held, prefix) in [
+                    (modifiers.ctrl, "Ctrl+"),
+                    (modifiers.alt, "Alt+"),
+                    (modifiers.shift, "Shift+"),
+                    (modifiers.super_key, "Super+"),
+                ] {
+                    if held {
+                        name.push_str(prefix);
+                    }
+                }
+                name + &key.name()
+            }
+            Binding::Mouse(MouseButton::Left) => String::from("Left click"),
+            Binding::Mouse(MouseButton::Right) => String::from("Right click"),
+            Binding::Mouse(MouseButton::Middle) => String::from("Middle click"),
+            Binding::Gamepad(button) => format!("Pad {button:?}"),
+        }
+    }
+}
+
+/// Returns the first bindable key or mouse button pressed this frame. Escape
+/// is kept for cancelling and modifiers are never bound on their own.
+pub fn poll_binding(gi: &mut dyn GameInterface) -> Result<Option<Binding>, String> {
+    for key in Key::ALL
+        .iter()
+        .copied()
+        .filter(|key| *key != Key::Escape && !key.is_modifier())
+    {
+        if gi.is_key_pressed(key)? {
+            return Ok(Some(Binding::Key(key, gi.get_modifiers()?)));
+        }
+    }
+    for button in BINDABLE_BUTTONS {
+        if gi.get_mouse_button_pressed(button)? {
+            return Ok(Some(Binding::Mouse(button)));
+        }
+    }
+    for button in GamepadButton::ALL {
+        let binding = Binding::Gamepad(button);
+        if binding.pressed(gi)? {
+            return Ok(Some(binding));
+        }
+    }
+    Ok(None)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputMap {
+    bindings: [Vec<Binding>; ACTION_COUNT],
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        let left = Binding::Mouse(MouseButton::Left);
+        let pad_a = Binding::Gamepad(GamepadButton::FaceDown);
+        Self {
+            bindings: [
+                vec![Binding::key(Key::S)],
+                vec![Binding::key(Key::L)],
+                vec![Binding::key(Key::R)],
+                vec![Binding::key(Key::O), Binding::Gamepad(GamepadButton::Start)],
+                vec![left, pad_a],
+                vec![left],
+                vec![left, pad_a],
+            ],
+        }
+    }
+}
+
+impl InputMap {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        &self.bindings[action as usize]
+    }
+
+    pub fn pressed(&self, gi: &mut dyn GameInterface, action: Action) -> Result<bool, String> {
+        Ok(self.pressed_binding(gi, action)?.is_some())
+    }
+
+    /// Returns which binding of the action was pressed, if any.
+    pub fn pressed_binding(
+        &self,
+        gi: &mut dyn GameInterface,
+        action: Action,
+    ) -> Result<Option<Binding>, String> {
+        for binding in self.get(action) {
+            if binding.pressed(gi)? {
+                return Ok(Some(*binding));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn released(&self, gi: &mut dyn GameInterface, action: Action) -> Result<bool, String> {
+        for binding in self.get(action) {
+            if binding.released(gi)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Adds the binding to the action, or removes it if the action already
+    /// has it. The last binding of an action is never removed, so returns
+    /// false in that case.
+    pub fn toggle(&mut self, action: Action, binding: Binding) -> bool {
+        let bindings = &mut self.bindings[action as usize];
+        if let Some(idx) = bindings.iter().position(|b| *b == binding) {
+            if bindings.len() == 1 {
+                return false;
+            }
+            bindings.remove(idx);
+        } else if bindings.len() < MAX_BINDINGS_PER_ACTION {
+            bindings.push(binding);
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// For maps saved before gamepads could be bound.
+    pub fn add_default_gamepad_bindings(&mut self) {
+        let defaults = Self::default();
+        for action in Action::ALL {
+            for binding in defaults.get(action) {
+                if matches!(binding, Binding::Gamepad(_)) {
+                    let bindings = &mut self.bindings[action as usize];
+                    if !bindings.contains(binding) && bindings.len() < MAX_BINDINGS_PER_ACTION {
+                        bindings.push(*binding);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The names of the action's bindings, as in "S/Pad Start".
+    pub fn names(&self, action: Action) -> String {
+        let names: Vec<String> = self.get(action).iter().map(|b| b.name()).collect();
+        names.join("/")
+    }
+
+    pub fn describe(&self, action: Action) -> String {
+        let names: Vec<String> = self.get(action).iter().map(|b| b.name()).collect();
+        format!("{}: {}", action.name(), names.join(", "))
+    }
+}
+
+impl Encode for Binding {
+    fn encode(&self, w: &mut Writer) {
+        match self {
+            // Keys with modifiers got a kind of their own, so older bindings
+            // read the same.
+            Binding::Key(key, modifiers) if *modifiers == Modifiers::default() => {
+                w.write(&0u8);
+                w.write(&key.code());
+            }
+            Binding::Key(key, modifiers) => {
+                w.write(&3u8);
+                w.write(&key.code());
+                w.write(&modifier_bits(*modifiers));
+            }
+            Binding::Mouse(button) => {
+                w.write(&1u8);
+                w.write(&match button {
+                    MouseButton::Left => 0u8,
+                    MouseButton::Right => 1u8,
+                    MouseButton::Middle => 2u8,
+                });
+            }
+            Binding::Gamepad(button) => {
+                w.write(&2u8);
+                w.write(&(*button as u8));
+            }
+        }
+    }
+}
+
+fn modifier_bits(modifiers: Modifiers) -> u8 {
+    modifiers.shift as u8
+        | (modifiers.ctrl as u8) << 1
+        | (modifiers.alt as u8) << 2
+        | (modifiers.super_key as u8) << 3
+}
+
+impl Decode for Binding {
+    const MIN_SIZE: usize = 2;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        let offset = r.get_offset();
+        let invalid = |field: &str, kind: String| {
+            DecodeError::new(offset, DecodeErrorKind::InvalidValue(kind)).in_field(field)
+        };
+        let kind: u8 = r.read("kind")?;
+        match kind {
+            0 | 3 => {
+                let code: u32 = r.read("key")?;
+                let key = Key::from_code(code)
+                    .ok_or_else(|| invalid("key", format!("{code} is not a key code")))?;
+                if kind == 0 {
+                    return Ok(Binding::key(key));
+                }
+                let bits: u8 = r.read("modifiers")?;
+                if bits >> 4 != 0 {
+                    return Err(invalid("modifiers", format!("{bits} are not modifiers")));
+                }
+                Ok(Binding::Key(
+                    key,
+                    Modifiers {
+                        shift: bits & 1 != 0,
+                        ctrl: bits & 2 != 0,
+                        alt: bits & 4 != 0,
+                        super_key: bits & 8 != 0,
+                    },
+                ))
+            }
+            1 => match r.read::<u8>("button")? {
+                0 => Ok(Binding::Mouse(MouseButton::Left)),
+                1 => Ok(Binding::Mouse(MouseButton::Right)),
+                2 => Ok(Binding::Mouse(MouseButton::Middle)),
+                button => Err(invalid("button", format!("{button} is not a mouse button"))),
+            },
+            2 => {
+                let button: u8 = r.read("button")?;
+                GamepadButton::ALL
+                    .get(button as usize)
+                    .map(|button| Binding::Gamepad(*button))
+                    .ok_or_else(|| invalid("button", format!("{button} is not a gamepad button")))
+            }
+            kind => Err(invalid("kind", format!("{kind} is not a binding kind"))),
+        }
+    }
+}
+
+impl Encode for InputMap {
+    fn encode(&self, w: &mut Writer) {
+        for bindings in &self.bindings {
+            w.write(bindings);
+        }
+    }
+}
+
+impl Decode for InputMap {
+    const MIN_SIZE: usize = ACTION_COUNT * Vec::<Binding>::MIN_SIZE;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        let mut map = Self::default();
+        for action in Action::ALL {
+            let offset = r.get_offset();
+            let bindings = r.read_vec(action.name(), MAX_BINDINGS_PER_ACTION)?;
+            if bindings.is_empty() {
+                return Err(DecodeError::new(
+                    offset,
+                    DecodeErrorKind::InvalidValue(String::from("no bindings")),
+                )
+                .in_field(action.name()));
+            }
+            map.bindings[action as usize] = bindings;
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agnostic_interface::headless_impl::HeadlessGame;
+    use crate::serialization::{from_bytes, to_bytes};
+
+    #[test]
+    fn test_input_map() {
+        let (mut gi, controller) = HeadlessGame::new_boxed(800, 600);
+        let mut map = InputMap::default();
+        assert_eq!(
+            map.describe(Action::Create),
+            "Create: Left click, Pad FaceDown"
+        );
+
+        assert!(map.toggle(Action::Save, Binding::key(Key::F5)));
+        controller.hold_key(Key::F5);
+        assert_eq!(map.pressed(gi.as_mut(), Action::Save), Ok(true));
+        assert_eq!(map.pressed(gi.as_mut(), Action::Load), Ok(false));
+        assert_eq!(poll_binding(gi.as_mut()), Ok(Some(Binding::key(Key::F5))));
+
+        assert!(map.toggle(Action::Save, Binding::key(Key::S)));
+        assert!(!map.toggle(Action::Save, Binding::key(Key::F5)));
+        assert_eq!(map.describe(Action::Save), "Save: F5");
+
+        // a bare key doesn't fire while a modifier is held
+        gi.begin_drawing().unwrap();
+        gi.end_drawing().unwrap();
+        controller.hold_key(Key::LeftControl);
+        controller.hold_key(Key::R);
+        assert_eq!(map.pressed(gi.as_mut(), Action::Reset), Ok(false));
+        let ctrl_r = Binding::Key(
+            Key::R,
+            Modifiers {
+                ctrl: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(poll_binding(gi.as_mut()), Ok(Some(ctrl_r)));
+        assert!(map.toggle(Action::Reset, ctrl_r));
+        assert_eq!(map.pressed(gi.as_mut(), Action::Reset), Ok(true));
+        assert_eq!(map.describe(Action::Reset), "Reset: R, Ctrl+R");
+
+        controller.press_gamepad_button(1, GamepadButton::FaceDown);
+        assert_eq!(
+            map.pressed_binding(gi.as_mut(), Action::Create),
+            Ok(Some(Binding::Gamepad(GamepadButton::FaceDown)))
+        );
+        assert!(map.toggle(Action::Load, Binding::Gamepad(GamepadButton::Select)));
+
+        let bytes = to_bytes(&map);
+        let (des_map, size) =
+            from_bytes::<InputMap>(&bytes).expect("Should be able to deserialize InputMap!");
+        assert_eq!(map, des_map);
+        assert_eq!(bytes.len(), size);
+    }
+}
@@ -0,0 +1,188 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const USAGE: &str = "Usage: ld45_bin [OPTIONS]
+
+Options:
+  --width <PIXELS>    Window width (default 800)
+  --height <PIXELS>   Window height (default 600)
+  --fullscreen        Start in fullscreen
+  --fps <FPS>         Target frames per second (default 60)
+  --assets <DIR>      Directory containing the game assets (default \"static\")
+  --pack <FILE>       Load the game assets from a pack file instead
+  --write-pack <FILE> Pack the asset directory into a file and exit
+  --dev               Reload images, fonts and shaders when their files change
+  --data-dir <DIR>    Directory saves are stored in (also set by LD45_DATA_DIR)
+  --seed <NUMBER>     Seed for the random number generator
+  --mute              Mute all audio
+  --skip-intro        Skip the intro and start creating right away
+  --load <FILE>       Load a save or autosave file at startup
+  -h, --help          Print this help";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LaunchOptions {
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+    pub target_fps: u32,
+    pub asset_root: PathBuf,
+    pub pack: Option<PathBuf>,
+    pub write_pack: Option<PathBuf>,
+    pub dev: bool,
+    pub data_dir: Option<PathBuf>,
+    pub seed: Option<u64>,
+    pub mute: bool,
+    pub skip_intro: bool,
+    pub load: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Default for LaunchOptions {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 600,
+            fullscreen: false,
+            target_fps: 60,
+            asset_root: PathBuf::from("static"),
+            pack: None,
+            write_pack: None,
+            dev: false,
+            data_dir: None,
+            seed: None,
+            mute: false,
+            skip_intro: false,
+            load: None,
+            help: false,
+        }
+    }
+}
+
+impl LaunchOptions {
+    /// Parses arguments without the program name. Values may be given as
+    /// `--fps 30` or `--fps=30`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (name, mut inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => {
+                    (name.to_owned(), Some(value.to_owned()))
+                }
+                _ => (arg, None),
+            };
+            let mut value = |value_name: &str| {
+                inline_value
+                    .take()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("\"{name}\" requires a {value_name}"))
+            };
+
+            match name.as_str() {
+                "--width" => options.width = parse_value(&name, &value("number")?)?,
+                "--height" => options.height = parse_value(&name, &value("number")?)?,
+                "--fullscreen" => options.fullscreen = true,
+                "--fps" => options.target_fps = parse_value(&name, &value("number")?)?,
+                "--assets" => options.asset_root = PathBuf::from(value("path")?),
+                "--pack" => options.pack = Some(PathBuf::from(value("path")?)),
+                "--write-pack" => options.write_pack = Some(PathBuf::from(value("path")?)),
+                "--dev" => options.dev = true,
+                "--data-dir" => options.data_dir = Some(PathBuf::from(value("path")?)),
+                "--seed" => options.seed = Some(parse_value(&name, &value("number")?)?),
+                "--mute" => options.mute = true,
+                "--skip-intro" => options.skip_intro = true,
+                "--load" => options.load = Some(PathBuf::from(value("path")?)),
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("Unknown argument \"{name}\"")),
+            }
+            if inline_value.is_some() {
+                return Err(format!("\"{name}\" does not take a value"));
+            }
+        }
+
+        if options.width == 0 || options.height == 0 {
+            return Err(String::from("Window size must not be zero"));
+        }
+
+        Ok(options)
+    }
+}
+
+fn parse_value<T: FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value \"{value}\" for \"{name}\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<LaunchOptions, String> {
+        LaunchOptions::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse(&[]), Ok(LaunchOptions::default()));
+
+        let options = parse(&[
+            "--width",
+            "1024",
+            "--height=768",
+            "--fullscreen",
+            "--fps",
+            "30",
+            "--assets",
+            "assets",
+            "--pack=assets.pack",
+            "--dev",
+            "--data-dir=saves",
+            "--seed",
+            "42",
+            "--mute",
+            "--skip-intro",
+            "--load",
+            "bug.bin",
+        ])
+        .unwrap();
+        assert_eq!(
+            options,
+            LaunchOptions {
+                width: 1024,
+                height: 768,
+                fullscreen: true,
+                target_fps: 30,
+                asset_root: PathBuf::from("assets"),
+                pack: Some(PathBuf::from("assets.pack")),
+                write_pack: None,
+                dev: true,
+                data_dir: Some(PathBuf::from("saves")),
+                seed: Some(42),
+                mute: true,
+                skip_intro: true,
+                load: Some(PathBuf::from("bug.bin")),
+                help: false,
+            }
+        );
+
+        assert!(parse(&["-h"]).unwrap().help);
+        assert_eq!(
+            parse(&["--seed"]),
+            Err(String::from("\"--seed\" requires a number"))
+        );
+        assert_eq!(
+            parse(&["--fps", "fast"]),
+            Err(String::from("Invalid value \"fast\" for \"--fps\""))
+        );
+        assert_eq!(
+            parse(&["--fullscreen=1"]),
+            Err(String::from("\"--fullscreen\" does not take a value"))
+        );
+        assert_eq!(
+            parse(&["--fullscreen", "1"]),
+            Err(String::from("Unknown argument \"1\""))
+        );
+        assert!(parse(&["--width", "0"]).is_err());
+    }
+}
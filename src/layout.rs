@@ -0,0 +1,248 @@
+use crate::faux_quicksilver::{Rectangle, Vector};
+
+/// Where a stack sits vertically on the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    Top,
+    Center,
+    Bottom,
+}
+
+/// Where each row of a stack sits horizontally on the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    /// Where something of width `w` starts in a space of width `space`.
+    pub fn offset(self, space: f32, w: f32) -> f32 {
+        match self {
+            Align::Left => 0.0,
+            Align::Center => (space - w) / 2.0,
+            Align::Right => space - w,
+        }
+    }
+}
+
+/// The measured content plus `padding` on each side, but at least `min`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Size {
+    pub padding: Vector,
+    pub min: Vector,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub item: usize,
+    pub size: Size,
+}
+
+impl Cell {
+    pub fn fit(item: usize) -> Self {
+        Self {
+            item,
+            size: Size {
+                padding: Vector::new(0.0, 0.0),
+                min: Vector::new(0.0, 0.0),
+            },
+        }
+    }
+
+    /// Sized like a button: padded text that is at least `w` by `h`.
+    pub fn button(item: usize, w: f32, h: f32) -> Self {
+        Self {
+            item,
+            size: Size {
+                padding: Vector::new(20.0, 10.0),
+                min: Vector::new(w, h),
+            },
+        }
+    }
+
+    fn size(&self, measure: &mut dyn FnMut(usize) -> Vector) -> Vector {
+        let Size { padding, min } = self.size;
+        let content = measure(self.item);
+        Vector::new(
+            (content.x + padding.x * 2.0).max(min.x),
+            (content.y + padding.y * 2.0).max(min.y),
+        )
+    }
+}
+
+/// Rows of cells stacked from top to bottom and anchored to a side of the
+/// screen. Cells in a row are laid out left to right and centered vertically.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stack {
+    anchor: Anchor,
+    align: Align,
+    margin: Vector,
+    spacing: Vector,
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Stack {
+    pub fn new(anchor: Anchor, align: Align) -> Self {
+        Self {
+            anchor,
+            align,
+            margin: Vector::new(0.0, 0.0),
+            spacing: Vector::new(0.0, 0.0),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Distance kept from the screen edges the stack is anchored or aligned to.
+    pub fn margin(mut self, x: f32, y: f32) -> Self {
+        self.margin = Vector::new(x, y);
+        self
+    }
+
+    /// Space between cells of a row and between rows.
+    pub fn spacing(mut self, x: f32, y: f32) -> Self {
+        self.spacing = Vector::new(x, y);
+        self
+    }
+
+    pub fn row(mut self, cells: Vec<Cell>) -> Self {
+        self.rows.push(cells);
+        self
+    }
+
+    pub fn cell(self, cell: Cell) -> Self {
+        self.row(vec![cell])
+    }
+
+    /// Places every cell on a screen of the given size, `measure` returns the
+    /// content size of an item.
+    pub fn layout(
+        &self,
+        screen: Vector,
+        measure: &mut dyn FnMut(usize) -> Vector,
+    ) -> Vec<(usize, Rectangle)> {
+        let rows: Vec<Vec<(usize, Vector)>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| (cell.item, cell.size(measure)))
+                    .collect()
+            })
+            .collect();
+        let row_sizes: Vec<Vector> = rows
+            .iter()
+            .map(|row| {
+                let w = row.iter().map(|(_, size)| size.x).sum::<f32>()
+                    + self.spacing.x * row.len().saturating_sub(1) as f32;
+                let h = row.iter().map(|(_, size)| size.y).fold(0.0, f32::max);
+                Vector::new(w, h)
+            })
+            .collect();
+        let height = row_sizes.iter().map(|size| size.y).sum::<f32>()
+            + self.spacing.y * rows.len().saturating_sub(1) as f32;
+
+        let mut y = match self.anchor {
+            Anchor::Top => self.margin.y,
+            Anchor::Center => (screen.y - height) / 2.0,
+            Anchor::Bottom => screen.y - self.margin.y - height,
+        };
+        let mut rects = Vec::new();
+        for (row, row_size) in rows.iter().zip(row_sizes) {
+            let mut x = match self.align {
+                Align::Left => self.margin.x,
+                Align::Center => (screen.x - row_size.x) / 2.0,
+                Align::Right => screen.x - self.margin.x - row_size.x,
+            };
+            for (item, size) in row {
+                rects.push((
+                    *item,
+                    Rectangle::new(x, y + (row_size.y - size.y) / 2.0, size.x, size.y),
+                ));
+                x += size.x + self.spacing.x;
+            }
+            y += row_size.y + self.spacing.y;
+        }
+        rects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: Vector = Vector { x: 800.0, y: 600.0 };
+
+    // Items are as wide as ten times their index and 20 high.
+    fn measure(item: usize) -> Vector {
+        Vector::new(item as f32 * 10.0, 20.0)
+    }
+
+    #[test]
+    fn test_anchors() {
+        let stack = Stack::new(Anchor::Top, Align::Left)
+            .margin(50.0, 40.0)
+            .spacing(0.0, 20.0)
+            .cell(Cell::fit(3))
+            .cell(Cell::button(0, 100.0, 50.0));
+        assert_eq!(
+            stack.layout(SCREEN, &mut measure),
+            vec![
+                (3, Rectangle::new(50.0, 40.0, 30.0, 20.0)),
+                (0, Rectangle::new(50.0, 80.0, 100.0, 50.0)),
+            ]
+        );
+
+        let stack = Stack {
+            anchor: Anchor::Bottom,
+            align: Align::Right,
+            ..stack
+        };
+        assert_eq!(
+            stack.layout(SCREEN, &mut measure),
+            vec![
+                (3, Rectangle::new(720.0, 470.0, 30.0, 20.0)),
+                (0, Rectangle::new(650.0, 510.0, 100.0, 50.0)),
+            ]
+        );
+
+        let stack = Stack {
+            anchor: Anchor::Center,
+            align: Align::Center,
+            ..stack
+        };
+        assert_eq!(
+            stack.layout(SCREEN, &mut measure),
+            vec![
+                (3, Rectangle::new(385.0, 255.0, 30.0, 20.0)),
+                (0, Rectangle::new(350.0, 295.0, 100.0, 50.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rows_and_buttons() {
+        let stack = Stack::new(Anchor::Top, Align::Center)
+            .margin(0.0, 30.0)
+            .spacing(200.0, 35.0)
+            .row(vec![Cell::button(0, 200.0, 85.0), Cell::fit(1)])
+            .row(vec![
+                Cell::button(2, 100.0, 50.0),
+                Cell::button(30, 100.0, 50.0),
+            ]);
+        assert_eq!(
+            stack.layout(SCREEN, &mut measure),
+            vec![
+                (0, Rectangle::new(195.0, 30.0, 200.0, 85.0)),
+                (1, Rectangle::new(595.0, 62.5, 10.0, 20.0)),
+                (2, Rectangle::new(80.0, 150.0, 100.0, 50.0)),
+                (30, Rectangle::new(380.0, 150.0, 340.0, 50.0)),
+            ]
+        );
+        assert_eq!(
+            Stack::new(Anchor::Center, Align::Center).layout(SCREEN, &mut measure),
+            vec![]
+        );
+    }
+}
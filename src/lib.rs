@@ -1,18 +1,96 @@
 pub mod agnostic_interface;
+#[cfg(not(target_family = "wasm"))]
+mod data_dir;
 mod faux_quicksilver;
+mod gestures;
+mod input;
+pub mod launch_options;
+mod layout;
+mod manifest;
 mod original_impl;
+mod save_slots;
+mod serialization;
+mod settings;
+mod slot_map;
+mod text;
+pub mod vfs;
+#[cfg(target_family = "wasm")]
 mod wasm_helpers;
+mod watcher;
+
+use std::path::PathBuf;
 
 use agnostic_interface::raylib_impl::RaylibGame;
+use agnostic_interface::GameInterface;
 use faux_quicksilver::Window;
+use launch_options::LaunchOptions;
 use original_impl::GameState;
+use vfs::{Source, Vfs};
+use watcher::Watcher;
 
 pub struct WasmState {
     pub window: Box<Window>,
     pub game_state: Box<GameState>,
+    // Only in development mode.
+    watcher: Option<Watcher>,
 }
 
 impl WasmState {
+    pub fn new(game_interface: Box<dyn GameInterface>) -> Result<Self, String> {
+        Self::with_options(game_interface, &LaunchOptions::default())
+    }
+
+    pub fn with_options(
+        mut game_interface: Box<dyn GameInterface>,
+        options: &LaunchOptions,
+    ) -> Result<Self, String> {
+        game_interface.set_target_fps(options.target_fps)?;
+
+        let mut window = Box::new(Window::new(game_interface));
+        let game_state = Box::new(GameState::with_options(&mut window, options)?);
+        let watcher = options.dev.then(|| Watcher::new(&options.asset_root));
+        if options.dev {
+            eprintln!("Holding {}", window.get_gi().resource_report());
+        }
+
+        Ok(Self {
+            window,
+            game_state,
+            watcher,
+        })
+    }
+
+    fn reload_changed_assets(&mut self) {
+        let Some(watcher) = &mut self.watcher else {
+            return;
+        };
+        let gi = self.window.get_gi_mut();
+        let mut reloaded = false;
+        for path in watcher.poll(gi.get_delta_time()) {
+            match gi.reload(&path) {
+                Ok(true) => {
+                    eprintln!("Reloaded \"{}\"", path.display());
+                    self.game_state.fonts_reloaded();
+                    reloaded = true;
+                }
+                Ok(false) => (),
+                // the old asset stays in use until the file is fixed
+                Err(e) => eprintln!("WARNING: {e}"),
+            }
+        }
+        // shows whether reloading leaks the replaced assets
+        if reloaded {
+            eprintln!("Holding {}", gi.resource_report());
+        }
+    }
+
+    pub fn iterate(&mut self) -> Result<(), String> {
+        self.reload_changed_assets();
+        self.window.update_music()?;
+        self.game_state.update(&mut self.window)?;
+        self.game_state.draw(&mut self.window)
+    }
+
     pub fn get_window_mut(&mut self) -> &mut Window {
         &mut self.window
     }
@@ -22,27 +100,55 @@ impl WasmState {
     }
 }
 
+/// Assets are read from a pack given with `--pack`, then from the ones built
+/// into the binary and last from the asset directory, which comes first in
+/// development mode. The built in assets are left out when `--assets` names
+/// another directory.
+fn mount_assets(options: &LaunchOptions) -> Result<Vfs, String> {
+    let mut vfs = Vfs::default();
+    if let Some(embedded) = Source::embedded() {
+        if options.asset_root == LaunchOptions::default().asset_root {
+            vfs.mount(&options.asset_root, embedded);
+        }
+    }
+    if let Some(pack) = &options.pack {
+        let data = std::fs::read(pack)
+            .map_err(|e| format!("Failed to read \"{}\": {e}", pack.display()))?;
+        vfs.mount(&options.asset_root, Source::read_pack(&data)?);
+    }
+    if options.dev {
+        // edited files are used over the packed ones
+        vfs.mount(PathBuf::new(), Source::Dir(PathBuf::new()));
+    }
+    Ok(vfs)
+}
+
+pub fn ld45_initialize_with_options(
+    options: &LaunchOptions,
+) -> Result<*mut ::std::os::raw::c_void, String> {
+    #[cfg(not(target_family = "wasm"))]
+    save_slots::Storage::open(options.data_dir.clone()).migrate_saves();
+
+    let game_interface = RaylibGame::new_boxed(
+        options.width,
+        options.height,
+        &options.asset_root,
+        mount_assets(options)?,
+    );
+    let wasm_state = WasmState::with_options(game_interface, options)?;
+
+    Ok(Box::into_raw(Box::new(wasm_state)) as *mut ::std::os::raw::c_void)
+}
+
 #[no_mangle]
 pub extern "C" fn ld45_initialize() -> *mut ::std::os::raw::c_void {
-    let game_interface = RaylibGame::new_boxed(800, 600);
-    let mut window = Box::new(Window::new(game_interface));
-    let game_state = Box::new(GameState::new(&mut window).unwrap());
-
-    Box::into_raw(Box::new(WasmState { window, game_state })) as *mut ::std::os::raw::c_void
+    ld45_initialize_with_options(&LaunchOptions::default()).unwrap()
 }
 
 #[no_mangle]
 pub extern "C" fn ld45_iterate(context: *mut ::std::os::raw::c_void) {
     let state_ptr = context as *mut WasmState;
     unsafe {
-        (*state_ptr).get_window_mut().update_music().unwrap();
-        (*state_ptr)
-            .get_state_mut()
-            .update((*state_ptr).get_window_mut())
-            .unwrap();
-        (*state_ptr)
-            .get_state_mut()
-            .draw((*state_ptr).get_window_mut())
-            .unwrap();
+        (*state_ptr).iterate().unwrap();
     }
 }
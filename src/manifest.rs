@@ -0,0 +1,242 @@
+use std::path::Path;
+
+use crate::agnostic_interface::FontOptions;
+use crate::vfs::Vfs;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AssetKind {
+    Image,
+    Font(FontOptions),
+    Sound,
+    Music {
+        looping: bool,
+    },
+    /// The entry's path is the vertex shader.
+    Shader {
+        fragment: String,
+    },
+}
+
+impl AssetKind {
+    /// Sounds and music can only be loaded once the audio device is ready.
+    pub fn is_audio(&self) -> bool {
+        matches!(self, AssetKind::Sound | AssetKind::Music { .. })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetEntry {
+    pub kind: AssetKind,
+    pub name: String,
+    /// Relative to the asset root.
+    pub path: String,
+}
+
+/// Every asset the game loads, one per line as `<kind> <name> <path>`
+/// followed by options. Empty lines and lines starting with `#` are skipped.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Manifest {
+    pub entries: Vec<AssetEntry>,
+}
+
+fn parse_range(s: &str) -> Result<std::ops::RangeInclusive<char>, String> {
+    match s {
+        "ascii" => return Ok(FontOptions::ASCII),
+        "latin-1" => return Ok(FontOptions::LATIN_1),
+        "latin-extended-a" => return Ok(FontOptions::LATIN_EXTENDED_A),
+        "greek" => return Ok(FontOptions::GREEK),
+        "cyrillic" => return Ok(FontOptions::CYRILLIC),
+        "punctuation" => return Ok(FontOptions::PUNCTUATION),
+        _ => (),
+    }
+    let codepoint = |s: &str| {
+        s.strip_prefix("U+")
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .and_then(char::from_u32)
+            .ok_or_else(|| format!("invalid codepoint \"{s}\""))
+    };
+    let (first, last) = s.split_once('-').unwrap_or((s, s));
+    Ok(codepoint(first)?..=codepoint(last)?)
+}
+
+fn parse_entry(words: &[&str]) -> Result<AssetEntry, String> {
+    let [kind, name, path, options @ ..] = words else {
+        return Err("expected <kind> <name> <path>".into());
+    };
+    let mut kind = match *kind {
+        "image" => AssetKind::Image,
+        "font" => AssetKind::Font(FontOptions::new(32)),
+        "sound" => AssetKind::Sound,
+        "music" => AssetKind::Music { looping: false },
+        "shader" => AssetKind::Shader {
+            fragment: String::new(),
+        },
+        _ => return Err(format!("unknown asset kind \"{kind}\"")),
+    };
+    for option in options {
+        let (key, value) = option.split_once('=').unwrap_or((option, ""));
+        match (&mut kind, key) {
+            (AssetKind::Font(font), "size") => {
+                font.size = value
+                    .parse()
+                    .map_err(|_| format!("invalid font size \"{value}\""))?;
+            }
+            (AssetKind::Font(font), "ranges") => {
+                for range in value.split(',') {
+                    font.ranges.push(parse_range(range)?);
+                }
+            }
+            (AssetKind::Font(font), "fallback") => font.fallbacks.push(value.into()),
+            (AssetKind::Music { looping }, "loop") => *looping = true,
+            (AssetKind::Shader { fragment }, "fragment") => *fragment = value.into(),
+            _ => return Err(format!("unknown option \"{option}\"")),
+        }
+    }
+    if let AssetKind::Shader { fragment } = &kind {
+        if fragment.is_empty() {
+            return Err("shaders need a fragment=<path> option".into());
+        }
+    }
+    Ok(AssetEntry {
+        kind,
+        name: name.to_string(),
+        path: path.to_string(),
+    })
+}
+
+impl Manifest {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut entries: Vec<AssetEntry> = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let entry = parse_entry(&words).map_err(|e| format!("Manifest line {}: {e}", i + 1))?;
+            if entries.iter().any(|other| other.name == entry.name) {
+                return Err(format!(
+                    "Manifest line {}: \"{}\" is listed twice",
+                    i + 1,
+                    entry.name
+                ));
+            }
+            entries.push(entry);
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn read(vfs: &Vfs, path: &Path) -> Result<Self, String> {
+        Self::parse(&vfs.read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agnostic_interface::headless_impl::HeadlessGame;
+    use crate::faux_quicksilver::Window;
+
+    #[test]
+    fn test_parse() {
+        let manifest = Manifest::parse(
+            "# comment\n\
+             \n\
+             image star star.png\n\
+             font text ClearSans-Regular.ttf size=45 ranges=greek,U+3040-U+309F,U+2026 fallback=b.ttf\n\
+             music theme music2.mp3 loop\n\
+             shader camera camera.vs fragment=simple.fs\n",
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.entries,
+            vec![
+                AssetEntry {
+                    kind: AssetKind::Image,
+                    name: "star".into(),
+                    path: "star.png".into(),
+                },
+                AssetEntry {
+                    kind: AssetKind::Font(
+                        FontOptions::new(45)
+                            .with_range(FontOptions::GREEK)
+                            .with_range('\u{3040}'..='\u{309F}')
+                            .with_range('…'..='…')
+                            .with_fallback("b.ttf")
+                    ),
+                    name: "text".into(),
+                    path: "ClearSans-Regular.ttf".into(),
+                },
+                AssetEntry {
+                    kind: AssetKind::Music { looping: true },
+                    name: "theme".into(),
+                    path: "music2.mp3".into(),
+                },
+                AssetEntry {
+                    kind: AssetKind::Shader {
+                        fragment: "simple.fs".into()
+                    },
+                    name: "camera".into(),
+                    path: "camera.vs".into(),
+                },
+            ]
+        );
+
+        for (manifest, error) in [
+            (
+                "image star",
+                "Manifest line 1: expected <kind> <name> <path>",
+            ),
+            (
+                "\nvideo intro intro.mp4",
+                "Manifest line 2: unknown asset kind \"video\"",
+            ),
+            (
+                "sound boom boom.mp3 loop",
+                "Manifest line 1: unknown option \"loop\"",
+            ),
+            (
+                "font f f.ttf ranges=U+D800",
+                "Manifest line 1: invalid codepoint \"U+D800\"",
+            ),
+            (
+                "shader s s.vs",
+                "Manifest line 1: shaders need a fragment=<path> option",
+            ),
+            (
+                "sound a a.mp3\nmusic a b.mp3",
+                "Manifest line 2: \"a\" is listed twice",
+            ),
+        ] {
+            assert_eq!(Manifest::parse(manifest), Err(error.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_load_reports_every_missing_file() {
+        let (gi, _controller) = HeadlessGame::new_boxed(800, 600);
+        let mut window = Window::new(gi);
+        let manifest =
+            Manifest::read(window.get_gi().vfs(), Path::new("static/manifest.txt")).unwrap();
+        window
+            .load_manifest(&manifest, Path::new("static"), false)
+            .unwrap();
+        assert!(window.image("star").is_ok());
+        assert!(window.sound("boom").is_err());
+
+        let manifest = Manifest::parse(
+            "image star star.png\n\
+             image missing missing.png\n\
+             font missing_font missing.ttf\n\
+             sound boom boom.mp3\n\
+             sound missing_sound missing.mp3\n",
+        )
+        .unwrap();
+        let error = window
+            .load_manifest(&manifest, Path::new("static"), false)
+            .unwrap_err();
+        assert!(error.contains("missing.png"), "{error}");
+        assert!(error.contains("missing.ttf"), "{error}");
+        assert!(!error.contains("missing.mp3"), "{error}");
+    }
+}
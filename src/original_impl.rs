@@ -1,17 +1,35 @@
-#[cfg(not(target_family = "wasm"))]
-use std::fs::File;
-use std::{io::Result as IOResult, path::PathBuf, str::FromStr};
-
-use crate::agnostic_interface::CameraInterface;
-use crate::faux_quicksilver::{Circle, Color, Rectangle, Transform, Vector, Window};
+use std::{
+    cell::RefCell,
+    io::Result as IOResult,
+    path::{Path, PathBuf},
+};
+
+use crate::agnostic_interface::{
+    CameraInterface, FontInterface, GamepadButton, GamepadStick, Key, MouseButton, ParagraphStyle,
+    PointerEvent, Viewport,
+};
+use crate::faux_quicksilver::{
+    Circle, Color, FontHandle, ImageHandle, MusicHandle, Rectangle, SoundHandle, Transform, Vector,
+    Window,
+};
+use crate::gestures::{Gesture, GestureRecognizer};
+use crate::input::{self, Action, Binding, InputMap, ACTION_COUNT};
+use crate::launch_options::LaunchOptions;
+use crate::layout::{Align, Anchor, Cell, Stack};
+use crate::manifest::Manifest;
+use crate::save_slots::{self, RecoveryScan, SlotMetadata, Snapshot, Storage, SAVE_SLOT_COUNT};
+use crate::serialization::{
+    crc32, from_bytes, to_bytes, Decode, DecodeError, DecodeErrorKind, Encode, Reader, Writer,
+    DEFAULT_LEN_SIZE,
+};
+use crate::settings::{self, Settings, SETTINGS_ITEM_COUNT};
+use crate::text;
 use rand::prelude::*;
-#[cfg(target_family = "wasm")]
 use std::sync::mpsc::{Receiver, TryRecvError};
 
 const WIDTH_F: f32 = 800.0;
 const HEIGHT_F: f32 = 600.0;
 //const MUSIC2_LENGTH: f32 = 2.0 * 60.0 * 1000.0;
-const TEXT_RATE: f32 = 0.1;
 const PP_GEN_RATE: f32 = 0.075;
 const PARTICLE_RAND_VEL_RANGE: f32 = 80.0;
 const PARTICLE_RAND_VEL_DIST: f32 = 0.2828427; // dist where x and y = 0.2
@@ -19,11 +37,60 @@ const PARTICLE_RAND_ROT_RANGE: f32 = 5.0;
 const JOINING_OPACITY_RATE: f32 = 0.13;
 const JOINING_FAR_DIST: f32 = 700.0;
 const JOINING_NEAR_DIST: f32 = 150.0;
-const DOUBLE_CLICK_TIME: f32 = 0.350;
 const SL_NOTIF_TIME: f32 = 7.0;
 const MAX_MOONS: usize = 5;
-#[cfg(not(target_family = "wasm"))]
-const SAVE_FILENAME: &str = "LudumDare45_OneAndAll_SaveFile.bin";
+const NARRATION_PITCH: f32 = 40.0;
+const NARRATION_WIDTH: f32 = WIDTH_F - 100.0;
+const AUTOSAVE_TIME: f32 = 60.0;
+const MUSIC_VOLUME: f32 = 0.5;
+const REDUCED_PARTICLES_FACTOR: usize = 2;
+const STICK_DEADZONE: f32 = 0.25;
+const STICK_MOVE_SPEED: f32 = 300.0;
+const RETICLE_SPEED: f32 = 400.0;
+const RETICLE_RANGE: f32 = 250.0;
+const ZOOM_STEP: f32 = 1.1;
+const ZOOM_MIN: f32 = 0.25;
+const ZOOM_MAX: f32 = 4.0;
+// A right click that drags further than this pans instead of creating.
+const DRAG_CLICK_DISTANCE: f32 = 5.0;
+
+fn reduced_count(count: usize, reduced_particles: bool) -> usize {
+    if reduced_particles {
+        count.div_ceil(REDUCED_PARTICLES_FACTOR)
+    } else {
+        count
+    }
+}
+
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_os_rng());
+}
+
+// All randomness goes through one seedable generator so a seed reproduces a
+// session.
+struct GameRng;
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        RNG.with_borrow_mut(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        RNG.with_borrow_mut(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        RNG.with_borrow_mut(|rng| rng.fill_bytes(dst))
+    }
+}
+
+fn game_rng() -> GameRng {
+    GameRng
+}
+
+fn seed_game_rng(seed: u64) {
+    RNG.with_borrow_mut(|rng| *rng = StdRng::seed_from_u64(seed));
+}
 
 fn interp_sq_inv(x: f32) -> f32 {
     if x < 0.0 {
@@ -44,11 +111,19 @@ fn interp_sq(x: f32) -> f32 {
     x * x
 }
 
+// Wrapped lines of appearing text keep the same pitch as the narration rows.
+fn narration_style(max_width: f32) -> ParagraphStyle {
+    ParagraphStyle {
+        line_spacing: NARRATION_PITCH - 20.0,
+        ..ParagraphStyle::new(max_width)
+    }
+}
+
 #[allow(unused_variables)]
 #[allow(dead_code)]
 enum MenuItemType {
     Button {
-        text: &'static str,
+        text: String,
         text_c: Color,
         h_c: Color,
         c: Color,
@@ -59,11 +134,15 @@ enum MenuItemType {
         text_size: f32,
         text_c: Color,
         timer: f32,
+        max_width: f32,
+        // The full text wrapped, set when the menu is laid out.
+        lines: Vec<String>,
     },
     InstantText {
-        text: &'static str,
+        text: String,
         text_size: f32,
         text_color: Color,
+        max_width: f32,
     },
     Pause {
         timer: f32,
@@ -87,32 +166,134 @@ impl MenuItem {
     fn is_inside(&self, x: f32, y: f32) -> bool {
         x >= self.x && x < self.x + self.w && y >= self.y && y < self.y + self.h
     }
+
+    /// Size of the item's full text.
+    fn measure(&self, font: &dyn FontInterface) -> Result<Vector, String> {
+        match &self.item_type {
+            MenuItemType::Button { text, .. } => font.measure(text, 20),
+            MenuItemType::AppearingText {
+                text, max_width, ..
+            } => font.measure_paragraph(text, 20, narration_style(*max_width)),
+            MenuItemType::InstantText {
+                text,
+                text_size,
+                max_width,
+                ..
+            } => font.measure_paragraph(
+                text,
+                text_size.round() as u32,
+                ParagraphStyle::new(*max_width),
+            ),
+            MenuItemType::Pause { .. } => Ok(Vector::new(0.0, 0.0)),
+        }
+    }
 }
 
+// Items are kept in the order they appear in, the stacks decide where they are
+// drawn.
 struct Menu {
     items: Vec<MenuItem>,
+    stacks: Vec<Stack>,
+    // The screen size the items were last placed on.
+    laid_out: Option<Vector>,
 }
 
 #[allow(clippy::too_many_arguments)]
 impl Menu {
+    fn new() -> Menu {
+        Menu {
+            items: Vec::new(),
+            stacks: Vec::new(),
+            laid_out: None,
+        }
+    }
+
+    fn add(&mut self, item: MenuItem) -> usize {
+        self.items.push(item);
+        self.items.len() - 1
+    }
+
+    fn stack(&mut self, stack: Stack) {
+        self.stacks.push(stack);
+    }
+
+    /// Places the items on a screen of the given size, unless they already
+    /// are.
+    fn relayout(&mut self, screen: Vector, font: &dyn FontInterface) -> Result<(), String> {
+        if self.laid_out == Some(screen) {
+            return Ok(());
+        }
+        let sizes = self
+            .items
+            .iter()
+            .map(|mi| mi.measure(font))
+            .collect::<Result<Vec<_>, String>>()?;
+        for stack in &self.stacks {
+            for (i, rect) in stack.layout(screen, &mut |i| sizes[i]) {
+                let mi = &mut self.items[i];
+                mi.x = rect.x;
+                mi.y = rect.y;
+                mi.w = rect.w;
+                mi.h = rect.h;
+            }
+        }
+        for mi in &mut self.items {
+            if let MenuItemType::AppearingText {
+                text,
+                max_width,
+                lines,
+                ..
+            } = &mut mi.item_type
+            {
+                *lines = text::wrap(text, *max_width, &mut |line| Ok(font.measure(line, 20)?.x))?;
+            }
+        }
+        self.laid_out = Some(screen);
+        Ok(())
+    }
+
+    fn set_button_text(&mut self, idx: usize, s: String) {
+        if let MenuItemType::Button { text, .. } = &mut self.items[idx].item_type {
+            *text = s;
+            self.laid_out = None;
+        }
+    }
+
+    /// Moves focus to the next or previous button and returns its index.
+    fn move_focus(&mut self, forward: bool) -> Option<usize> {
+        let buttons: Vec<usize> = (0..self.items.len())
+            .filter(|i| matches!(self.items[*i].item_type, MenuItemType::Button { .. }))
+            .collect();
+        if buttons.is_empty() {
+            return None;
+        }
+        let next = match buttons.iter().position(|i| self.items[*i].is_focus) {
+            Some(current) if forward => (current + 1) % buttons.len(),
+            Some(current) => (current + buttons.len() - 1) % buttons.len(),
+            None if forward => 0,
+            None => buttons.len() - 1,
+        };
+        for mi in &mut self.items {
+            mi.is_focus = false;
+        }
+        self.items[buttons[next]].is_focus = true;
+        Some(buttons[next])
+    }
+
     fn button(
-        x: f32,
-        y: f32,
-        w: f32,
-        h: f32,
-        s: &'static str,
+        s: impl Into<String>,
         t_color: Color,
         box_color: Color,
         boxh_color: Color,
         first: bool,
     ) -> MenuItem {
         MenuItem {
-            x,
-            y,
-            w,
-            h,
+            x: 0.0,
+            y: 0.0,
+            w: 0.0,
+            h: 0.0,
             item_type: MenuItemType::Button {
-                text: s,
+                text: s.into(),
                 text_c: t_color,
                 h_c: boxh_color,
                 c: box_color,
@@ -123,61 +304,200 @@ impl Menu {
         }
     }
 
+    fn cyan_button(s: impl Into<String>) -> MenuItem {
+        Menu::button(
+            s,
+            Color::WHITE,
+            Color::from_rgba(0x33, 0xDD, 0xDD, 255),
+            Color::from_rgba(0x66, 0xFF, 0xFF, 255),
+            true,
+        )
+    }
+
+    fn grey_button(s: impl Into<String>) -> MenuItem {
+        Menu::button(
+            s,
+            Color::WHITE,
+            Color::from_rgba(0x33, 0x33, 0x33, 255),
+            Color::from_rgba(0x66, 0x66, 0x66, 255),
+            true,
+        )
+    }
+
+    fn title(&mut self, y: f32, s: &'static str) {
+        let title = self.add(Menu::paragraph(45.0, WIDTH_F - 140.0, true, s));
+        self.stack(
+            Stack::new(Anchor::Top, Align::Left)
+                .margin(70.0, y)
+                .cell(Cell::fit(title)),
+        );
+    }
+
+    /// Lines of text stacked at the bottom left, like the narration.
+    fn narration(&mut self, margin: Vector, lines: Vec<Vec<usize>>) {
+        let mut stack = Stack::new(Anchor::Bottom, Align::Left)
+            .margin(margin.x, margin.y)
+            .spacing(20.0, NARRATION_PITCH - 20.0);
+        for line in lines {
+            stack = stack.row(line.into_iter().map(Cell::fit).collect());
+        }
+        self.stack(stack);
+    }
+
     fn start() -> Menu {
-        let item = MenuItem {
-            x: WIDTH_F / 2.0 - 120.0,
-            y: 150.0,
-            w: 240.0,
-            h: 150.0,
-            item_type: MenuItemType::Button {
-                text: "Start the Game",
-                text_c: Color::WHITE,
-                h_c: Color::from_rgba(0x66, 0xFF, 0xFF, 255),
-                c: Color::from_rgba(0x33, 0xDD, 0xDD, 255),
-            },
-            is_hover: false,
-            is_focus: false,
-            is_loaded: false,
-        };
+        let mut menu = Menu::new();
+        let start = menu.add(Menu::cyan_button("Start the Game"));
+        let slots = menu.add(Menu::cyan_button("Save Slots"));
+        let settings = menu.add(Menu::cyan_button("Settings"));
+        menu.stack(
+            Stack::new(Anchor::Top, Align::Center)
+                .margin(0.0, 150.0)
+                .spacing(0.0, 20.0)
+                .cell(Cell::button(start, 240.0, 150.0))
+                .cell(Cell::button(slots, 240.0, 60.0))
+                .cell(Cell::button(settings, 240.0, 60.0)),
+        );
+        menu.title(50.0, "One And All - A Ludum Dare 45 Entry");
+        let raylib = menu.add(Menu::instant_text(
+            27.0,
+            true,
+            "Made with Raylib which is licensed with the zlib license",
+        ));
+        let clear_sans = menu.add(Menu::instant_text(
+            27.0,
+            true,
+            "Uses Clear-Sans which is licensed with Apache License Version 2.0",
+        ));
+        menu.stack(
+            Stack::new(Anchor::Bottom, Align::Left)
+                .margin(25.0, 23.0)
+                .spacing(0.0, 23.0)
+                .cell(Cell::fit(raylib))
+                .cell(Cell::fit(clear_sans)),
+        );
+        menu
+    }
 
-        Menu {
-            items: vec![
-                item,
-                Menu::instant_text(
-                    70.0,
-                    50.0,
-                    45.0,
-                    true,
-                    "One And All - A Ludum Dare 45 Entry",
-                ),
-                Menu::instant_text(
-                    25.0,
-                    HEIGHT_F - 100.0,
-                    27.0,
-                    true,
-                    "Made with Raylib which is licensed with the zlib license",
+    // Slot buttons come first so their item index is the slot index, followed
+    // by the back button.
+    fn slots(metadata: &[Option<SlotMetadata>]) -> Menu {
+        let mut menu = Menu::new();
+        let mut stack = Stack::new(Anchor::Top, Align::Center)
+            .margin(0.0, 130.0)
+            .spacing(0.0, 20.0);
+        for (i, slot) in metadata.iter().enumerate() {
+            let (text, c, h_c) = match slot {
+                Some(m) => (
+                    format!("Slot {}: {}", i + 1, m.describe()),
+                    Color::from_rgba(
+                        m.virtue_color.r / 2,
+                        m.virtue_color.g / 2,
+                        m.virtue_color.b / 2,
+                        255,
+                    ),
+                    m.virtue_color,
                 ),
-                Menu::instant_text(
-                    25.0,
-                    HEIGHT_F - 50.0,
-                    27.0,
-                    true,
-                    "Uses Clear-Sans which is licensed with Apache License Version 2.0",
+                None => (
+                    format!("Slot {}: Empty", i + 1),
+                    Color::from_rgba(0x33, 0x33, 0x33, 255),
+                    Color::from_rgba(0x66, 0x66, 0x66, 255),
                 ),
-            ],
+            };
+            let item = menu.add(Menu::button(text, Color::WHITE, c, h_c, true));
+            stack = stack.cell(Cell::button(item, 600.0, 80.0));
+        }
+        let back = menu.add(Menu::cyan_button("Back"));
+        menu.stack(stack.cell(Cell::button(back, 120.0, 50.0)));
+        menu.title(50.0, "Choose a save slot");
+        menu
+    }
+
+    // Setting buttons come first so their item index is the one used by
+    // `Settings`, followed by the back button.
+    fn settings(settings: &Settings) -> Menu {
+        let mut menu = Menu::new();
+        let mut stack = Stack::new(Anchor::Top, Align::Center)
+            .margin(0.0, 100.0)
+            .spacing(20.0, 8.0);
+        for i in 0..SETTINGS_ITEM_COUNT {
+            let item = menu.add(Menu::cyan_button(settings.item_text(i)));
+            stack = stack.cell(Cell::button(item, 440.0, 46.0));
+        }
+        let controls = menu.add(Menu::cyan_button("Controls"));
+        let back = menu.add(Menu::cyan_button("Back"));
+        menu.stack(stack.row(vec![
+            Cell::button(controls, 260.0, 48.0),
+            Cell::button(back, 160.0, 48.0),
+        ]));
+        menu.title(40.0, "Settings");
+        menu
+    }
+
+    // One button per `Action`, in order, followed by reset and back.
+    fn controls(bindings: &InputMap) -> Menu {
+        let mut menu = Menu::new();
+        let mut stack = Stack::new(Anchor::Top, Align::Center)
+            .margin(0.0, 100.0)
+            .spacing(20.0, 8.0);
+        for action in Action::ALL {
+            let item = menu.add(Menu::cyan_button(bindings.describe(action)));
+            stack = stack.cell(Cell::button(item, 440.0, 46.0));
         }
+        let reset = menu.add(Menu::grey_button("Reset to defaults"));
+        let back = menu.add(Menu::cyan_button("Back"));
+        menu.stack(stack.row(vec![
+            Cell::button(reset, 260.0, 48.0),
+            Cell::button(back, 160.0, 48.0),
+        ]));
+        menu.title(30.0, "Controls");
+        let hint = menu.add(Menu::paragraph(
+            20.0,
+            WIDTH_F - 140.0,
+            true,
+            "Click an action, then press a key or button to add or remove it",
+        ));
+        menu.stack(
+            Stack::new(Anchor::Bottom, Align::Left)
+                .margin(70.0, 20.0)
+                .cell(Cell::fit(hint)),
+        );
+        menu
+    }
+
+    fn restore_prompt(snapshot: &Snapshot) -> Menu {
+        let mut menu = Menu::new();
+        let restore = menu.add(Menu::cyan_button(format!(
+            "Restore slot {}: {}",
+            snapshot.slot + 1,
+            snapshot.metadata.describe()
+        )));
+        let discard = menu.add(Menu::grey_button("Discard"));
+        menu.stack(
+            Stack::new(Anchor::Center, Align::Center)
+                .spacing(0.0, 40.0)
+                .cell(Cell::button(restore, 600.0, 80.0))
+                .cell(Cell::button(discard, 240.0, 60.0)),
+        );
+        menu.title(50.0, "An autosave is newer than your last save");
+        menu
+    }
+
+    fn instant_text(text_size: f32, first: bool, s: impl Into<String>) -> MenuItem {
+        Menu::paragraph(text_size, f32::INFINITY, first, s)
     }
 
-    fn instant_text(x: f32, y: f32, text_size: f32, first: bool, s: &'static str) -> MenuItem {
+    /// Instant text wrapped to lines of at most `max_width`.
+    fn paragraph(text_size: f32, max_width: f32, first: bool, s: impl Into<String>) -> MenuItem {
         MenuItem {
-            x,
-            y,
+            x: 0.0,
+            y: 0.0,
             w: 0.0,
             h: 0.0,
             item_type: MenuItemType::InstantText {
-                text: s,
+                text: s.into(),
                 text_size,
                 text_color: Color::WHITE,
+                max_width,
             },
             is_hover: false,
             is_focus: false,
@@ -185,10 +505,10 @@ impl Menu {
         }
     }
 
-    fn text(x: f32, y: f32, text_size: f32, first: bool, s: &'static str) -> MenuItem {
+    fn text(text_size: f32, first: bool, s: &'static str) -> MenuItem {
         MenuItem {
-            x,
-            y,
+            x: 0.0,
+            y: 0.0,
             w: 0.0,
             h: 0.0,
             item_type: MenuItemType::AppearingText {
@@ -197,6 +517,8 @@ impl Menu {
                 text_c: Color::WHITE,
                 timer: 0.0,
                 text_idx: 0,
+                max_width: NARRATION_WIDTH,
+                lines: Vec::new(),
             },
             is_hover: false,
             is_focus: false,
@@ -218,317 +540,226 @@ impl Menu {
     }
 
     fn s_01() -> Menu {
-        Menu {
-            items: vec![
-                Menu::pause(0.500, true),
-                Menu::text(50.0, HEIGHT_F - 140.0, 40.0, false, "This is how it is."),
-                Menu::pause(0.500, false),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 100.0,
-                    40.0,
-                    false,
-                    "Nothing is, and everything is nothing.",
-                ),
-                Menu::pause(0.500, false),
-                Menu::text(50.0, HEIGHT_F - 60.0, 40.0, false, "...until you appeared."),
-                Menu::pause(0.100, false),
-                Menu::text(
-                    570.0,
-                    HEIGHT_F - 50.0,
-                    30.0,
-                    false,
-                    "(Click to continue...)",
-                ),
-            ],
-        }
+        let mut menu = Menu::new();
+        menu.add(Menu::pause(0.500, true));
+        let a = menu.add(Menu::text(40.0, false, "This is how it is."));
+        menu.add(Menu::pause(0.500, false));
+        let b = menu.add(Menu::text(
+            40.0,
+            false,
+            "Nothing is, and everything is nothing.",
+        ));
+        menu.add(Menu::pause(0.500, false));
+        let c = menu.add(Menu::text(40.0, false, "...until you appeared."));
+        menu.add(Menu::pause(0.100, false));
+        let hint = menu.add(Menu::text(30.0, false, "(Click to continue...)"));
+        menu.narration(Vector::new(50.0, 40.0), vec![vec![a], vec![b], vec![c]]);
+        menu.stack(
+            Stack::new(Anchor::Bottom, Align::Right)
+                .margin(30.0, 30.0)
+                .cell(Cell::fit(hint)),
+        );
+        menu
     }
 
     fn s_02() -> Menu {
-        Menu {
-            items: vec![
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 150.0,
-                    40.0,
-                    true,
-                    "Just by being, you brought light into existence.",
-                ),
-                Menu::pause(0.500, false),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 110.0,
-                    40.0,
-                    false,
-                    "What brings you here? What drives you?",
-                ),
-                Menu::pause(0.500, false),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 70.0,
-                    40.0,
-                    false,
-                    "Please tell me, what fuels you?",
-                ),
-                Menu::button(
-                    100.0,
-                    30.0,
-                    200.0,
-                    85.0,
-                    "Hope",
-                    Color::WHITE,
-                    Color::BLACK,
-                    Color::from_rgba(0x33, 0x33, 0x33, 255),
-                    false,
-                ),
-                Menu::button(
-                    500.0,
-                    30.0,
-                    200.0,
-                    85.0,
-                    "Miracles",
-                    Color::WHITE,
-                    Color::BLACK,
-                    Color::from_rgba(0x33, 0x33, 0x33, 255),
-                    false,
-                ),
-                Menu::button(
-                    100.0,
-                    150.0,
-                    200.0,
-                    85.0,
-                    "Kindness",
-                    Color::WHITE,
-                    Color::BLACK,
-                    Color::from_rgba(0x33, 0x33, 0x33, 255),
-                    false,
-                ),
-                Menu::button(
-                    500.0,
-                    150.0,
-                    200.0,
-                    85.0,
-                    "Determination",
-                    Color::WHITE,
-                    Color::BLACK,
-                    Color::from_rgba(0x33, 0x33, 0x33, 255),
-                    false,
-                ),
-            ],
+        let mut menu = Menu::new();
+        let a = menu.add(Menu::text(
+            40.0,
+            true,
+            "Just by being, you brought light into existence.",
+        ));
+        menu.add(Menu::pause(0.500, false));
+        let b = menu.add(Menu::text(
+            40.0,
+            false,
+            "What brings you here? What drives you?",
+        ));
+        menu.add(Menu::pause(0.500, false));
+        let c = menu.add(Menu::text(40.0, false, "Please tell me, what fuels you?"));
+        let mut choices = Vec::new();
+        for s in ["Hope", "Miracles", "Kindness", "Determination"] {
+            choices.push(menu.add(Menu::button(
+                s,
+                Color::WHITE,
+                Color::BLACK,
+                Color::from_rgba(0x33, 0x33, 0x33, 255),
+                false,
+            )));
         }
+        menu.narration(Vector::new(50.0, 50.0), vec![vec![a], vec![b], vec![c]]);
+        menu.stack(
+            Stack::new(Anchor::Top, Align::Center)
+                .margin(0.0, 30.0)
+                .spacing(200.0, 35.0)
+                .row(vec![
+                    Cell::button(choices[0], 200.0, 85.0),
+                    Cell::button(choices[1], 200.0, 85.0),
+                ])
+                .row(vec![
+                    Cell::button(choices[2], 200.0, 85.0),
+                    Cell::button(choices[3], 200.0, 85.0),
+                ]),
+        );
+        menu
     }
 
     // choose hope
     fn s_03() -> Menu {
-        Menu {
-            items: vec![
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 170.0,
-                    40.0,
-                    true,
-                    "Hope... hope that your actions will inspire others..",
-                ),
-                Menu::pause(0.500, false),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 130.0,
-                    40.0,
-                    false,
-                    "Hope that a brighter future will come tomorrow...",
-                ),
-                Menu::pause(0.500, false),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 90.0,
-                    40.0,
-                    false,
-                    ".. With your appearance, perhaps it shall...",
-                ),
-            ],
-        }
+        let mut menu = Menu::new();
+        let a = menu.add(Menu::text(
+            40.0,
+            true,
+            "Hope... hope that your actions will inspire others..",
+        ));
+        menu.add(Menu::pause(0.500, false));
+        let b = menu.add(Menu::text(
+            40.0,
+            false,
+            "Hope that a brighter future will come tomorrow...",
+        ));
+        menu.add(Menu::pause(0.500, false));
+        let c = menu.add(Menu::text(
+            40.0,
+            false,
+            ".. With your appearance, perhaps it shall...",
+        ));
+        menu.narration(Vector::new(50.0, 70.0), vec![vec![a], vec![b], vec![c]]);
+        menu
     }
 
     // choose miracles
     fn s_04() -> Menu {
-        Menu {
-            items: vec![
-                Menu::text(
-                    30.0,
-                    HEIGHT_F - 170.0,
-                    40.0,
-                    true,
-                    "Miracles huh?.. I see, then your appearance is special.",
-                ),
-                Menu::pause(0.500, false),
-                Menu::text(
-                    30.0,
-                    HEIGHT_F - 130.0,
-                    40.0,
-                    false,
-                    "With your appearance, things may change for the better..",
-                ),
-                Menu::pause(0.500, false),
-                Menu::text(
-                    30.0,
-                    HEIGHT_F - 90.0,
-                    40.0,
-                    false,
-                    "Now I am certain that this meeting was not by chance.",
-                ),
-            ],
-        }
+        let mut menu = Menu::new();
+        let a = menu.add(Menu::text(
+            40.0,
+            true,
+            "Miracles huh?.. I see, then your appearance is special.",
+        ));
+        menu.add(Menu::pause(0.500, false));
+        let b = menu.add(Menu::text(
+            40.0,
+            false,
+            "With your appearance, things may change for the better..",
+        ));
+        menu.add(Menu::pause(0.500, false));
+        let c = menu.add(Menu::text(
+            40.0,
+            false,
+            "Now I am certain that this meeting was not by chance.",
+        ));
+        menu.narration(Vector::new(30.0, 70.0), vec![vec![a], vec![b], vec![c]]);
+        menu
     }
 
     // choose kindness
     fn s_05() -> Menu {
-        Menu {
-            items: vec![
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 170.0,
-                    40.0,
-                    true,
-                    "Kindness?.. I am in your debt.",
-                ),
-                Menu::pause(0.250, false),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 130.0,
-                    40.0,
-                    false,
-                    "It has been a long time since I have encountered",
-                ),
-                Menu::text(50.0, HEIGHT_F - 90.0, 40.0, false, "another being..."),
-                Menu::pause(0.500, false),
-                Menu::text(270.0, HEIGHT_F - 90.0, 40.0, false, "... Thank you..."),
-            ],
-        }
+        let mut menu = Menu::new();
+        let a = menu.add(Menu::text(40.0, true, "Kindness?.. I am in your debt."));
+        menu.add(Menu::pause(0.250, false));
+        let b = menu.add(Menu::text(
+            40.0,
+            false,
+            "It has been a long time since I have encountered another being...",
+        ));
+        menu.add(Menu::pause(0.500, false));
+        let c = menu.add(Menu::text(40.0, false, "... Thank you..."));
+        menu.narration(Vector::new(50.0, 70.0), vec![vec![a], vec![b], vec![c]]);
+        menu
     }
 
     // choose determination
     fn s_06() -> Menu {
-        Menu {
-            items: vec![
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 170.0,
-                    40.0,
-                    true,
-                    "Determination.. I see...",
-                ),
-                Menu::pause(0.500, false),
-                Menu::text(
-                    400.0,
-                    HEIGHT_F - 170.0,
-                    40.0,
-                    false,
-                    "I do not doubt it, for it",
-                ),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 130.0,
-                    40.0,
-                    false,
-                    "must have been difficult to come here..",
-                ),
-                Menu::pause(0.500, false),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 90.0,
-                    40.0,
-                    false,
-                    "Your resolve is evident by your mere presence..",
-                ),
-            ],
-        }
+        let mut menu = Menu::new();
+        let a = menu.add(Menu::text(40.0, true, "Determination.. I see..."));
+        menu.add(Menu::pause(0.500, false));
+        let b = menu.add(Menu::text(
+            40.0,
+            false,
+            "I do not doubt it, for it must have been difficult to come here..",
+        ));
+        menu.add(Menu::pause(0.500, false));
+        let c = menu.add(Menu::text(
+            40.0,
+            false,
+            "Your resolve is evident by your mere presence..",
+        ));
+        menu.narration(Vector::new(50.0, 70.0), vec![vec![a], vec![b], vec![c]]);
+        menu
     }
 
     fn s_07() -> Menu {
-        Menu {
-            items: vec![
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 130.0,
-                    40.0,
-                    true,
-                    "Now that you are here, it must mean a new era of",
-                ),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 90.0,
-                    40.0,
-                    false,
-                    "creation for all that will be.",
-                ),
-                Menu::pause(0.200, false),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 50.0,
-                    40.0,
-                    false,
-                    "Try double-clicking the void to create something...",
-                ),
-            ],
-        }
+        let mut menu = Menu::new();
+        let a = menu.add(Menu::text(
+            40.0,
+            true,
+            "Now that you are here, it must mean a new era of creation for all that will be.",
+        ));
+        menu.add(Menu::pause(0.200, false));
+        let b = menu.add(Menu::text(
+            40.0,
+            false,
+            "Try double-clicking the void to create something...",
+        ));
+        menu.narration(Vector::new(50.0, 30.0), vec![vec![a], vec![b]]);
+        menu
     }
 
     fn s_08() -> Menu {
-        Menu {
-            items: vec![Menu::instant_text(
-                50.0,
-                HEIGHT_F - 90.0,
-                35.0,
-                true,
-                "(Try double-clicking now...)",
-            )],
-        }
+        let mut menu = Menu::new();
+        let a = menu.add(Menu::instant_text(
+            35.0,
+            true,
+            "(Try double-clicking now...)",
+        ));
+        menu.narration(Vector::new(50.0, 55.0), vec![vec![a]]);
+        menu
     }
 
     fn s_09() -> Menu {
-        Menu {
-            items: vec![
-                Menu::pause(0.400, true),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 140.0,
-                    40.0,
-                    false,
-                    "A new planet... It has most certainly been a while.",
-                ),
-                Menu::pause(0.500, false),
-                Menu::text(
-                    50.0,
-                    HEIGHT_F - 100.0,
-                    40.0,
-                    false,
-                    "Please, go out and create the new universe, and again..",
-                ),
-                Menu::pause(0.300, false),
-                Menu::text(50.0, HEIGHT_F - 60.0, 40.0, false, "Thank you."),
-            ],
-        }
+        let mut menu = Menu::new();
+        menu.add(Menu::pause(0.400, true));
+        let a = menu.add(Menu::text(
+            40.0,
+            false,
+            "A new planet... It has most certainly been a while.",
+        ));
+        menu.add(Menu::pause(0.500, false));
+        let b = menu.add(Menu::text(
+            40.0,
+            false,
+            "Please, go out and create the new universe, and again..",
+        ));
+        menu.add(Menu::pause(0.300, false));
+        let c = menu.add(Menu::text(40.0, false, "Thank you."));
+        menu.narration(Vector::new(50.0, 40.0), vec![vec![a], vec![b], vec![c]]);
+        menu
     }
 
-    fn s_10() -> Menu {
-        Menu {
-            items: vec![
-                Menu::instant_text(
-                    20.0,
-                    HEIGHT_F - 40.0,
-                    20.0,
-                    true,
-                    "Single click to move, Double-click to create something",
-                ),
-                Menu::instant_text(
-                    20.0,
-                    HEIGHT_F - 20.0,
-                    20.0,
-                    true,
-                    "S - save; L - load (can load from the start); R - reset",
-                ),
-            ],
-        }
+    fn s_10(bindings: &InputMap) -> Menu {
+        let mut menu = Menu::new();
+        let a = menu.add(Menu::instant_text(
+            20.0,
+            true,
+            "Single click to move, Double-click to create something",
+        ));
+        let b = menu.add(Menu::instant_text(
+            20.0,
+            true,
+            format!(
+                "{} - save; {} - load (can load from the start); {} - reset",
+                bindings.names(Action::Save),
+                bindings.names(Action::Load),
+                bindings.names(Action::Reset)
+            ),
+        ));
+        menu.stack(
+            Stack::new(Anchor::Bottom, Align::Left)
+                .margin(20.0, 0.0)
+                .cell(Cell::fit(a))
+                .cell(Cell::fit(b)),
+        );
+        menu
     }
 }
 
@@ -561,102 +792,36 @@ impl Default for Particle {
     }
 }
 
-impl Particle {
-    pub fn deserialize(data: &[u8], offset: usize) -> Result<(Particle, usize), ()> {
-        let mut idx: usize = 0;
-        let mut particle = Particle::default();
-
-        let (rect, rect_size) = Rectangle::deserialize(data, offset + idx)?;
-        particle.rect = rect;
-        idx += rect_size;
-
-        let (circle, circle_size) = Circle::deserialize(data, offset + idx)?;
-        particle.circle = circle;
-        idx += circle_size;
-
-        if data.len() < offset + idx + 1 {
-            return Err(());
-        }
-        particle.is_rect = data[offset + idx] != 0;
-        idx += 1;
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        particle.velx = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        particle.vely = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        particle.velr = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        particle.r = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        particle.lifetime = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        particle.life_timer = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        Ok((particle, idx))
+impl Encode for Particle {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.rect);
+        w.write(&self.circle);
+        w.write(&self.is_rect);
+        w.write(&self.velx);
+        w.write(&self.vely);
+        w.write(&self.velr);
+        w.write(&self.r);
+        w.write(&self.lifetime);
+        w.write(&self.life_timer);
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-
-        bytes.append(&mut self.rect.serialize());
-        bytes.append(&mut self.circle.serialize());
-        bytes.push(if self.is_rect { 1 } else { 0 });
-        bytes.append(&mut self.velx.to_be_bytes().into());
-        bytes.append(&mut self.vely.to_be_bytes().into());
-        bytes.append(&mut self.velr.to_be_bytes().into());
-        bytes.append(&mut self.r.to_be_bytes().into());
-        bytes.append(&mut self.lifetime.to_be_bytes().into());
-        bytes.append(&mut self.life_timer.to_be_bytes().into());
+impl Decode for Particle {
+    const MIN_SIZE: usize =
+        Rectangle::MIN_SIZE + Circle::MIN_SIZE + bool::MIN_SIZE + 6 * f32::MIN_SIZE;
 
-        bytes
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            rect: r.read("rect")?,
+            circle: r.read("circle")?,
+            is_rect: r.read("is_rect")?,
+            velx: r.read("velx")?,
+            vely: r.read("vely")?,
+            velr: r.read("velr")?,
+            r: r.read("r")?,
+            lifetime: r.read("lifetime")?,
+            life_timer: r.read("life_timer")?,
+        })
     }
 }
 
@@ -721,7 +886,7 @@ impl ParticleSystem {
         }
     }
 
-    fn update(&mut self, dt: f32) {
+    fn update(&mut self, dt: f32, reduced_particles: bool) {
         for i in (0..self.particles.len()).rev() {
             self.particles[i].life_timer += dt;
             if self.particles[i].life_timer > self.particles[i].lifetime {
@@ -736,24 +901,29 @@ impl ParticleSystem {
             }
         }
 
+        let spawn_time = if reduced_particles {
+            self.spawn_time * REDUCED_PARTICLES_FACTOR as f32
+        } else {
+            self.spawn_time
+        };
         self.spawn_timer += dt;
-        if self.spawn_timer > self.spawn_time {
-            self.spawn_timer -= self.spawn_time;
+        if self.spawn_timer > spawn_time {
+            self.spawn_timer -= spawn_time;
             self.particles.push(Particle {
                 rect: self.host_rect,
                 circle: self.host_circle,
                 is_rect: self.is_rect,
-                velx: (rand::rng().random_range(-PARTICLE_RAND_VEL_RANGE..PARTICLE_RAND_VEL_RANGE)
+                velx: (game_rng().random_range(-PARTICLE_RAND_VEL_RANGE..PARTICLE_RAND_VEL_RANGE)
                     + self.direction.x)
                     * self.vel_multiplier,
-                vely: (rand::rng().random_range(-PARTICLE_RAND_VEL_RANGE..PARTICLE_RAND_VEL_RANGE)
+                vely: (game_rng().random_range(-PARTICLE_RAND_VEL_RANGE..PARTICLE_RAND_VEL_RANGE)
                     + self.direction.y)
                     * self.vel_multiplier,
                 // velx: self.direction.x,
                 // vely: self.direction.y,
-                velr: rand::rng().random_range(-PARTICLE_RAND_ROT_RANGE..PARTICLE_RAND_ROT_RANGE)
+                velr: game_rng().random_range(-PARTICLE_RAND_ROT_RANGE..PARTICLE_RAND_ROT_RANGE)
                     * self.vel_multiplier,
-                r: rand::rng().random_range(0.0..90.0),
+                r: game_rng().random_range(0.0..90.0),
                 lifetime: self.lifetime,
                 life_timer: 0.0,
             });
@@ -800,154 +970,70 @@ impl ParticleSystem {
         }
     }
 
-    fn force_spawn(&mut self, count: usize) {
-        for _ in 0..count {
+    fn force_spawn(&mut self, count: usize, reduced_particles: bool) {
+        for _ in 0..reduced_count(count, reduced_particles) {
             self.particles.push(Particle {
                 rect: self.host_rect,
                 circle: self.host_circle,
                 is_rect: self.is_rect,
-                velx: (rand::rng().random_range(-PARTICLE_RAND_VEL_RANGE..PARTICLE_RAND_VEL_RANGE)
+                velx: (game_rng().random_range(-PARTICLE_RAND_VEL_RANGE..PARTICLE_RAND_VEL_RANGE)
                     + self.direction.x)
                     * self.vel_multiplier,
-                vely: (rand::rng().random_range(-PARTICLE_RAND_VEL_RANGE..PARTICLE_RAND_VEL_RANGE)
+                vely: (game_rng().random_range(-PARTICLE_RAND_VEL_RANGE..PARTICLE_RAND_VEL_RANGE)
                     + self.direction.y)
                     * self.vel_multiplier,
                 // velx: self.direction.x,
                 // vely: self.direction.y,
-                velr: rand::rng().random_range(-PARTICLE_RAND_ROT_RANGE..PARTICLE_RAND_ROT_RANGE)
+                velr: game_rng().random_range(-PARTICLE_RAND_ROT_RANGE..PARTICLE_RAND_ROT_RANGE)
                     * self.vel_multiplier,
-                r: rand::rng().random_range(0.0..90.0),
+                r: game_rng().random_range(0.0..90.0),
                 lifetime: self.lifetime,
                 life_timer: 0.0,
             });
         }
     }
+}
 
-    pub fn deserialize(data: &[u8], offset: usize) -> Result<(Self, usize), ()> {
-        let mut idx: usize = 0;
-        let mut psystem = ParticleSystem::default();
-
-        if data.len() < offset + idx + std::mem::size_of::<usize>() {
-            return Err(());
-        }
-        let particles_len: usize = usize::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<usize>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<usize>();
-
-        let mut particles: Vec<Particle> = Vec::new();
-        for _ in 0..particles_len {
-            let (particle, particle_size) = Particle::deserialize(data, offset + idx)?;
-            particles.push(particle);
-            idx += particle_size;
-        }
-        psystem.particles = particles;
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        psystem.spawn_timer = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        psystem.spawn_time = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        psystem.lifetime = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        let (rect, rect_size) = Rectangle::deserialize(data, offset + idx)?;
-        psystem.host_rect = rect;
-        idx += rect_size;
-
-        let (circle, circle_size) = Circle::deserialize(data, offset + idx)?;
-        psystem.host_circle = circle;
-        idx += circle_size;
-
-        if data.len() < offset + idx + 1 {
-            return Err(());
-        }
-        psystem.is_rect = data[offset + idx] != 0;
-        idx += 1;
-
-        let (vector, vector_size) = Vector::deserialize(data, offset + idx)?;
-        psystem.direction = vector;
-        idx += vector_size;
-
-        let (color, color_size) = Color::deserialize(data, offset + idx)?;
-        psystem.color = color;
-        idx += color_size;
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        psystem.opacity = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        psystem.vel_multiplier = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        Ok((psystem, idx))
+impl Encode for ParticleSystem {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.particles);
+        w.write(&self.spawn_timer);
+        w.write(&self.spawn_time);
+        w.write(&self.lifetime);
+        w.write(&self.host_rect);
+        w.write(&self.host_circle);
+        w.write(&self.is_rect);
+        w.write(&self.direction);
+        w.write(&self.color);
+        w.write(&self.opacity);
+        w.write(&self.vel_multiplier);
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-
-        let particle_count: usize = self.particles.len();
-        bytes.append(&mut particle_count.to_be_bytes().into());
-
-        for particle in self.particles.iter() {
-            bytes.append(&mut particle.serialize());
-        }
-
-        bytes.extend(self.spawn_timer.to_be_bytes());
-        bytes.extend(self.spawn_time.to_be_bytes());
-        bytes.extend(self.lifetime.to_be_bytes());
-
-        bytes.append(&mut self.host_rect.serialize());
-
-        bytes.append(&mut self.host_circle.serialize());
-
-        bytes.push(if self.is_rect { 1 } else { 0 });
-
-        bytes.append(&mut self.direction.serialize());
-
-        bytes.append(&mut self.color.serialize());
-
-        bytes.extend(self.opacity.to_be_bytes());
-        bytes.extend(self.vel_multiplier.to_be_bytes());
-
-        bytes
+impl Decode for ParticleSystem {
+    const MIN_SIZE: usize = Vec::<Particle>::MIN_SIZE
+        + 3 * f32::MIN_SIZE
+        + Rectangle::MIN_SIZE
+        + Circle::MIN_SIZE
+        + bool::MIN_SIZE
+        + Vector::MIN_SIZE
+        + Color::MIN_SIZE
+        + 2 * f32::MIN_SIZE;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            particles: r.read_vec("particles", MAX_SAVED_PARTICLES)?,
+            spawn_timer: r.read("spawn_timer")?,
+            spawn_time: r.read("spawn_time")?,
+            lifetime: r.read("lifetime")?,
+            host_rect: r.read("host_rect")?,
+            host_circle: r.read("host_circle")?,
+            is_rect: r.read("is_rect")?,
+            direction: r.read("direction")?,
+            color: r.read("color")?,
+            opacity: r.read("opacity")?,
+            vel_multiplier: r.read("vel_multiplier")?,
+        })
     }
 }
 
@@ -1004,20 +1090,20 @@ impl RotatingParticleSystem {
         }
     }
 
-    fn update(&mut self, dt: f32) {
+    fn update(&mut self, dt: f32, reduced_particles: bool) {
         if self.particle_system.is_rect {
             let saved_rect = self.particle_system.host_rect;
             self.particle_system
                 .host_rect
                 .pos_add_vec(Transform::rotate(self.r) * Vector::new(self.offset, 0.0));
-            self.particle_system.update(dt);
+            self.particle_system.update(dt, reduced_particles);
             self.particle_system.host_rect = saved_rect;
         } else {
             let saved_cir = self.particle_system.host_circle;
             self.particle_system
                 .host_circle
                 .pos_add_vec(Transform::rotate(self.r) * Vector::new(self.offset, 0.0));
-            self.particle_system.update(dt);
+            self.particle_system.update(dt, reduced_particles);
             self.particle_system.host_circle = saved_cir;
         }
         self.r += self.velr * dt * 10.0;
@@ -1066,67 +1152,58 @@ impl RotatingParticleSystem {
                 .ok();
         }
     }
+}
 
-    pub fn deserialize(data: &[u8], offset: usize) -> Result<(Self, usize), ()> {
-        let mut idx: usize = 0;
-        let mut rpsystem = RotatingParticleSystem::default();
-
-        let (psystem, psystem_size) = ParticleSystem::deserialize(data, offset + idx)?;
-        rpsystem.particle_system = psystem;
-        idx += psystem_size;
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        rpsystem.r = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        rpsystem.velr = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        rpsystem.offset = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        Ok((rpsystem, idx))
+impl Encode for RotatingParticleSystem {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.particle_system);
+        w.write(&self.r);
+        w.write(&self.velr);
+        w.write(&self.offset);
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-
-        bytes.append(&mut self.particle_system.serialize());
-
-        bytes.extend(self.r.to_be_bytes());
-        bytes.extend(self.velr.to_be_bytes());
-        bytes.extend(self.offset.to_be_bytes());
+impl Decode for RotatingParticleSystem {
+    const MIN_SIZE: usize = ParticleSystem::MIN_SIZE + 3 * f32::MIN_SIZE;
 
-        bytes
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            particle_system: r.read("particle_system")?,
+            r: r.read("r")?,
+            velr: r.read("velr")?,
+            offset: r.read("offset")?,
+        })
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Default)]
 struct ExplConvCircleParticle {
     circle: Circle,
     offset: f32,
     r: f32,
 }
 
+impl Encode for ExplConvCircleParticle {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.circle);
+        w.write(&self.offset);
+        w.write(&self.r);
+    }
+}
+
+impl Decode for ExplConvCircleParticle {
+    const MIN_SIZE: usize = Circle::MIN_SIZE + 2 * f32::MIN_SIZE;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            circle: r.read("circle")?,
+            offset: r.read("offset")?,
+            r: r.read("r")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
 struct ExplConvParticleSystem {
     particles: Vec<ExplConvCircleParticle>,
     lifetime: f32,
@@ -1148,13 +1225,13 @@ impl ExplConvParticleSystem {
         }
     }
 
-    fn activate(&mut self, count: usize, offset: f32) {
+    fn activate(&mut self, count: usize, offset: f32, reduced_particles: bool) {
         self.life_timer = 0.0;
-        for _ in 0..count {
+        for _ in 0..reduced_count(count, reduced_particles) {
             self.particles.push(ExplConvCircleParticle {
                 circle: self.host_circle,
                 offset,
-                r: rand::rng().random_range(0.0..360.0),
+                r: game_rng().random_range(0.0..360.0),
             });
         }
     }
@@ -1214,6 +1291,35 @@ impl ExplConvParticleSystem {
     }
 }
 
+impl Encode for ExplConvParticleSystem {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.particles);
+        w.write(&self.lifetime);
+        w.write(&self.host_circle);
+        w.write(&self.color);
+        w.write(&self.opacity);
+        w.write(&self.life_timer);
+    }
+}
+
+impl Decode for ExplConvParticleSystem {
+    const MIN_SIZE: usize = Vec::<ExplConvCircleParticle>::MIN_SIZE
+        + 3 * f32::MIN_SIZE
+        + Circle::MIN_SIZE
+        + Color::MIN_SIZE;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            particles: r.read_vec("particles", MAX_SAVED_PARTICLES)?,
+            lifetime: r.read("lifetime")?,
+            host_circle: r.read("host_circle")?,
+            color: r.read("color")?,
+            opacity: r.read("opacity")?,
+            life_timer: r.read("life_timer")?,
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Default)]
 struct Planet {
     circle: Circle,
@@ -1230,7 +1336,7 @@ impl Planet {
             circle,
             color,
             particle_system: ParticleSystem::new(
-                rand::rng().random_range(2.0..3.8),
+                game_rng().random_range(2.0..3.8),
                 0.9,
                 Rectangle::new(0.0, 0.0, 1.0, 1.0),
                 circle,
@@ -1243,11 +1349,11 @@ impl Planet {
             moons: Vec::with_capacity(MAX_MOONS),
         };
 
-        let r: f32 = rand::rng().random_range(0.0..360.0);
-        let clockwise = rand::rng().random_bool(0.5);
-        for _ in 0..rand::rng().random_range(0..MAX_MOONS) {
+        let r: f32 = game_rng().random_range(0.0..360.0);
+        let clockwise = game_rng().random_bool(0.5);
+        for _ in 0..game_rng().random_range(0..MAX_MOONS) {
             planet.moons.push(RotatingParticleSystem::new(
-                rand::rng().random_range(1.0..2.6),
+                game_rng().random_range(1.0..2.6),
                 0.6,
                 Rectangle::new(0.0, 0.0, 1.0, 1.0),
                 smaller_circle,
@@ -1257,11 +1363,11 @@ impl Planet {
                 1.0,
                 r,
                 if clockwise {
-                    rand::rng().random_range(0.05..0.15)
+                    game_rng().random_range(0.05..0.15)
                 } else {
-                    rand::rng().random_range(-0.15..-0.05)
+                    game_rng().random_range(-0.15..-0.05)
                 },
-                rand::rng().random_range(35.0..200.0),
+                game_rng().random_range(35.0..200.0),
                 0.2,
             ));
         }
@@ -1269,14 +1375,14 @@ impl Planet {
         planet
     }
 
-    fn update(&mut self, dt: f32) {
+    fn update(&mut self, dt: f32, reduced_particles: bool) {
         self.particle_system.host_circle.x = self.circle.x;
         self.particle_system.host_circle.y = self.circle.y;
-        self.particle_system.update(dt);
+        self.particle_system.update(dt, reduced_particles);
         for moon in &mut self.moons {
             moon.particle_system.host_circle.x = self.circle.x;
             moon.particle_system.host_circle.y = self.circle.y;
-            moon.update(dt);
+            moon.update(dt, reduced_particles);
         }
     }
 
@@ -1298,58 +1404,47 @@ impl Planet {
             moon.draw(window, transform);
         }
     }
+}
 
-    pub fn deserialize(data: &[u8], offset: usize) -> Result<(Planet, usize), ()> {
-        let mut idx: usize = 0;
-        let mut planet = Planet::default();
-
-        let (circle, circle_size) = Circle::deserialize(data, offset + idx)?;
-        planet.circle = circle;
-        idx += circle_size;
-
-        let (color, color_size) = Color::deserialize(data, offset + idx)?;
-        planet.color = color;
-        idx += color_size;
-
-        // No need to deserialize ParticleSystem
-        //let (psystem, psystem_size) = ParticleSystem::deserialize(data, offset + idx)?;
-        //planet.particle_system = psystem;
-        //idx += psystem_size;
-
-        if data.len() < offset + idx + std::mem::size_of::<usize>() {
-            return Err(());
-        }
-        let moons_size = usize::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<usize>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<usize>();
-        for _ in 0..moons_size {
-            let (rpsystem, rps_size) = RotatingParticleSystem::deserialize(data, offset + idx)?;
-            planet.moons.push(rpsystem);
-            idx += rps_size;
-        }
-
-        Ok((planet, idx))
+impl Encode for Planet {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.circle);
+        w.write(&self.color);
+        w.write(&self.particle_system);
+        w.write(&self.moons);
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
+impl Decode for Planet {
+    const MIN_SIZE: usize = Circle::MIN_SIZE
+        + Color::MIN_SIZE
+        + ParticleSystem::MIN_SIZE
+        + Vec::<RotatingParticleSystem>::MIN_SIZE;
 
-        bytes.append(&mut self.circle.serialize());
-        bytes.append(&mut self.color.serialize());
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            circle: r.read("circle")?,
+            color: r.read("color")?,
+            particle_system: r.read("particle_system")?,
+            moons: r.read_vec("moons", MAX_MOONS)?,
+        })
+    }
+}
 
-        // No need to serialize ParticleSystem.
-        //bytes.append(&mut self.particle_system.serialize());
+// Saves before version 4 did not store the ParticleSystem of planets and stars.
+struct LegacyPlanet(Planet);
 
-        let moons_size = self.moons.len();
-        bytes.extend(moons_size.to_be_bytes());
-        for i in 0..moons_size {
-            bytes.append(&mut self.moons[i].serialize());
-        }
+impl Decode for LegacyPlanet {
+    const MIN_SIZE: usize =
+        Circle::MIN_SIZE + Color::MIN_SIZE + Vec::<RotatingParticleSystem>::MIN_SIZE;
 
-        bytes
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self(Planet {
+            circle: r.read("circle")?,
+            color: r.read("color")?,
+            particle_system: ParticleSystem::default(),
+            moons: r.read_vec("moons", MAX_MOONS)?,
+        }))
     }
 }
 
@@ -1373,11 +1468,11 @@ impl Default for Star {
 }
 
 impl Star {
-    fn new(circle: Circle, color: Color, velr: f32, r: f32) -> Self {
+    fn new(circle: Circle, color: Color, velr: f32, r: f32, reduced_particles: bool) -> Self {
         let mut star = Star {
             color,
             particle_system: ParticleSystem::new(
-                rand::rng().random_range(0.08..0.2),
+                game_rng().random_range(0.08..0.2),
                 0.85,
                 Rectangle::new(0.0, 0.0, 1.0, 1.0),
                 circle,
@@ -1401,19 +1496,24 @@ impl Star {
             star.color.b = (0.75 * 255.0) as u8;
         }
         star.particle_system
-            .force_spawn(rand::rng().random_range(20..45));
+            .force_spawn(game_rng().random_range(20..45), reduced_particles);
 
         star
     }
 
-    fn update(&mut self, dt: f32) {
-        self.particle_system.update(dt);
+    fn update(&mut self, dt: f32, reduced_particles: bool) {
+        self.particle_system.update(dt, reduced_particles);
         self.r += self.velr * dt;
     }
 
-    fn draw(&mut self, image: &str, window: &mut Window, transform: Transform) {
+    fn draw(
+        &mut self,
+        image: ImageHandle,
+        window: &mut Window,
+        transform: Transform,
+    ) -> Result<(), String> {
         self.particle_system.draw(window, transform);
-        let image = window.get_image_mut(image).expect("Should be loaded image");
+        let image = window.get_image_mut(image)?;
         let mut image_rect = image.get_wh_rect();
         image_rect.x = self.particle_system.host_circle.x - image_rect.w / 2.0;
         image_rect.y = self.particle_system.host_circle.y - image_rect.h / 2.0;
@@ -1429,56 +1529,44 @@ impl Star {
                 },
             )
             .ok();
+        Ok(())
     }
+}
 
-    pub fn deserialize(data: &[u8], offset: usize) -> Result<(Star, usize), ()> {
-        let mut idx: usize = 0;
-        let mut star = Star::default();
-
-        let (color, color_size) = Color::deserialize(data, offset + idx)?;
-        star.color = color;
-        idx += color_size;
-
-        // No need to deserialize ParticleSystem.
-        //let (psystem, psystem_size) = ParticleSystem::deserialize(data, offset + idx)?;
-        //star.particle_system = psystem;
-        //idx += psystem_size;
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        star.velr = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        star.r = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        Ok((star, idx))
+impl Encode for Star {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.color);
+        w.write(&self.particle_system);
+        w.write(&self.velr);
+        w.write(&self.r);
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
+impl Decode for Star {
+    const MIN_SIZE: usize = Color::MIN_SIZE + ParticleSystem::MIN_SIZE + 2 * f32::MIN_SIZE;
 
-        bytes.append(&mut self.color.serialize());
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            color: r.read("color")?,
+            particle_system: r.read("particle_system")?,
+            velr: r.read("velr")?,
+            r: r.read("r")?,
+        })
+    }
+}
 
-        // No need to serialize ParticleSystem.
-        //bytes.append(&mut self.particle_system.serialize());
+struct LegacyStar(Star);
 
-        bytes.extend(self.velr.to_be_bytes());
-        bytes.extend(self.r.to_be_bytes());
+impl Decode for LegacyStar {
+    const MIN_SIZE: usize = Color::MIN_SIZE + 2 * f32::MIN_SIZE;
 
-        bytes
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self(Star {
+            color: r.read("color")?,
+            particle_system: ParticleSystem::default(),
+            velr: r.read("velr")?,
+            r: r.read("r")?,
+        }))
     }
 }
 
@@ -1520,7 +1608,7 @@ impl Default for Fish {
 
 impl Fish {
     fn new(pos: Vector, r: f32, color: Color) -> Self {
-        let anim_timer = rand::rng().random_range(0.8..1.0);
+        let anim_timer = game_rng().random_range(0.8..1.0);
         Self {
             pos,
             r,
@@ -1548,17 +1636,17 @@ impl Fish {
     fn set_next(&mut self, state: FishState) {
         match state {
             FishState::Idle => {
-                self.swim_time = rand::rng().random_range(1.1..2.4);
+                self.swim_time = game_rng().random_range(1.1..2.4);
                 self.swim_timer = self.swim_time;
                 self.anim_timer = 2.8;
                 self.anim_time = 1.6;
                 self.swim_v = 0.0;
             }
             FishState::Swim => {
-                self.swim_time = rand::rng().random_range(1.4..2.3);
+                self.swim_time = game_rng().random_range(1.4..2.3);
                 self.swim_timer = self.swim_time;
-                self.r = rand::rng().random_range(0.0..std::f32::consts::PI * 2.0);
-                self.anim_timer = rand::rng().random_range(1.6..2.0);
+                self.r = game_rng().random_range(0.0..std::f32::consts::PI * 2.0);
+                self.anim_timer = game_rng().random_range(1.6..2.0);
                 self.anim_time = self.anim_timer;
                 self.swim_v = self.anim_timer / 8.0;
             }
@@ -1571,7 +1659,7 @@ impl Fish {
             self.swim_v /= 1.1;
         }
         if self.swim_time <= 0.0 {
-            if rand::rng().random_bool(0.4) {
+            if game_rng().random_bool(0.4) {
                 self.set_next(FishState::Idle);
             } else {
                 self.set_next(FishState::Swim);
@@ -1586,10 +1674,13 @@ impl Fish {
         self.pos -= Transform::rotate(self.r) * Vector::new(self.swim_v, 0.0) * dt * 200.0;
     }
 
-    fn draw(&mut self, i_fish: &str, window: &mut Window, transform: Transform) {
-        let fish_img = window
-            .get_image_mut(i_fish)
-            .expect("\"fish\" Image should be loaded");
+    fn draw(
+        &mut self,
+        i_fish: ImageHandle,
+        window: &mut Window,
+        transform: Transform,
+    ) -> Result<(), String> {
+        let fish_img = window.get_image_mut(i_fish)?;
         let anim_angle = ((self.anim_timer / self.anim_time) * std::f32::consts::PI * 2.0).sin();
         let mut body_rect = self.body_rect;
         body_rect.x = self.pos.x - self.body_rect.w / 2.0;
@@ -1629,108 +1720,103 @@ impl Fish {
                 },
             )
             .ok();
+        Ok(())
     }
+}
 
-    pub fn deserialize(data: &[u8], offset: usize) -> Result<(Fish, usize), ()> {
-        let mut idx: usize = 0;
-        let mut fish = Fish::default();
-
-        let (pos, pos_size) = Vector::deserialize(data, offset + idx)?;
-        fish.pos = pos;
-        idx += pos_size;
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        fish.r = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        fish.swim_time = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        fish.swim_timer = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        fish.swim_v = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        fish.anim_timer = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        if data.len() < offset + idx + std::mem::size_of::<f32>() {
-            return Err(());
-        }
-        fish.anim_time = f32::from_be_bytes(
-            data[(offset + idx)..(offset + idx + std::mem::size_of::<f32>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<f32>();
-
-        let (color, color_size) = Color::deserialize(data, offset + idx)?;
-        fish.color = color;
-        idx += color_size;
-
-        let (body_rect, body_rect_size) = Rectangle::deserialize(data, offset + idx)?;
-        fish.body_rect = body_rect;
-        idx += body_rect_size;
+impl Encode for Fish {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.pos);
+        w.write(&self.r);
+        w.write(&self.swim_time);
+        w.write(&self.swim_timer);
+        w.write(&self.swim_v);
+        w.write(&self.anim_timer);
+        w.write(&self.anim_time);
+        w.write(&self.color);
+        w.write(&self.body_rect);
+        w.write(&self.tail_rect);
+    }
+}
 
-        let (tail_rect, tail_rect_size) = Rectangle::deserialize(data, offset + idx)?;
-        fish.tail_rect = tail_rect;
-        idx += tail_rect_size;
+impl Decode for Fish {
+    const MIN_SIZE: usize =
+        Vector::MIN_SIZE + 6 * f32::MIN_SIZE + Color::MIN_SIZE + 2 * Rectangle::MIN_SIZE;
 
-        Ok((fish, idx))
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(Self {
+            pos: r.read("pos")?,
+            r: r.read("r")?,
+            swim_time: r.read("swim_time")?,
+            swim_timer: r.read("swim_timer")?,
+            swim_v: r.read("swim_v")?,
+            anim_timer: r.read("anim_timer")?,
+            anim_time: r.read("anim_time")?,
+            color: r.read("color")?,
+            body_rect: r.read("body_rect")?,
+            tail_rect: r.read("tail_rect")?,
+        })
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-
-        bytes.append(&mut self.pos.serialize());
+// Everything besides the created objects that is needed to restore the exact
+// scene.
+#[derive(Clone, Debug, PartialEq, Default)]
+struct SceneData {
+    expl_conv_p_systems: Vec<ExplConvParticleSystem>,
+    player_r: f32,
+    player_particles: ParticleSystem,
+    move_to: Vector,
+    camera_view: Vector,
+    state: u32,
+    music_on: bool,
+}
 
-        bytes.extend(self.r.to_be_bytes());
-        bytes.extend(self.swim_time.to_be_bytes());
-        bytes.extend(self.swim_timer.to_be_bytes());
-        bytes.extend(self.swim_v.to_be_bytes());
-        bytes.extend(self.anim_timer.to_be_bytes());
-        bytes.extend(self.anim_time.to_be_bytes());
+impl Encode for SceneData {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.expl_conv_p_systems);
+        w.write(&self.player_r);
+        w.write(&self.player_particles);
+        w.write(&self.move_to);
+        w.write(&self.camera_view);
+        w.write(&self.state);
+        w.write(&self.music_on);
+    }
+}
 
-        bytes.append(&mut self.color.serialize());
-        bytes.append(&mut self.body_rect.serialize());
-        bytes.append(&mut self.tail_rect.serialize());
+impl Decode for SceneData {
+    const MIN_SIZE: usize = Vec::<ExplConvParticleSystem>::MIN_SIZE
+        + f32::MIN_SIZE
+        + ParticleSystem::MIN_SIZE
+        + 2 * Vector::MIN_SIZE
+        + u32::MIN_SIZE
+        + bool::MIN_SIZE;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        let expl_conv_p_systems = r.read_vec("expl_conv_p_systems", MAX_SAVED_ENTITIES)?;
+        let player_r = r.read("player_r")?;
+        let player_particles = r.read("player_particles")?;
+        let move_to = r.read("move_to")?;
+        let camera_view = r.read("camera_view")?;
+        let state_offset = r.get_offset();
+        let state = r.read("state")?;
+        if !(1..=10).contains(&state) {
+            return Err(DecodeError::new(
+                state_offset,
+                DecodeErrorKind::InvalidValue(format!("{state} is not a valid state")),
+            )
+            .in_field("state"));
+        }
 
-        bytes
+        Ok(Self {
+            expl_conv_p_systems,
+            player_r,
+            player_particles,
+            move_to,
+            camera_view,
+            state,
+            music_on: r.read("music_on")?,
+        })
     }
 }
 
@@ -1741,129 +1827,247 @@ struct SaveData {
     fishes: Vec<Fish>,
     player: Rectangle,
     joining_particles: RotatingParticleSystem,
+    // Saves before version 4 have no scene.
+    scene: Option<SceneData>,
 }
 
 const SAVE_DATA_IDENTIFIER: [u8; 8] = [0x53, 0x41, 0x56, 0x45, b'V', b'e', b'r', 1];
+const SAVE_DATA_VERSION: u32 = 4;
+const SAVE_CHECKSUM_SIZE: usize = 4;
+const MAX_SAVED_ENTITIES: usize = 10_000;
+const MAX_SAVED_PARTICLES: usize = 10_000;
 
-impl SaveData {
-    pub fn deserialize(data: &[u8]) -> Result<(SaveData, usize), ()> {
-        let mut idx: usize = 0;
-        let mut save_data = SaveData::default();
-
-        if data.len() < idx + SAVE_DATA_IDENTIFIER.len() {
-            return Err(());
-        }
-        for i in 0..SAVE_DATA_IDENTIFIER.len() {
-            if data[idx + i] != SAVE_DATA_IDENTIFIER[i] {
-                return Err(());
-            }
-        }
-        idx += SAVE_DATA_IDENTIFIER.len();
-
-        if data.len() < idx + std::mem::size_of::<usize>() {
-            return Err(());
+impl SaveData {
+    fn metadata(&self, created: u64, modified: u64, sequence: u64, play_time: f32) -> SlotMetadata {
+        SlotMetadata {
+            created,
+            modified,
+            sequence,
+            planet_count: self.planets.len() as u32,
+            star_count: self.stars.len() as u32,
+            fish_count: self.fishes.len() as u32,
+            virtue_color: self.joining_particles.particle_system.color,
+            play_time,
         }
-        let planets_size = usize::from_be_bytes(
-            data[idx..(idx + std::mem::size_of::<usize>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<usize>();
+    }
 
-        for _ in 0..planets_size {
-            let (planet, planet_size) = Planet::deserialize(data, idx)?;
-            save_data.planets.push(planet);
-            idx += planet_size;
-        }
+    fn encode_body(&self, w: &mut Writer) {
+        w.write(&self.planets);
+        w.write(&self.stars);
+        w.write(&self.fishes);
+        w.write(&self.player);
+        w.write(&self.joining_particles);
+        w.write(&self.scene);
+    }
 
-        if data.len() < idx + std::mem::size_of::<usize>() {
-            return Err(());
-        }
-        let stars_size = usize::from_be_bytes(
-            data[idx..(idx + std::mem::size_of::<usize>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<usize>();
+    fn decode_body(r: &mut Reader, version: u32) -> Result<Self, DecodeError> {
+        let save_data = if version < 4 {
+            Self {
+                planets: r
+                    .read_vec::<LegacyPlanet>("planets", MAX_SAVED_ENTITIES)?
+                    .into_iter()
+                    .map(|p| p.0)
+                    .collect(),
+                stars: r
+                    .read_vec::<LegacyStar>("stars", MAX_SAVED_ENTITIES)?
+                    .into_iter()
+                    .map(|s| s.0)
+                    .collect(),
+                fishes: r.read_vec("fishes", MAX_SAVED_ENTITIES)?,
+                player: r.read("player")?,
+                joining_particles: r.read("joining_particles")?,
+                scene: None,
+            }
+        } else {
+            Self {
+                planets: r.read_vec("planets", MAX_SAVED_ENTITIES)?,
+                stars: r.read_vec("stars", MAX_SAVED_ENTITIES)?,
+                fishes: r.read_vec("fishes", MAX_SAVED_ENTITIES)?,
+                player: r.read("player")?,
+                joining_particles: r.read("joining_particles")?,
+                scene: r.read("scene")?,
+            }
+        };
 
-        for _ in 0..stars_size {
-            let (star, star_size) = Star::deserialize(data, idx)?;
-            save_data.stars.push(star);
-            idx += star_size;
-        }
+        // Trailing bytes mean the lengths were read with the wrong width.
+        r.expect_end()?;
 
-        if data.len() < idx + std::mem::size_of::<usize>() {
-            return Err(());
-        }
-        let fishes_size = usize::from_be_bytes(
-            data[idx..(idx + std::mem::size_of::<usize>())]
-                .try_into()
-                .map_err(|_| ())?,
-        );
-        idx += std::mem::size_of::<usize>();
+        Ok(save_data)
+    }
 
-        for _ in 0..fishes_size {
-            let (fish, fish_size) = Fish::deserialize(data, idx)?;
-            save_data.fishes.push(fish);
-            idx += fish_size;
+    fn decode_checksummed_body(r: &mut Reader, version: u32) -> Result<Self, DecodeError> {
+        let rest = r.peek_remaining();
+        if rest.len() < SAVE_CHECKSUM_SIZE {
+            return Err(r
+                .error(DecodeErrorKind::UnexpectedEnd {
+                    needed: SAVE_CHECKSUM_SIZE,
+                    remaining: rest.len(),
+                })
+                .in_field("checksum"));
+        }
+        let body = &rest[..(rest.len() - SAVE_CHECKSUM_SIZE)];
+
+        // Verify before decoding so corrupt lengths are never acted upon.
+        let mut checksum_reader = r.fork_at(r.get_offset() + body.len(), DEFAULT_LEN_SIZE);
+        let expected: u32 = checksum_reader.read("checksum")?;
+        let actual = crc32(body);
+        if expected != actual {
+            return Err(DecodeError::new(
+                r.get_offset() + body.len(),
+                DecodeErrorKind::ChecksumMismatch { expected, actual },
+            )
+            .in_field("checksum"));
         }
 
-        let (p_rect, p_rect_size) = Rectangle::deserialize(data, idx)?;
-        save_data.player = p_rect;
-        idx += p_rect_size;
-
-        let (jp, jp_size) = RotatingParticleSystem::deserialize(data, idx)?;
-        save_data.joining_particles = jp;
-        idx += jp_size;
+        let save_data = Self::decode_body(&mut r.limit(body.len()), version)?;
+        *r = checksum_reader;
 
-        Ok((save_data, idx))
+        Ok(save_data)
     }
+}
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
+impl Encode for SaveData {
+    fn encode(&self, w: &mut Writer) {
+        let mut body = Writer::new();
+        self.encode_body(&mut body);
+        let body = body.into_bytes();
 
-        bytes.extend(SAVE_DATA_IDENTIFIER.iter());
+        w.write_bytes(&SAVE_DATA_IDENTIFIER);
+        w.write(&SAVE_DATA_VERSION);
+        w.write_bytes(&body);
+        w.write(&crc32(&body));
+    }
+}
 
-        bytes.extend(self.planets.len().to_be_bytes());
-        for planet in &self.planets {
-            bytes.append(&mut planet.serialize());
-        }
+impl Decode for SaveData {
+    const MIN_SIZE: usize = SAVE_DATA_IDENTIFIER.len();
 
-        bytes.extend(self.stars.len().to_be_bytes());
-        for star in &self.stars {
-            bytes.append(&mut star.serialize());
-        }
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        r.expect_identifier(&SAVE_DATA_IDENTIFIER)?;
+        let body_offset = r.get_offset();
 
-        bytes.extend(self.fishes.len().to_be_bytes());
-        for fish in &self.fishes {
-            bytes.append(&mut fish.serialize());
+        let mut versioned_error = None;
+        if let Ok(version @ 2..=SAVE_DATA_VERSION) = r.read::<u32>("version") {
+            let mut body_reader = r.clone();
+            // Version 2 saves have no checksum trailer.
+            let result = if version >= 3 {
+                Self::decode_checksummed_body(&mut body_reader, version)
+            } else {
+                Self::decode_body(&mut body_reader, version)
+            };
+            match result {
+                Ok(save_data) => {
+                    *r = body_reader;
+                    return Ok(save_data);
+                }
+                Err(e) => versioned_error = Some(e),
+            }
         }
 
-        bytes.append(&mut self.player.serialize());
+        // Version 1 saves have no version field and store lengths as native
+        // usize, so they may come from either a 32-bit (wasm) or a 64-bit build.
+        let mut furthest_error: Option<DecodeError> = None;
+        for len_size in [4, 8] {
+            let mut body_reader = r.fork_at(body_offset, len_size);
+            match Self::decode_body(&mut body_reader, 1) {
+                Ok(save_data) => {
+                    *r = body_reader;
+                    return Ok(save_data);
+                }
+                Err(e) => {
+                    if furthest_error
+                        .as_ref()
+                        .is_none_or(|furthest| e.offset > furthest.offset)
+                    {
+                        furthest_error = Some(e);
+                    }
+                }
+            }
+        }
 
-        bytes.append(&mut self.joining_particles.serialize());
+        // A known version field is far more likely than a version 1 save that
+        // happens to start with the same bytes, so its error is the useful one.
+        Err(versioned_error
+            .or(furthest_error)
+            .expect("Should have attempted at least one decode"))
+    }
+}
 
-        bytes
+// Saves written before slots existed have their metadata derived from the
+// save data itself.
+fn slot_metadata_from_bytes(data: &[u8]) -> Option<SlotMetadata> {
+    let (metadata, save_bytes) = save_slots::decode_slot(data).ok()?;
+    if metadata.is_some() {
+        return metadata;
     }
+    let (save_data, _) = from_bytes::<SaveData>(save_bytes).ok()?;
+    Some(save_data.metadata(0, 0, 0, 0.0))
+}
+
+// While a right or middle button is held.
+#[derive(Clone, Copy, Debug)]
+struct Drag {
+    distance: f32,
+    last_pos: Vector,
 }
 
 enum SaveLoadNotification {
     Save { text: Option<String>, timer: f32 },
     Load { text: Option<String>, timer: f32 },
+    // Names the current load binding, so it stays right after a rebind.
+    LoadHint { timer: f32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum StartScreen {
+    Main,
+    SlotPicker,
+    RestorePrompt,
+}
+
+// Handles to the assets in the manifest.
+#[derive(Clone, Copy, Debug)]
+struct Assets {
+    star: ImageHandle,
+    fish: ImageHandle,
+    text: FontHandle,
+}
+
+impl Assets {
+    fn find(window: &Window) -> Result<Self, String> {
+        Ok(Self {
+            star: window.image("star")?,
+            fish: window.image("fish")?,
+            text: window.font("text")?,
+        })
+    }
+}
+
+// Sounds and music are loaded once the audio device is ready.
+#[derive(Clone, Copy, Debug)]
+struct AudioAssets {
+    boom: SoundHandle,
+    get: SoundHandle,
+    tap: SoundHandle,
+    theme: MusicHandle,
+}
+
+impl AudioAssets {
+    fn find(window: &Window) -> Result<Self, String> {
+        Ok(Self {
+            boom: window.sound("boom")?,
+            get: window.sound("get")?,
+            tap: window.sound("tap")?,
+            theme: window.music("theme")?,
+        })
+    }
 }
 
 #[allow(dead_code)]
 pub struct GameState {
-    s_boom: String,
-    s_get: String,
-    s_power_up: String,
-    s_tap: String,
-    s_speak_m: String,
-    s_speak_f: String,
-    font: String,
-    music2: String,
-    i_star: String,
-    i_fish: String,
+    manifest: Manifest,
+    assets: Assets,
+    audio: Option<AudioAssets>,
     music_on: bool,
     menu: Menu,
     state: u32,
@@ -1881,98 +2085,74 @@ pub struct GameState {
     click_time: Option<f32>,
     click_pos: Vector,
     mouse_pos: Vector,
+    // Relative to the player, where gamepads create things.
+    reticle: Vector,
+    gamepad_active: bool,
     expl_conv_p_systems: Vec<ExplConvParticleSystem>,
     planets: Vec<Planet>,
     stars: Vec<Star>,
     fishes: Vec<Fish>,
     camera: Box<dyn CameraInterface>,
+    zoom: f32,
+    // Offset of the view from the player, in world units.
+    pan: Vector,
+    drag: Option<Drag>,
+    gestures: GestureRecognizer,
     move_to: Vector,
     save_load_notification: Option<SaveLoadNotification>,
-    #[cfg(target_family = "wasm")]
     load_recv: Option<Receiver<Vec<u8>>>,
-    loaded_sounds_music: bool,
+    storage: Storage,
+    current_slot: usize,
+    slot_created: Option<u64>,
+    play_time: f32,
+    start_screen: StartScreen,
+    slot_metadata: Vec<Option<SlotMetadata>>,
+    slot_recvs: Vec<(usize, Receiver<Vec<u8>>)>,
+    autosave_timer: f32,
+    autosave_index: usize,
+    // Of the last save or autosave written.
+    save_sequence: u64,
+    autosave_recv: Option<Receiver<Result<(), String>>>,
+    recovery_scan: Option<RecoveryScan>,
+    restore_snapshot: Option<Snapshot>,
+    asset_root: PathBuf,
+    settings: Settings,
+    settings_recv: Option<Receiver<Vec<u8>>>,
+    settings_open: bool,
+    controls_open: bool,
+    rebind_action: Option<Action>,
+    muted: bool,
+    // Fullscreen from the command line wins over the saved setting.
+    force_fullscreen: bool,
 }
 
 impl GameState {
     pub fn new(window: &mut Window) -> Result<Self, String> {
-        let s_boom = String::from("boom.mp3");
-        //window.load_sound(
-        //    &PathBuf::from_str("static/boom.mp3")
-        //        .map_err(|_| String::from("Failed to load \"static/boom.mp3\""))?,
-        //    s_boom.clone(),
-        //)?;
-        let s_get = String::from("get.mp3");
-        //window.load_sound(
-        //    &PathBuf::from_str("static/get.mp3")
-        //        .map_err(|_| String::from("Failed to load \"static/get.mp3\""))?,
-        //    s_get.clone(),
-        //)?;
-        let s_power_up = String::from("power_up.mp3");
-        //window.load_sound(
-        //    &PathBuf::from_str("static/power_up.mp3")
-        //        .map_err(|_| String::from("Failed to load \"static/power_up.mp3\""))?,
-        //    s_power_up.clone(),
-        //)?;
-        let s_tap = String::from("tap.mp3");
-        //window.load_sound(
-        //    &PathBuf::from_str("static/tap.mp3")
-        //        .map_err(|_| String::from("Failed to load \"static/tap.mp3\""))?,
-        //    s_tap.clone(),
-        //)?;
-        let s_speak_m = String::from("speak_m.mp3");
-        //window.load_sound(
-        //    &PathBuf::from_str("static/speak_m.mp3")
-        //        .map_err(|_| String::from("Failed to load \"static/speak_m.mp3\""))?,
-        //    s_speak_m.clone(),
-        //)?;
-        let s_speak_f = String::from("speak_f.mp3");
-        //window.load_sound(
-        //    &PathBuf::from_str("static/speak_f.mp3")
-        //        .map_err(|_| String::from("Failed to load \"static/speak_f.mp3\""))?,
-        //    s_speak_f.clone(),
-        //)?;
-
-        let font = String::from("ClearSans-Regular.ttf");
-        window.load_font(
-            &PathBuf::from_str("static/ClearSans-Regular.ttf")
-                .map_err(|_| String::from("Failed to load \"static/ClearSans-Regular.ttf\""))?,
-            font.clone(),
-        )?;
+        Self::with_options(window, &LaunchOptions::default())
+    }
 
-        let music2 = String::from("music2.mp3");
-        //window.load_music(
-        //    &PathBuf::from_str("static/music2.mp3")
-        //        .map_err(|_| String::from("Failed to load \"static/music2.mp3\""))?,
-        //    music2.clone(),
-        //)?;
-
-        let i_star = String::from("star.png");
-        window.load_image(
-            &PathBuf::from_str("static/star.png")
-                .map_err(|_| String::from("Failed to load \"static/star.png\""))?,
-            i_star.clone(),
-        )?;
+    pub fn with_options(window: &mut Window, options: &LaunchOptions) -> Result<Self, String> {
+        if let Some(seed) = options.seed {
+            seed_game_rng(seed);
+        }
 
-        let i_fish = String::from("fish.png");
-        window.load_image(
-            &PathBuf::from_str("static/fish.png")
-                .map_err(|_| String::from("Failed to load \"static/fish.png\""))?,
-            i_fish.clone(),
+        let manifest = Manifest::read(
+            window.get_gi().vfs(),
+            &options.asset_root.join("manifest.txt"),
         )?;
+        window.load_manifest(&manifest, &options.asset_root, false)?;
+        let assets = Assets::find(window)?;
 
         let mut camera = window.get_gi_mut().get_default_camera()?;
         camera.set_view_xy(0.0, 0.0)?;
-        Ok(Self {
-            s_boom,
-            s_get,
-            s_power_up,
-            s_tap,
-            s_speak_m,
-            s_speak_f,
-            font,
-            music2,
-            i_star,
-            i_fish,
+
+        let storage = Storage::open(options.data_dir.clone());
+        let recovery_scan = RecoveryScan::start(&storage);
+        let settings_recv = settings::read_settings(&storage).ok();
+        let mut game_state = Self {
+            manifest,
+            assets,
+            audio: None,
             music_on: false,
             menu: Menu::start(),
             state: 0,
@@ -2013,59 +2193,83 @@ impl GameState {
             click_time: None,
             click_pos: Vector::new(0.0, 0.0),
             mouse_pos: Vector::new(0.0, 0.0),
+            reticle: Vector::new(0.0, -100.0),
+            gamepad_active: false,
             expl_conv_p_systems: Vec::new(),
             planets: Vec::new(),
             stars: Vec::new(),
             fishes: Vec::new(),
             camera,
+            zoom: 1.0,
+            pan: Vector::new(0.0, 0.0),
+            drag: None,
+            gestures: GestureRecognizer::new(Settings::default().double_click_time),
             move_to: Vector::new(400.0, 300.0),
             save_load_notification: None,
-            #[cfg(target_family = "wasm")]
             load_recv: None,
-            loaded_sounds_music: false,
-        })
+            storage,
+            current_slot: 0,
+            slot_created: None,
+            play_time: 0.0,
+            start_screen: StartScreen::Main,
+            slot_metadata: vec![None; SAVE_SLOT_COUNT],
+            slot_recvs: Vec::new(),
+            autosave_timer: 0.0,
+            autosave_index: 0,
+            save_sequence: 0,
+            autosave_recv: None,
+            recovery_scan: Some(recovery_scan),
+            restore_snapshot: None,
+            asset_root: options.asset_root.clone(),
+            settings: Settings::default(),
+            settings_recv,
+            settings_open: false,
+            controls_open: false,
+            rebind_action: None,
+            muted: options.mute,
+            force_fullscreen: options.fullscreen,
+        };
+        game_state.apply_settings(window)?;
+
+        if options.skip_intro {
+            game_state.skip_intro();
+        }
+        if let Some(path) = &options.load {
+            game_state.load_file(window, path)?;
+        }
+
+        Ok(game_state)
     }
 
     pub fn update(&mut self, window: &mut Window) -> Result<(), String> {
         let dt = window.get_gi().get_delta_time();
 
         // check music/sounds loaded
-        if !self.loaded_sounds_music {
-            let bool_ret =
-                unsafe { crate::agnostic_interface::raylib_impl::ffi::IsAudioDeviceReady() };
-            if bool_ret {
-                window.load_sound(
-                    &PathBuf::from_str("static/boom.mp3")
-                        .map_err(|_| String::from("Failed to load \"static/boom.mp3\""))?,
-                    self.s_boom.clone(),
-                )?;
-                window.load_sound(
-                    &PathBuf::from_str("static/get.mp3")
-                        .map_err(|_| String::from("Failed to load \"static/get.mp3\""))?,
-                    self.s_get.clone(),
-                )?;
-                window.load_sound(
-                    &PathBuf::from_str("static/tap.mp3")
-                        .map_err(|_| String::from("Failed to load \"static/tap.mp3\""))?,
-                    self.s_tap.clone(),
-                )?;
-                window.load_music(
-                    &PathBuf::from_str("static/music2.mp3")
-                        .map_err(|_| String::from("Failed to load \"static/music2.mp3\""))?,
-                    self.music2.clone(),
-                )?;
-
-                self.loaded_sounds_music = true;
-            }
+        if self.audio.is_none() && window.get_gi().is_audio_ready() {
+            window.load_manifest(&self.manifest, &self.asset_root, true)?;
+            self.audio = Some(AudioAssets::find(window)?);
         }
 
+        let (width, height) = window.get_gi().get_dimensions()?;
+        self.relayout_menu(window)?;
+
         // check mouse pos
         {
-            self.mouse_pos = window.get_gi().get_mouse_xy_vec()?;
+            let mouse_pos = window.get_gi().get_mouse_xy_vec()?;
+            if mouse_pos != self.mouse_pos {
+                self.gamepad_active = false;
+                for mi in &mut self.menu.items {
+                    mi.is_focus = false;
+                }
+            }
+            self.mouse_pos = mouse_pos;
             //self.mouse_pos = window.get_gi().vec_to_world(self.mouse_pos)?;
+            let focused = self.menu.items.iter().position(|mi| mi.is_focus);
             let mut hovered = false;
             for i in 0..self.menu.items.len() {
-                if self.menu.items[i].is_inside(self.mouse_pos.x, self.mouse_pos.y) {
+                if focused.is_none()
+                    && self.menu.items[i].is_inside(self.mouse_pos.x, self.mouse_pos.y)
+                {
                     self.menu.items[i].is_hover = true;
                     self.current_item = Some(i);
                     hovered = true;
@@ -2074,141 +2278,280 @@ impl GameState {
                 }
             }
             if !hovered {
-                self.current_item = None;
+                self.current_item = focused;
+            }
+        }
+
+        // check gamepads
+        {
+            let gi = window.get_gi_mut();
+            let mut movement = Vector::new(0.0, 0.0);
+            let mut aim = Vector::new(0.0, 0.0);
+            let mut focus_step = 0;
+            for pad in gi.get_gamepads()? {
+                movement += gi.get_gamepad_stick(pad, GamepadStick::Left, STICK_DEADZONE)?;
+                aim += gi.get_gamepad_stick(pad, GamepadStick::Right, STICK_DEADZONE)?;
+                if gi.is_gamepad_button_pressed(pad, GamepadButton::DpadUp)?
+                    || gi.is_gamepad_button_pressed(pad, GamepadButton::DpadLeft)?
+                {
+                    focus_step -= 1;
+                }
+                if gi.is_gamepad_button_pressed(pad, GamepadButton::DpadDown)?
+                    || gi.is_gamepad_button_pressed(pad, GamepadButton::DpadRight)?
+                {
+                    focus_step += 1;
+                }
+            }
+            let moved = movement.x != 0.0 || movement.y != 0.0;
+            let aimed = aim.x != 0.0 || aim.y != 0.0;
+            if moved || aimed || focus_step != 0 {
+                self.gamepad_active = true;
+            }
+
+            if focus_step != 0
+                && self.selection_mode
+                && self.current_finished
+                && self.rebind_action.is_none()
+            {
+                self.current_item = self.menu.move_focus(focus_step > 0);
+            }
+            if self.is_create_mode && self.current_finished {
+                if moved && self.state == 10 {
+                    self.click_time = None;
+                    self.move_to += movement * (STICK_MOVE_SPEED * dt);
+                }
+                if aimed {
+                    self.reticle += aim * (RETICLE_SPEED * dt);
+                    let len =
+                        (self.reticle.x * self.reticle.x + self.reticle.y * self.reticle.y).sqrt();
+                    if len > RETICLE_RANGE {
+                        self.reticle = self.reticle * (RETICLE_RANGE / len);
+                    }
+                }
+            }
+        }
+
+        let can_navigate = self.state == 10
+            && self.is_create_mode
+            && self.current_finished
+            && self.rebind_action.is_none();
+
+        // check touch gestures
+        let touching = {
+            let touches = window.get_gi_mut().get_touch_points()?;
+            let was_touching = self.gestures.is_tracking();
+            for gesture in self.gestures.update(dt, &touches) {
+                if !can_navigate {
+                    continue;
+                }
+                match gesture {
+                    Gesture::Tap { pos } => {
+                        // moves once it is clear this is no double tap
+                        self.click_time = Some(0.0);
+                        self.click_pos = window.get_gi().vec_to_world(pos)?;
+                    }
+                    Gesture::DoubleTap { pos } | Gesture::LongPress { pos } => {
+                        self.click_time = None;
+                        let world_pos = window.get_gi().vec_to_world(pos)?;
+                        self.create(window, world_pos)?;
+                    }
+                    Gesture::Pan { delta } => {
+                        self.pan -= delta * (1.0 / self.camera.get_zoom()?);
+                    }
+                    Gesture::Pinch { scale, .. } => {
+                        self.zoom = (self.zoom * scale).clamp(ZOOM_MIN, ZOOM_MAX);
+                    }
+                }
+            }
+            was_touching || !touches.is_empty()
+        };
+
+        // check wheel zoom and right/middle drag
+        {
+            // Buttons bound to creating or moving are left to those actions.
+            let bindings = &self.settings.bindings;
+            let pan_buttons: Vec<MouseButton> = [MouseButton::Right, MouseButton::Middle]
+                .into_iter()
+                .filter(|button| {
+                    let binding = Binding::Mouse(*button);
+                    !bindings.get(Action::Create).contains(&binding)
+                        && !bindings.get(Action::MoveTo).contains(&binding)
+                })
+                .collect();
+            for event in window.get_gi_mut().get_pointer_events()? {
+                match event {
+                    PointerEvent::Wheel { delta } if can_navigate => {
+                        self.zoom = (self.zoom * ZOOM_STEP.powf(delta)).clamp(ZOOM_MIN, ZOOM_MAX);
+                    }
+                    PointerEvent::Pressed { button, pos }
+                        if can_navigate && pan_buttons.contains(&button) =>
+                    {
+                        self.drag = Some(Drag {
+                            distance: 0.0,
+                            last_pos: pos,
+                        });
+                    }
+                    PointerEvent::Moved { pos } => {
+                        if let Some(drag) = &mut self.drag {
+                            let delta = pos - drag.last_pos;
+                            drag.distance += (delta.x * delta.x + delta.y * delta.y).sqrt();
+                            drag.last_pos = pos;
+                            self.pan -= delta * (1.0 / self.camera.get_zoom()?);
+                        }
+                    }
+                    PointerEvent::Released { button, pos }
+                        if button != MouseButton::Left && self.drag.is_some() =>
+                    {
+                        let distance = self.drag.take().map_or(0.0, |drag| drag.distance);
+                        if button == MouseButton::Right
+                            && distance < DRAG_CLICK_DISTANCE
+                            && can_navigate
+                        {
+                            let world_pos = window.get_gi().vec_to_world(pos)?;
+                            self.create(window, world_pos)?;
+                        }
+                    }
+                    _ => (),
+                }
             }
         }
 
-        // check mouse down
-        if window.get_gi_mut().get_mouse_released()? {
+        // check bound inputs
+        let gi = window.get_gi_mut();
+        let bindings = &self.settings.bindings;
+        // Browsers also report touches as left clicks, the gestures handle
+        // those.
+        let from_touch = |binding: &Binding| touching && matches!(binding, Binding::Mouse(_));
+        let create_pressed = bindings
+            .pressed_binding(gi, Action::Create)?
+            .filter(|b| !from_touch(b));
+        let move_pressed = bindings
+            .pressed_binding(gi, Action::MoveTo)?
+            .filter(|b| !from_touch(b));
+        let confirm_pressed = bindings.pressed(gi, Action::Confirm)?;
+        let released =
+            bindings.released(gi, Action::Create)? || bindings.released(gi, Action::MoveTo)?;
+
+        if let Some(action) = self.rebind_action {
+            if window.get_gi_mut().is_key_pressed(Key::Escape)? {
+                self.cancel_rebind(action);
+            } else if let Some(binding) = input::poll_binding(window.get_gi_mut())? {
+                self.rebind(action, binding);
+            }
+        } else if released {
             if self.dbl_click_timeout.is_none() {
                 self.click_release_time = 0.0;
             }
-        } else if window.get_gi_mut().get_mouse_pressed()?.is_some() {
-            if self.current_finished {
-                if self.is_create_mode {
-                    let click_pos = window.get_gi().vec_to_world(self.mouse_pos)?;
-                    if self.click_release_time < DOUBLE_CLICK_TIME {
-                        self.click_release_time = DOUBLE_CLICK_TIME;
+        } else if self.current_finished && self.is_create_mode {
+            let mouse_pos = window.get_gi().vec_to_world(self.mouse_pos)?;
+            let reticle_pos = Vector::new(self.player.x, self.player.y) + self.reticle;
+            let target = |binding: Binding| match binding {
+                Binding::Gamepad(_) => reticle_pos,
+                _ => mouse_pos,
+            };
+            match (create_pressed, move_pressed) {
+                (Some(binding), Some(_)) => {
+                    // Both are bound to the same input, so creating needs a
+                    // double click.
+                    if self.click_release_time < self.settings.double_click_time {
+                        self.click_release_time = self.settings.double_click_time;
                         self.dbl_click_timeout = Some(0.0);
                         self.click_time = None;
-                        if self.state == 8 {
-                            let mut expl_conv_system = ExplConvParticleSystem::new(
-                                1.5,
-                                Circle::new(click_pos.x, click_pos.y, 20.0),
-                                Color::from_rgba(0x99, 0xFF, 0x99, 255),
-                                1.0,
-                            );
-                            expl_conv_system.activate(30, 200.0);
-                            self.expl_conv_p_systems.push(expl_conv_system);
-                            self.state = 9;
-                            self.state_dirty = true;
-                            window.get_sound_mut(&self.s_boom)?.play(0.8)?;
-                        } else if self.state == 10 {
-                            let mut rng = rand::rng();
-                            let rand_out = rng.random_range(0.0..1.0);
-                            if rand_out < 0.6 {
-                                // spawn planet
-                                let mut expl_conv_system = ExplConvParticleSystem::new(
-                                    rng.random_range(1.2..1.6),
-                                    Circle::new(
-                                        click_pos.x,
-                                        click_pos.y,
-                                        rng.random_range(15.0..25.0),
-                                    ),
-                                    Color::from_rgba(
-                                        rng.random_range(0x44..0xFF),
-                                        rng.random_range(0x44..0xFF),
-                                        rng.random_range(0x44..0xFF),
-                                        255,
-                                    ),
-                                    1.0,
-                                );
-                                expl_conv_system.activate(
-                                    rng.random_range(13..40),
-                                    rng.random_range(150.0..300.0),
-                                );
-                                self.expl_conv_p_systems.push(expl_conv_system);
-                            } else if rand_out < 0.85 {
-                                // spawn star
-                                let rot_clockwise = rng.random_bool(0.5);
-                                self.stars.push(Star::new(
-                                    Circle::new(
-                                        click_pos.x,
-                                        click_pos.y,
-                                        rng.random_range(3.0..7.0),
-                                    ),
-                                    Color::from_rgba(
-                                        rng.random_range(0x58..0xFF),
-                                        rng.random_range(0x58..0xFF),
-                                        rng.random_range(0x58..0xFF),
-                                        255,
-                                    ),
-                                    if rot_clockwise {
-                                        rng.random_range(0.1..0.3)
-                                    } else {
-                                        rng.random_range(-0.3..-0.1)
-                                    },
-                                    rng.random_range(0.0..90.0),
-                                ));
-                            } else {
-                                // spawn fish
-                                for _ in 0..rng.random_range(1..4) {
-                                    self.fishes.push(Fish::new(
-                                        click_pos,
-                                        rng.random_range(0.0..360.0),
-                                        Color::from_rgba(
-                                            rng.random_range(0x44..0xFF),
-                                            rng.random_range(0x44..0xFF),
-                                            rng.random_range(0x44..0xFF),
-                                            255,
-                                        ),
-                                    ));
-                                }
-                            }
-                            window.get_sound_mut(&self.s_boom)?.play(0.8)?;
-                        }
+                        self.create(window, target(binding))?;
                     } else if self.state == 10 {
                         self.click_time = Some(0.0);
-                        self.click_pos = click_pos;
+                        self.click_pos = target(binding);
                     }
-                } else if self.selection_mode {
+                }
+                (Some(binding), None) => self.create(window, target(binding))?,
+                (None, Some(binding)) if self.state == 10 => {
+                    self.click_time = None;
+                    self.move_to = target(binding);
+                }
+                _ => (),
+            }
+        } else if confirm_pressed {
+            if self.current_finished {
+                if self.selection_mode {
                     if let Some(idx) = self.current_item {
-                        match self.state {
-                            0 => {
-                                self.state += 1;
-                                self.state_dirty = true;
-                            }
-                            2 => {
-                                if idx == 5 {
-                                    // hope
-                                    self.state = 3;
-                                    self.state_dirty = true;
-                                    self.joining_particles.particle_system.color =
-                                        Color::from_rgba(0xAA, 0xCC, 0xFF, 255);
-                                } else if idx == 6 {
-                                    // miracles
-                                    self.state = 4;
-                                    self.state_dirty = true;
-                                    self.joining_particles.particle_system.color =
-                                        Color::from_rgba(0xFF, 0xFF, 0xAA, 255);
-                                } else if idx == 7 {
-                                    // kindness
-                                    self.state = 5;
-                                    self.state_dirty = true;
-                                    self.joining_particles.particle_system.color =
-                                        Color::from_rgba(0xBB, 0xFF, 0xBB, 255);
-                                } else {
-                                    // determination
-                                    self.state = 6;
+                        if self.controls_open {
+                            self.controls_clicked(idx);
+                        } else if self.settings_open {
+                            self.settings_clicked(window, idx)?;
+                        } else {
+                            match self.state {
+                                0 => match self.start_screen {
+                                    StartScreen::SlotPicker => {
+                                        if idx < SAVE_SLOT_COUNT {
+                                            self.current_slot = idx;
+                                            if self.slot_metadata[idx].is_some() {
+                                                self.try_load();
+                                            } else {
+                                                self.state = 1;
+                                                self.state_dirty = true;
+                                                self.start_screen = StartScreen::Main;
+                                            }
+                                        } else {
+                                            // back
+                                            self.state_dirty = true;
+                                        }
+                                    }
+                                    StartScreen::RestorePrompt => {
+                                        if idx == 0 {
+                                            self.restore(window);
+                                        } else {
+                                            // discard
+                                            self.restore_snapshot = None;
+                                            self.state_dirty = true;
+                                        }
+                                    }
+                                    StartScreen::Main => {
+                                        if idx == 1 {
+                                            self.open_slot_picker();
+                                        } else if idx == 2 {
+                                            self.open_settings();
+                                        } else {
+                                            self.state += 1;
+                                            self.state_dirty = true;
+                                        }
+                                    }
+                                },
+                                2 => {
+                                    if idx == 5 {
+                                        // hope
+                                        self.state = 3;
+                                        self.state_dirty = true;
+                                        self.joining_particles.particle_system.color =
+                                            Color::from_rgba(0xAA, 0xCC, 0xFF, 255);
+                                    } else if idx == 6 {
+                                        // miracles
+                                        self.state = 4;
+                                        self.state_dirty = true;
+                                        self.joining_particles.particle_system.color =
+                                            Color::from_rgba(0xFF, 0xFF, 0xAA, 255);
+                                    } else if idx == 7 {
+                                        // kindness
+                                        self.state = 5;
+                                        self.state_dirty = true;
+                                        self.joining_particles.particle_system.color =
+                                            Color::from_rgba(0xBB, 0xFF, 0xBB, 255);
+                                    } else {
+                                        // determination
+                                        self.state = 6;
+                                        self.state_dirty = true;
+                                        self.joining_particles.particle_system.color =
+                                            Color::from_rgba(0xFF, 0xAA, 0xAA, 255);
+                                    }
+                                    if let Some(audio) = self.audio {
+                                        window
+                                            .get_sound_mut(audio.get)?
+                                            .play(0.7 * self.settings.sfx_volume)?;
+                                    }
+                                }
+                                _ => {
+                                    self.state = 0;
                                     self.state_dirty = true;
-                                    self.joining_particles.particle_system.color =
-                                        Color::from_rgba(0xFF, 0xAA, 0xAA, 255);
                                 }
-                                window.get_sound_mut(&self.s_get)?.play(0.7)?;
-                            }
-                            _ => {
-                                self.state = 0;
-                                self.state_dirty = true;
                             }
                         }
                     }
@@ -2231,6 +2574,8 @@ impl GameState {
                             text_size: _,
                             text_c: _,
                             timer: _,
+                            max_width: _,
+                            lines: _,
                         } => {
                             *text_idx = text.len();
                         }
@@ -2246,11 +2591,7 @@ impl GameState {
                             timer: _,
                             length: _,
                         } => (),
-                        MenuItemType::InstantText {
-                            text: _,
-                            text_size: _,
-                            text_color: _,
-                        } => {}
+                        MenuItemType::InstantText { .. } => {}
                     }
                     mi.is_loaded = true;
                 }
@@ -2258,8 +2599,12 @@ impl GameState {
             }
         }
 
-        // check pressed keys
-        if window.get_gi_mut().get_key_pressed('s')? {
+        // check bound actions
+        let gi = window.get_gi_mut();
+        let bindings = &self.settings.bindings;
+        if self.rebind_action.is_some() {
+            // keep the captured input from triggering anything
+        } else if bindings.pressed(gi, Action::Save)? {
             if self.state == 10 {
                 let save_result = self.save().map_err(|e| e.to_string());
                 if let Err(s) = save_result {
@@ -2269,27 +2614,39 @@ impl GameState {
                     });
                 }
             }
-        } else if window.get_gi_mut().get_key_pressed('l')? {
+        } else if bindings.pressed(gi, Action::Load)? {
             if self.state > 0 {
-                let load_result = self.load().map_err(|e| e.to_string());
-                if let Err(s) = load_result {
-                    self.save_load_notification = Some(SaveLoadNotification::Load {
-                        text: Some(format!("Failed to load! {}", s)),
-                        timer: SL_NOTIF_TIME,
-                    });
-                }
+                self.try_load();
+            }
+        } else if bindings.pressed(gi, Action::Settings)? {
+            if self.state == 10 && !self.settings_open {
+                self.open_settings();
             }
-        } else if window.get_gi_mut().get_key_pressed('r')? && self.state == 10 {
+        } else if bindings.pressed(gi, Action::Reset)? && self.state == 10 {
             self.state = 0;
             self.state_dirty = true;
-            window.get_music_mut(&self.music2)?.stop()?;
+            if let Some(audio) = self.audio {
+                window.get_music_mut(audio.theme)?.stop()?;
+            }
             self.music_on = false;
         }
 
+        if self.state > 0 {
+            self.play_time += dt;
+        }
+
+        if self.state == 10 {
+            self.autosave_timer += dt;
+            if self.autosave_timer > AUTOSAVE_TIME && self.autosave_recv.is_none() {
+                self.autosave_timer = 0.0;
+                self.autosave();
+            }
+        }
+
         self.click_release_time += dt;
         if let Some(t) = &mut self.click_time {
             *t += dt;
-            if *t > DOUBLE_CLICK_TIME {
+            if *t > self.settings.double_click_time {
                 self.move_to = self.click_pos; // - Vector::new(WIDTH_F / 2.0, HEIGHT_F / 2.0);
             }
         }
@@ -2309,10 +2666,13 @@ impl GameState {
             (self.player.x - self.joining_particles.particle_system.host_rect.x) / 30.0;
         self.joining_particles.particle_system.host_rect.y +=
             (self.player.y - self.joining_particles.particle_system.host_rect.y) / 30.0;
+        let cz = self.camera.get_zoom()?;
+        let cz = cz + (self.zoom - cz) / 10.0;
+        self.camera.set_zoom(cz)?;
         let (cx, cy) = self.camera.get_view_xy()?;
         self.camera.set_view_xy(
-            cx + (self.player.x - WIDTH_F / 2.0 - cx) / 40.0,
-            cy + (self.player.y - HEIGHT_F / 2.0 - cy) / 40.0,
+            cx + (self.player.x + self.pan.x - width / 2.0 / cz - cx) / 40.0,
+            cy + (self.player.y + self.pan.y - height / 2.0 / cz - cy) / 40.0,
         )?;
         window.get_gi_mut().set_camera(self.camera.as_ref())?;
 
@@ -2320,10 +2680,13 @@ impl GameState {
 
         if self.state_dirty {
             self.state_dirty = false;
-            if self.state > 1 && !self.music_on && self.loaded_sounds_music {
-                let music = window.get_music_mut(&self.music2)?;
-                music.set_loop(true)?;
-                music.play(0.5)?;
+            self.settings_open = false;
+            self.controls_open = false;
+            self.rebind_action = None;
+            if let Some(audio) = self.audio.filter(|_| self.state > 1 && !self.music_on) {
+                window
+                    .get_music_mut(audio.theme)?
+                    .play(MUSIC_VOLUME * self.settings.music_volume)?;
                 self.music_on = true;
             }
             match self.state {
@@ -2331,8 +2694,7 @@ impl GameState {
                     self.menu = Menu::s_01();
                     self.current_finished = false;
                     self.selection_mode = false;
-                    self.save_load_notification = Some(SaveLoadNotification::Load {
-                        text: Some("You can load with \"L\" from here on.".into()),
+                    self.save_load_notification = Some(SaveLoadNotification::LoadHint {
                         timer: SL_NOTIF_TIME,
                     });
                 }
@@ -2379,7 +2741,7 @@ impl GameState {
                     self.is_create_mode = false;
                 }
                 10 => {
-                    self.menu = Menu::s_10();
+                    self.menu = Menu::s_10(&self.settings.bindings);
                     self.current_finished = false;
                     self.selection_mode = false;
                     self.is_create_mode = true;
@@ -2400,7 +2762,15 @@ impl GameState {
                     self.player.y = HEIGHT_F / 2.0;
                     self.move_to = Vector::new(WIDTH_F / 2.0, HEIGHT_F / 2.0);
                     self.camera.set_view_xy(0.0, 0.0)?;
+                    self.camera.set_zoom(1.0)?;
+                    self.zoom = 1.0;
+                    self.pan = Vector::new(0.0, 0.0);
+                    self.drag = None;
                     self.click_time = None;
+                    self.start_screen = StartScreen::Main;
+                    self.slot_created = None;
+                    self.play_time = 0.0;
+                    self.autosave_timer = 0.0;
                 }
             }
         }
@@ -2423,10 +2793,10 @@ impl GameState {
         }
 
         if self.music_on {
-        } else if self.state == 10 {
-            let music = window.get_music_mut(&self.music2)?;
-            music.set_loop(true)?;
-            music.play(0.5)?;
+        } else if let Some(audio) = self.audio.filter(|_| self.state == 10) {
+            window
+                .get_music_mut(audio.theme)?
+                .play(MUSIC_VOLUME * self.settings.music_volume)?;
             self.music_on = true;
         }
 
@@ -2460,12 +2830,19 @@ impl GameState {
                         text_size: _,
                         text_c: _,
                         timer,
+                        max_width: _,
+                        lines: _,
                     } => {
                         *timer += dt;
-                        if *timer > TEXT_RATE {
-                            *timer -= TEXT_RATE;
-                            *text_idx += 1;
-                            window.get_sound_mut(&self.s_tap).unwrap().play(0.2)?;
+                        if *timer > self.settings.text_rate {
+                            *timer -= self.settings.text_rate;
+                            // text_idx is a byte offset, so it skips whole characters
+                            *text_idx += text[*text_idx..].chars().next().map_or(1, char::len_utf8);
+                            if let Some(audio) = self.audio {
+                                window
+                                    .get_sound_mut(audio.tap)?
+                                    .play(0.2 * self.settings.sfx_volume)?;
+                            }
                             if *text_idx >= text.len() {
                                 mi.is_loaded = true;
                                 if i + 1 < self.menu.items.len() {
@@ -2493,11 +2870,7 @@ impl GameState {
                             }
                         }
                     }
-                    MenuItemType::InstantText {
-                        text: _,
-                        text_size: _,
-                        text_color: _,
-                    } => {
+                    MenuItemType::InstantText { .. } => {
                         //if text_image.is_none() {
                         //    self.font.execute(|f| {
                         //        let style = FontStyle::new(*text_size, *text_color);
@@ -2518,9 +2891,10 @@ impl GameState {
             }
         }
 
+        let reduced_particles = self.settings.reduced_particles;
         self.player_particles.host_rect = self.player;
-        self.player_particles.update(dt);
-        self.joining_particles.update(dt);
+        self.player_particles.update(dt, reduced_particles);
+        self.joining_particles.update(dt, reduced_particles);
 
         for i in (0..self.expl_conv_p_systems.len()).rev() {
             if self.expl_conv_p_systems[i].update(dt, &mut self.planets) {
@@ -2528,10 +2902,10 @@ impl GameState {
             }
         }
         for planet in &mut self.planets {
-            planet.update(dt);
+            planet.update(dt, reduced_particles);
         }
         for star in &mut self.stars {
-            star.update(dt);
+            star.update(dt, reduced_particles);
         }
 
         if let Some(sl) = &mut self.save_load_notification {
@@ -2552,6 +2926,12 @@ impl GameState {
                         *text = Some(String::from("Loaded the Game!"));
                     }
                 }
+                SaveLoadNotification::LoadHint { timer } => {
+                    *timer -= dt;
+                    if *timer <= 0.0 {
+                        self.save_load_notification = None;
+                    }
+                }
             }
         }
 
@@ -2559,46 +2939,22 @@ impl GameState {
             fish.update(dt);
         }
 
-        #[cfg(target_family = "wasm")]
         if let Some(rx) = &mut self.load_recv {
             let recv_result = rx.try_recv();
             if let Ok(v) = recv_result {
                 if v.is_empty() {
                     self.save_load_notification = Some(SaveLoadNotification::Load {
-                        text: Some(String::from("Failed to load! (callback failure)")),
+                        text: Some(format!(
+                            "Failed to load! (slot {} is empty)",
+                            self.current_slot + 1
+                        )),
+                        timer: SL_NOTIF_TIME,
+                    });
+                } else if let Err(e) = self.apply_loaded(window, &v) {
+                    self.save_load_notification = Some(SaveLoadNotification::Load {
+                        text: Some(format!("Failed to load! ({e})")),
                         timer: SL_NOTIF_TIME,
                     });
-                } else {
-                    let des_result = SaveData::deserialize(&v);
-                    if let Ok((save_data, _)) = des_result {
-                        self.planets = save_data.planets;
-                        self.stars = save_data.stars;
-                        self.fishes = save_data.fishes;
-                        self.player = save_data.player;
-                        self.joining_particles = save_data.joining_particles;
-                        self.expl_conv_p_systems.clear();
-                        self.move_to = Vector::new(self.player.x, self.player.y);
-                        self.camera
-                            .set_view_xy(
-                                self.player.x - WIDTH_F / 2.0,
-                                self.player.y - HEIGHT_F / 2.0,
-                            )
-                            .ok();
-                        self.dbl_click_timeout = None;
-                        self.click_time = None;
-                        self.click_release_time = DOUBLE_CLICK_TIME;
-                        self.state = 10;
-                        self.state_dirty = true;
-                        self.save_load_notification = Some(SaveLoadNotification::Load {
-                            text: None,
-                            timer: SL_NOTIF_TIME,
-                        });
-                    } else {
-                        self.save_load_notification = Some(SaveLoadNotification::Load {
-                            text: Some(String::from("Failed to load! (parse issue)")),
-                            timer: SL_NOTIF_TIME,
-                        });
-                    }
                 }
                 self.load_recv = None;
             } else if recv_result == Err(TryRecvError::Disconnected) {
@@ -2610,12 +2966,133 @@ impl GameState {
             }
         }
 
+        let mut slots_changed = false;
+        let slot_metadata = &mut self.slot_metadata;
+        self.slot_recvs.retain(|(slot, rx)| match rx.try_recv() {
+            Ok(v) => {
+                slot_metadata[*slot] = slot_metadata_from_bytes(&v);
+                slots_changed = true;
+                false
+            }
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => false,
+        });
+        if slots_changed && self.start_screen == StartScreen::SlotPicker {
+            self.menu = Menu::slots(&self.slot_metadata);
+        }
+
+        if let Some(rx) = &self.autosave_recv {
+            match rx.try_recv() {
+                Ok(result) => {
+                    if let Err(e) = result {
+                        self.save_load_notification = Some(SaveLoadNotification::Save {
+                            text: Some(format!("Autosave failed! {}", e)),
+                            timer: SL_NOTIF_TIME,
+                        });
+                    }
+                    self.autosave_recv = None;
+                }
+                Err(TryRecvError::Empty) => (),
+                Err(TryRecvError::Disconnected) => self.autosave_recv = None,
+            }
+        }
+
+        if let Some(rx) = &self.settings_recv {
+            match rx.try_recv() {
+                Ok(v) => {
+                    self.settings_recv = None;
+                    if !v.is_empty() {
+                        match from_bytes::<Settings>(&v) {
+                            Ok((settings, _)) => {
+                                self.settings = settings;
+                                self.apply_settings(window)?;
+                            }
+                            Err(e) => {
+                                self.save_load_notification = Some(SaveLoadNotification::Load {
+                                    text: Some(format!("Failed to load settings! ({e})")),
+                                    timer: SL_NOTIF_TIME,
+                                });
+                            }
+                        }
+                    }
+                }
+                Err(TryRecvError::Empty) => (),
+                Err(TryRecvError::Disconnected) => self.settings_recv = None,
+            }
+        }
+
+        if let Some(scan) = &mut self.recovery_scan {
+            if scan.poll(slot_metadata_from_bytes) {
+                self.autosave_index = scan.next_index();
+                self.save_sequence = self.save_sequence.max(scan.last_sequence());
+                // Only offer the restore if nothing else was started meanwhile.
+                if let Some(snapshot) = scan.take_restore() {
+                    if self.state == 0
+                        && !self.state_dirty
+                        && !self.settings_open
+                        && self.start_screen == StartScreen::Main
+                    {
+                        self.menu = Menu::restore_prompt(&snapshot);
+                        self.current_item = None;
+                        self.start_screen = StartScreen::RestorePrompt;
+                        self.restore_snapshot = Some(snapshot);
+                    }
+                }
+                self.recovery_scan = None;
+            }
+        }
+
+        // Escape cancels rebinding instead of closing the window.
+        window
+            .get_gi_mut()
+            .set_exit_key(self.rebind_action.is_none().then_some(Key::Escape))?;
+
         Ok(())
     }
 
     pub fn draw(&mut self, window: &mut Window) -> Result<(), String> {
         window.get_gi_mut().begin_drawing()?;
         window.get_gi_mut().clear_window(Color::BLACK)?;
+        self.player_particles.draw(window, Transform::IDENTITY);
+        window.get_gi_mut().draw_rect_transform(
+            self.player,
+            Color::from_rgba(255, 255, 255, (self.player_particles.opacity * 255.0) as u8),
+            Transform::translate(self.player.w / 2.0, self.player.h / 2.0)
+                * Transform::rotate(self.player_r),
+            Vector {
+                x: self.player.x + self.player.w / 2.0,
+                y: self.player.y + self.player.h / 2.0,
+            },
+        )?;
+        self.joining_particles.draw(window, Transform::IDENTITY);
+        for expl_conv_ps in &mut self.expl_conv_p_systems {
+            expl_conv_ps.draw(window, Transform::IDENTITY);
+        }
+        for planet in &mut self.planets {
+            planet.draw(window, Transform::IDENTITY);
+        }
+
+        for star in &mut self.stars {
+            star.draw(self.assets.star, window, Transform::IDENTITY)?;
+        }
+
+        for fish in &mut self.fishes {
+            fish.draw(self.assets.fish, window, Transform::IDENTITY)?;
+        }
+
+        if self.gamepad_active && self.is_create_mode && self.state == 10 {
+            let reticle = Vector::new(self.player.x, self.player.y) + self.reticle;
+            window.get_gi_mut().draw_circle(
+                Circle::new(reticle.x, reticle.y, 5.0),
+                Color::from_rgba(0xFF, 0xFF, 0xFF, 0x99),
+            )?;
+        }
+
+        // menus and notifications are drawn on top, without the camera
+        let ui_camera = window.get_gi_mut().get_default_camera()?;
+        window.get_gi_mut().set_camera(ui_camera.as_ref())?;
+        // the menu may have changed since the last update
+        self.relayout_menu(window)?;
         let mut rect = Rectangle::default();
         for mi in &mut self.menu.items {
             rect.x = mi.x;
@@ -2629,14 +3106,20 @@ impl GameState {
                     h_c,
                     c,
                 } => {
-                    if mi.is_hover {
+                    if mi.is_hover || mi.is_focus {
                         window.get_gi_mut().draw_rect(rect, *h_c)?;
                     } else {
                         window.get_gi_mut().draw_rect(rect, *c)?;
                     }
-                    window
-                        .get_font_mut(&self.font)?
-                        .draw(text, 20, rect.x, rect.y, *text_c)?;
+                    let font = window.get_font_mut(self.assets.text)?;
+                    let size = font.measure(text, 20)?;
+                    font.draw(
+                        text,
+                        20,
+                        rect.x + (rect.w - size.x) / 2.0,
+                        rect.y + (rect.h - size.y) / 2.0,
+                        *text_c,
+                    )?;
                 }
                 MenuItemType::AppearingText {
                     text,
@@ -2644,16 +3127,19 @@ impl GameState {
                     text_size: _,
                     text_c,
                     timer: _,
+                    max_width,
+                    lines,
                 } => {
-                    window.get_font_mut(&self.font)?.draw(
-                        if *text_idx < text.len() {
-                            &text[0..*text_idx]
-                        } else {
-                            text
-                        },
+                    let shown = text[0..(*text_idx).min(text.len())]
+                        .chars()
+                        .filter(|c| !c.is_whitespace())
+                        .count();
+                    window.get_font_mut(self.assets.text)?.draw_paragraph(
+                        &text::reveal(lines, shown),
                         20,
                         rect.x,
                         rect.y,
+                        narration_style(*max_width),
                         *text_c,
                     )?;
                 }
@@ -2661,176 +3147,877 @@ impl GameState {
                     text,
                     text_size,
                     text_color,
+                    max_width,
                 } => {
-                    window.get_font_mut(&self.font)?.draw(
+                    window.get_font_mut(self.assets.text)?.draw_paragraph(
                         text,
                         text_size.round() as u32,
                         rect.x,
                         rect.y,
+                        ParagraphStyle::new(*max_width),
                         *text_color,
                     )?;
                 }
                 MenuItemType::Pause {
                     timer: _,
                     length: _,
-                } => (),
-            }
-        }
-        self.player_particles.draw(window, Transform::IDENTITY);
-        window.get_gi_mut().draw_rect_transform(
-            self.player,
-            Color::from_rgba(255, 255, 255, (self.player_particles.opacity * 255.0) as u8),
-            Transform::translate(self.player.w / 2.0, self.player.h / 2.0)
-                * Transform::rotate(self.player_r),
-            Vector {
-                x: self.player.x + self.player.w / 2.0,
-                y: self.player.y + self.player.h / 2.0,
-            },
-        )?;
-        self.joining_particles.draw(window, Transform::IDENTITY);
-        for expl_conv_ps in &mut self.expl_conv_p_systems {
-            expl_conv_ps.draw(window, Transform::IDENTITY);
-        }
-        for planet in &mut self.planets {
-            planet.draw(window, Transform::IDENTITY);
-        }
-
-        for star in &mut self.stars {
-            star.draw(&self.i_star, window, Transform::IDENTITY);
-        }
-
-        for fish in &mut self.fishes {
-            fish.draw(&self.i_fish, window, Transform::IDENTITY);
-        }
-
-        if let Some(sl) = &mut self.save_load_notification {
-            match sl {
-                SaveLoadNotification::Save { text, timer }
-                | SaveLoadNotification::Load { text, timer } => {
-                    if let Some(s) = text {
-                        window.get_font_mut(&self.font)?.draw(
-                            s,
-                            20,
-                            20.0,
-                            20.0,
-                            Color::from_rgba(
-                                255,
-                                255,
-                                255,
-                                ((*timer / SL_NOTIF_TIME) * 255.0) as u8,
-                            ),
-                        )?;
-                    }
-                }
+                } => (),
             }
         }
+        let notification = match &self.save_load_notification {
+            Some(
+                SaveLoadNotification::Save {
+                    text: Some(text),
+                    timer,
+                }
+                | SaveLoadNotification::Load {
+                    text: Some(text),
+                    timer,
+                },
+            ) => Some((text.clone(), *timer)),
+            Some(SaveLoadNotification::LoadHint { timer }) => Some((
+                format!(
+                    "You can load with \"{}\" from here on.",
+                    self.settings.bindings.names(Action::Load)
+                ),
+                *timer,
+            )),
+            _ => None,
+        };
+        if let Some((text, timer)) = notification {
+            window.get_font_mut(self.assets.text)?.draw(
+                &text,
+                20,
+                20.0,
+                20.0,
+                Color::from_rgba(255, 255, 255, ((timer / SL_NOTIF_TIME) * 255.0) as u8),
+            )?;
+        }
+        window.get_gi_mut().set_camera(self.camera.as_ref())?;
         window.get_gi_mut().end_drawing()?;
 
         Ok(())
     }
 
-    #[cfg(not(target_family = "wasm"))]
-    pub fn save(&mut self) -> IOResult<()> {
-        use std::io::Write;
+    /// Reloaded fonts may measure text differently.
+    pub fn fonts_reloaded(&mut self) {
+        self.menu.laid_out = None;
+    }
+
+    fn relayout_menu(&mut self, window: &Window) -> Result<(), String> {
+        let (width, height) = window.get_gi().get_dimensions()?;
+        self.menu.relayout(
+            Vector::new(width, height),
+            window.get_font(self.assets.text)?,
+        )
+    }
 
-        let save_bytes = SaveData {
+    fn save_data(&self) -> SaveData {
+        let (camera_x, camera_y) = self.camera.get_view_xy().unwrap_or((
+            self.player.x - WIDTH_F / 2.0,
+            self.player.y - HEIGHT_F / 2.0,
+        ));
+        SaveData {
             planets: self.planets.clone(),
             stars: self.stars.clone(),
             fishes: self.fishes.clone(),
             player: self.player,
             joining_particles: self.joining_particles.clone(),
+            scene: Some(SceneData {
+                expl_conv_p_systems: self.expl_conv_p_systems.clone(),
+                player_r: self.player_r,
+                player_particles: self.player_particles.clone(),
+                move_to: self.move_to,
+                camera_view: Vector::new(camera_x, camera_y),
+                state: self.state,
+                music_on: self.music_on,
+            }),
         }
-        .serialize();
-        let mut file = File::create(SAVE_FILENAME)?;
-        file.write_all(&save_bytes)?;
+    }
+
+    pub fn save(&mut self) -> IOResult<()> {
+        let save_data = self.save_data();
+        let now = save_slots::now_timestamp();
+        let created = *self.slot_created.get_or_insert(now);
+        self.save_sequence += 1;
+        let metadata = save_data.metadata(created, now, self.save_sequence, self.play_time);
+
+        save_slots::write_slot(
+            &self.storage,
+            self.current_slot,
+            &save_slots::encode_slot(&metadata, &to_bytes(&save_data)),
+        )?;
+        self.slot_metadata[self.current_slot] = Some(metadata);
         self.save_load_notification = Some(SaveLoadNotification::Save {
-            text: None,
+            text: Some(format!("Saved to slot {}.", self.current_slot + 1)),
             timer: SL_NOTIF_TIME,
         });
 
         Ok(())
     }
 
-    #[cfg(not(target_family = "wasm"))]
+    // Writes the next recovery snapshot in the background, overwriting the
+    // oldest one.
+    fn autosave(&mut self) {
+        let save_data = self.save_data();
+        let now = save_slots::now_timestamp();
+        let created = *self.slot_created.get_or_insert(now);
+        self.save_sequence += 1;
+        let metadata = save_data.metadata(created, now, self.save_sequence, self.play_time);
+
+        self.autosave_recv = Some(save_slots::write_snapshot(
+            &self.storage,
+            self.autosave_index,
+            save_slots::encode_snapshot(self.current_slot, &metadata, &to_bytes(&save_data)),
+        ));
+        self.autosave_index = (self.autosave_index + 1) % save_slots::AUTOSAVE_SNAPSHOT_COUNT;
+    }
+
+    fn restore(&mut self, window: &mut Window) {
+        let Some(snapshot) = self.restore_snapshot.take() else {
+            return;
+        };
+        self.current_slot = snapshot.slot;
+        if let Err(e) = self.apply_loaded(window, &snapshot.slot_data) {
+            self.save_load_notification = Some(SaveLoadNotification::Load {
+                text: Some(format!("Failed to restore autosave! ({e})")),
+                timer: SL_NOTIF_TIME,
+            });
+            self.state_dirty = true;
+        }
+    }
+
     pub fn load(&mut self) -> IOResult<()> {
-        use std::io::Read;
+        let receiver = save_slots::read_slot(&self.storage, self.current_slot)?;
 
-        let mut bytes = Vec::new();
-        {
-            let mut file = File::open(SAVE_FILENAME)?;
+        self.load_recv = Some(receiver);
+        self.save_load_notification = Some(SaveLoadNotification::Load {
+            text: Some(String::from("Loading...")),
+            timer: SL_NOTIF_TIME,
+        });
+
+        Ok(())
+    }
 
-            file.read_to_end(&mut bytes)?;
+    fn try_load(&mut self) {
+        let load_result = self.load().map_err(|e| e.to_string());
+        if let Err(s) = load_result {
+            self.save_load_notification = Some(SaveLoadNotification::Load {
+                text: Some(format!("Failed to load! {}", s)),
+                timer: SL_NOTIF_TIME,
+            });
         }
-        let (save_data, _) = SaveData::deserialize(&bytes).map_err(|_| {
-            std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Failed to deserialize into SaveData!",
-            )
-        })?;
+    }
+
+    fn apply_loaded(&mut self, window: &mut Window, data: &[u8]) -> Result<(), String> {
+        let (metadata, save_bytes) =
+            save_slots::decode_slot(data).map_err(|e| format!("parse issue: {e}"))?;
+        let (save_data, _) =
+            from_bytes::<SaveData>(save_bytes).map_err(|e| format!("parse issue: {e}"))?;
 
+        self.slot_created = metadata.as_ref().map(|m| m.created);
+        if let Some(metadata) = &metadata {
+            self.save_sequence = self.save_sequence.max(metadata.sequence);
+        }
+        self.play_time = metadata.map(|m| m.play_time).unwrap_or(0.0);
         self.planets = save_data.planets;
         self.stars = save_data.stars;
         self.fishes = save_data.fishes;
         self.player = save_data.player;
         self.joining_particles = save_data.joining_particles;
-        self.expl_conv_p_systems.clear();
-        self.move_to = Vector::new(self.player.x, self.player.y);
-        self.camera
-            .set_view_xy(
-                self.player.x - WIDTH_F / 2.0,
-                self.player.y - HEIGHT_F / 2.0,
-            )
-            .ok();
+        if let Some(scene) = save_data.scene {
+            self.expl_conv_p_systems = scene.expl_conv_p_systems;
+            self.player_r = scene.player_r;
+            self.player_particles = scene.player_particles;
+            self.move_to = scene.move_to;
+            self.camera
+                .set_view_xy(scene.camera_view.x, scene.camera_view.y)
+                .ok();
+            self.state = scene.state;
+            if self.music_on && !scene.music_on {
+                if let Some(audio) = self.audio {
+                    window.get_music_mut(audio.theme)?.stop()?;
+                }
+                self.music_on = false;
+            }
+        } else {
+            self.expl_conv_p_systems.clear();
+            self.move_to = Vector::new(self.player.x, self.player.y);
+            let (width, height) = window.get_gi().get_dimensions()?;
+            self.camera
+                .set_view_xy(self.player.x - width / 2.0, self.player.y - height / 2.0)
+                .ok();
+            self.state = 10;
+        }
         self.dbl_click_timeout = None;
         self.click_time = None;
-        self.click_release_time = DOUBLE_CLICK_TIME;
-        self.state = 10;
+        self.click_release_time = self.settings.double_click_time;
+        self.zoom = 1.0;
+        self.pan = Vector::new(0.0, 0.0);
+        self.drag = None;
+        self.camera.set_zoom(1.0).ok();
+        self.start_screen = StartScreen::Main;
+        self.autosave_timer = 0.0;
         self.state_dirty = true;
         self.save_load_notification = Some(SaveLoadNotification::Load {
-            text: None,
+            text: Some(format!("Loaded slot {}.", self.current_slot + 1)),
             timer: SL_NOTIF_TIME,
         });
 
         Ok(())
     }
 
-    #[cfg(target_family = "wasm")]
-    pub fn save(&mut self) -> IOResult<()> {
-        let save_bytes = SaveData {
-            planets: self.planets.clone(),
-            stars: self.stars.clone(),
-            fishes: self.fishes.clone(),
-            player: self.player,
-            joining_particles: self.joining_particles.clone(),
-        }
-        .serialize();
+    // Goes straight to creating with a random virtue.
+    fn skip_intro(&mut self) {
+        let colors = [
+            Color::from_rgba(0xAA, 0xCC, 0xFF, 255),
+            Color::from_rgba(0xFF, 0xFF, 0xAA, 255),
+            Color::from_rgba(0xBB, 0xFF, 0xBB, 255),
+            Color::from_rgba(0xFF, 0xAA, 0xAA, 255),
+        ];
+        self.joining_particles.particle_system.color = colors[game_rng().random_range(0..4)];
+        self.state = 10;
+        self.state_dirty = true;
+    }
 
-        crate::wasm_helpers::save_data(&save_bytes)?;
-        self.save_load_notification = Some(SaveLoadNotification::Save {
-            text: None,
+    // Loads a save or an autosave snapshot from any path.
+    fn load_file(&mut self, window: &mut Window, path: &Path) -> Result<(), String> {
+        let data = std::fs::read(path)
+            .map_err(|e| format!("Failed to read \"{}\": {e}", path.display()))?;
+        let slot_data = match save_slots::decode_snapshot(0, &data) {
+            Ok(snapshot) => {
+                self.current_slot = snapshot.slot;
+                snapshot.slot_data
+            }
+            Err(_) => data,
+        };
+        self.apply_loaded(window, &slot_data)
+            .map_err(|e| format!("Failed to load \"{}\": {e}", path.display()))?;
+        self.save_load_notification = Some(SaveLoadNotification::Load {
+            text: Some(format!("Loaded {}.", path.display())),
             timer: SL_NOTIF_TIME,
         });
 
         Ok(())
     }
 
-    #[cfg(target_family = "wasm")]
-    pub fn load(&mut self) -> IOResult<()> {
-        let receiver = crate::wasm_helpers::load_data()?;
+    fn create(&mut self, window: &mut Window, click_pos: Vector) -> Result<(), String> {
+        if self.state == 8 {
+            let mut expl_conv_system = ExplConvParticleSystem::new(
+                1.5,
+                Circle::new(click_pos.x, click_pos.y, 20.0),
+                Color::from_rgba(0x99, 0xFF, 0x99, 255),
+                1.0,
+            );
+            expl_conv_system.activate(30, 200.0, self.settings.reduced_particles);
+            self.expl_conv_p_systems.push(expl_conv_system);
+            self.state = 9;
+            self.state_dirty = true;
+            if let Some(audio) = self.audio {
+                window
+                    .get_sound_mut(audio.boom)?
+                    .play(0.8 * self.settings.sfx_volume)?;
+            }
+        } else if self.state == 10 {
+            let mut rng = game_rng();
+            let rand_out = rng.random_range(0.0..1.0);
+            if rand_out < 0.6 {
+                // spawn planet
+                let mut expl_conv_system = ExplConvParticleSystem::new(
+                    rng.random_range(1.2..1.6),
+                    Circle::new(click_pos.x, click_pos.y, rng.random_range(15.0..25.0)),
+                    Color::from_rgba(
+                        rng.random_range(0x44..0xFF),
+                        rng.random_range(0x44..0xFF),
+                        rng.random_range(0x44..0xFF),
+                        255,
+                    ),
+                    1.0,
+                );
+                expl_conv_system.activate(
+                    rng.random_range(13..40),
+                    rng.random_range(150.0..300.0),
+                    self.settings.reduced_particles,
+                );
+                self.expl_conv_p_systems.push(expl_conv_system);
+            } else if rand_out < 0.85 {
+                // spawn star
+                let rot_clockwise = rng.random_bool(0.5);
+                self.stars.push(Star::new(
+                    Circle::new(click_pos.x, click_pos.y, rng.random_range(3.0..7.0)),
+                    Color::from_rgba(
+                        rng.random_range(0x58..0xFF),
+                        rng.random_range(0x58..0xFF),
+                        rng.random_range(0x58..0xFF),
+                        255,
+                    ),
+                    if rot_clockwise {
+                        rng.random_range(0.1..0.3)
+                    } else {
+                        rng.random_range(-0.3..-0.1)
+                    },
+                    rng.random_range(0.0..90.0),
+                    self.settings.reduced_particles,
+                ));
+            } else {
+                // spawn fish
+                for _ in 0..rng.random_range(1..4) {
+                    self.fishes.push(Fish::new(
+                        click_pos,
+                        rng.random_range(0.0..360.0),
+                        Color::from_rgba(
+                            rng.random_range(0x44..0xFF),
+                            rng.random_range(0x44..0xFF),
+                            rng.random_range(0x44..0xFF),
+                            255,
+                        ),
+                    ));
+                }
+            }
+            if let Some(audio) = self.audio {
+                window
+                    .get_sound_mut(audio.boom)?
+                    .play(0.8 * self.settings.sfx_volume)?;
+            }
+        }
 
-        self.load_recv = Some(receiver);
-        self.save_load_notification = Some(SaveLoadNotification::Save {
-            text: Some(String::from("Loading...")),
-            timer: SL_NOTIF_TIME,
-        });
+        Ok(())
+    }
+
+    fn apply_settings(&mut self, window: &mut Window) -> Result<(), String> {
+        let gi = window.get_gi_mut();
+        gi.set_master_volume(if self.muted {
+            0.0
+        } else {
+            self.settings.master_volume
+        })?;
+        gi.set_fullscreen(self.settings.fullscreen || self.force_fullscreen)?;
+        gi.set_viewport(Viewport {
+            virtual_size: Vector::new(WIDTH_F, HEIGHT_F),
+            mode: self.settings.scale_mode,
+        })?;
+        if let Some(audio) = self.audio {
+            window
+                .get_music_mut(audio.theme)?
+                .set_volume(MUSIC_VOLUME * self.settings.music_volume)?;
+        }
+        self.gestures
+            .set_double_tap_time(self.settings.double_click_time);
+
+        Ok(())
+    }
+
+    fn open_settings(&mut self) {
+        self.settings_open = true;
+        self.controls_open = false;
+        self.rebind_action = None;
+        self.menu = Menu::settings(&self.settings);
+        self.current_item = None;
+        self.current_finished = false;
+        self.selection_mode = true;
+        self.is_create_mode = false;
+        self.click_time = None;
+    }
+
+    fn settings_clicked(&mut self, window: &mut Window, idx: usize) -> Result<(), String> {
+        if idx == SETTINGS_ITEM_COUNT {
+            self.controls_open = true;
+            self.menu = Menu::controls(&self.settings.bindings);
+            self.current_item = None;
+            return Ok(());
+        } else if idx > SETTINGS_ITEM_COUNT {
+            // back
+            self.state_dirty = true;
+            return Ok(());
+        }
+
+        self.settings.cycle(idx);
+        if idx == settings::FULLSCREEN_ITEM {
+            self.force_fullscreen = false;
+        }
+        self.apply_settings(window)?;
+        self.menu.set_button_text(idx, self.settings.item_text(idx));
+        self.write_settings();
 
         Ok(())
     }
+
+    fn controls_clicked(&mut self, idx: usize) {
+        if idx < ACTION_COUNT {
+            let action = Action::ALL[idx];
+            self.rebind_action = Some(action);
+            self.menu.set_button_text(
+                idx,
+                format!(
+                    "{} (press a key or button, Escape to cancel)",
+                    self.settings.bindings.describe(action)
+                ),
+            );
+        } else if idx == ACTION_COUNT {
+            self.settings.bindings = InputMap::default();
+            self.menu = Menu::controls(&self.settings.bindings);
+            self.current_item = None;
+            self.write_settings();
+        } else {
+            // back
+            self.open_settings();
+        }
+    }
+
+    fn rebind(&mut self, action: Action, binding: Binding) {
+        if self.settings.bindings.toggle(action, binding) {
+            self.write_settings();
+        }
+        self.cancel_rebind(action);
+    }
+
+    fn cancel_rebind(&mut self, action: Action) {
+        self.rebind_action = None;
+        self.menu
+            .set_button_text(action as usize, self.settings.bindings.describe(action));
+    }
+
+    fn write_settings(&mut self) {
+        // Changes made before the saved settings arrived win.
+        self.settings_recv = None;
+        if let Err(e) = settings::write_settings(&self.storage, &self.settings) {
+            self.save_load_notification = Some(SaveLoadNotification::Save {
+                text: Some(format!("Failed to save settings! {}", e)),
+                timer: SL_NOTIF_TIME,
+            });
+        }
+    }
+
+    fn open_slot_picker(&mut self) {
+        self.start_screen = StartScreen::SlotPicker;
+        self.menu = Menu::slots(&self.slot_metadata);
+        self.current_item = None;
+        self.slot_recvs.clear();
+        for slot in 0..SAVE_SLOT_COUNT {
+            match save_slots::read_slot(&self.storage, slot) {
+                Ok(rx) => self.slot_recvs.push((slot, rx)),
+                Err(e) => {
+                    self.save_load_notification = Some(SaveLoadNotification::Load {
+                        text: Some(format!("Failed to read slot {}! {}", slot + 1, e)),
+                        timer: SL_NOTIF_TIME,
+                    });
+                }
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agnostic_interface::headless_impl::{HeadlessController, HeadlessGame};
+    use crate::agnostic_interface::{GamepadAxis, ScaleMode};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Keeps each test's saves and settings apart from the real ones and from
+    // the other tests running in parallel.
+    struct TestDataDir(PathBuf);
+
+    impl TestDataDir {
+        fn new() -> Self {
+            static COUNT: AtomicUsize = AtomicUsize::new(0);
+            Self(std::env::temp_dir().join(format!(
+                "ld45_data_{}_{}",
+                std::process::id(),
+                COUNT.fetch_add(1, Ordering::Relaxed)
+            )))
+        }
+
+        fn options(&self) -> LaunchOptions {
+            LaunchOptions {
+                data_dir: Some(self.0.clone()),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl Drop for TestDataDir {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    /// A headless game with its own data dir, which has to outlive the game.
+    fn headless(options: LaunchOptions) -> (Window, HeadlessController, GameState, TestDataDir) {
+        let (gi, controller) = HeadlessGame::new_boxed(800, 600);
+        let mut window = Window::new(gi);
+        let data_dir = TestDataDir::new();
+        let options = LaunchOptions {
+            data_dir: data_dir.options().data_dir,
+            ..options
+        };
+        let game_state =
+            GameState::with_options(&mut window, &options).expect("GameState should load headless");
+        (window, controller, game_state, data_dir)
+    }
+
+    fn frame(game_state: &mut GameState, window: &mut Window) {
+        game_state.update(window).unwrap();
+        game_state.draw(window).unwrap();
+    }
+
+    /// Skips the intro and finishes its text, so the game can be played.
+    fn skip_to_play() -> (Window, HeadlessController, GameState, TestDataDir) {
+        let (mut window, controller, mut game_state, data_dir) = headless(LaunchOptions {
+            skip_intro: true,
+            ..Default::default()
+        });
+        frame(&mut game_state, &mut window);
+        controller.press_gamepad_button(0, GamepadButton::FaceDown);
+        frame(&mut game_state, &mut window);
+        assert!(game_state.current_finished);
+        (window, controller, game_state, data_dir)
+    }
+
+    /// How many things were created so far.
+    fn created(game_state: &GameState) -> usize {
+        game_state.expl_conv_p_systems.len() + game_state.stars.len() + game_state.fishes.len()
+    }
+
+    #[test]
+    fn test_headless_start_game() {
+        let (mut window, controller, mut game_state, _data_dir) = headless(Default::default());
+        assert_eq!(
+            controller.get_image_dimensions("static/fish.png"),
+            Some((48, 16))
+        );
+
+        frame(&mut game_state, &mut window);
+        assert!(controller.is_sound_loaded("static/boom.mp3"));
+        assert_eq!(game_state.state, 0);
+
+        controller.click(WIDTH_F / 2.0, 200.0);
+        frame(&mut game_state, &mut window);
+        assert_eq!(game_state.state, 1);
+        assert_eq!(controller.get_frame_count(), 2);
+        assert!(controller.get_draw_calls() > 0);
+
+        for _ in 0..120 {
+            frame(&mut game_state, &mut window);
+        }
+        assert!(controller.get_sound_play_count("static/tap.mp3") > 0);
+    }
+
+    #[test]
+    fn test_headless_narration_pitch() {
+        let (window, _controller, mut game_state, _data_dir) = headless(Default::default());
+        game_state.menu = Menu::s_01();
+        game_state.relayout_menu(&window).unwrap();
+        let lines: Vec<f32> = game_state
+            .menu
+            .items
+            .iter()
+            .filter(|mi| matches!(mi.item_type, MenuItemType::AppearingText { .. }))
+            .map(|mi| mi.y)
+            .collect();
+        assert_eq!(
+            lines[..3],
+            [HEIGHT_F - 140.0, HEIGHT_F - 100.0, HEIGHT_F - 60.0]
+        );
+
+        // the first line wraps and its second line keeps the same pitch
+        game_state.menu = Menu::s_07();
+        game_state.relayout_menu(&window).unwrap();
+        let items = &game_state.menu.items;
+        assert_eq!(items[0].h, 60.0);
+        match &items[0].item_type {
+            MenuItemType::AppearingText { lines, .. } => assert_eq!(lines.len(), 2),
+            _ => unreachable!(),
+        }
+        assert_eq!(items[0].y, HEIGHT_F - 130.0);
+        assert_eq!(items[2].y, HEIGHT_F - 50.0);
+    }
+
+    #[test]
+    fn test_help_names_bindings() {
+        let mut bindings = InputMap::default();
+        bindings.toggle(Action::Load, Binding::key(Key::K));
+        bindings.toggle(Action::Load, Binding::key(Key::L));
+        let menu = Menu::s_10(&bindings);
+        let MenuItemType::InstantText { text, .. } = &menu.items[1].item_type else {
+            panic!("the keys are listed in instant text");
+        };
+        assert_eq!(
+            text,
+            "S - save; K - load (can load from the start); R - reset"
+        );
+    }
+
+    #[test]
+    fn test_headless_slot_picker() {
+        let (mut window, controller, mut game_state, _data_dir) = headless(Default::default());
+        game_state.update(&mut window).unwrap();
+
+        controller.click(WIDTH_F / 2.0, 350.0);
+        game_state.update(&mut window).unwrap();
+        assert_eq!(game_state.start_screen, StartScreen::SlotPicker);
+        assert_eq!(game_state.menu.items.len(), SAVE_SLOT_COUNT + 2);
+        match &game_state.menu.items[1].item_type {
+            MenuItemType::Button { text, .. } => assert!(text.starts_with("Slot 2: ")),
+            _ => panic!("Slot items should be buttons"),
+        }
+
+        // back
+        controller.click(WIDTH_F / 2.0, 130.0 + SAVE_SLOT_COUNT as f32 * 100.0 + 25.0);
+        game_state.update(&mut window).unwrap();
+        assert_eq!(game_state.start_screen, StartScreen::Main);
+        assert_eq!(game_state.state, 0);
+    }
+
+    #[test]
+    fn test_headless_gamepad() {
+        let (mut window, controller, mut game_state, _data_dir) = headless(Default::default());
+        frame(&mut game_state, &mut window);
+
+        controller.press_gamepad_button(0, GamepadButton::DpadDown);
+        frame(&mut game_state, &mut window);
+        assert_eq!(game_state.current_item, Some(0));
+        assert!(game_state.menu.items[0].is_focus);
+        controller.press_gamepad_button(0, GamepadButton::FaceDown);
+        frame(&mut game_state, &mut window);
+        assert_eq!(game_state.state, 1);
+
+        let (mut window, controller, mut game_state, _data_dir) = skip_to_play();
+        let start = game_state.move_to;
+        controller.set_gamepad_axis(0, GamepadAxis::LeftX, 1.0);
+        controller.set_gamepad_axis(0, GamepadAxis::RightY, -1.0);
+        for _ in 0..30 {
+            frame(&mut game_state, &mut window);
+        }
+        assert!(game_state.move_to.x > start.x);
+        assert_eq!(game_state.move_to.y, start.y);
+        assert_eq!(game_state.reticle.y, -RETICLE_RANGE);
+
+        controller.set_gamepad_axis(0, GamepadAxis::LeftX, 0.0);
+        let before = created(&game_state);
+        controller.press_gamepad_button(0, GamepadButton::FaceDown);
+        game_state.update(&mut window).unwrap();
+        assert!(created(&game_state) > before);
+    }
+
+    #[test]
+    fn test_headless_appearing_unicode_text() {
+        let (mut window, controller, mut game_state, _data_dir) = headless(Default::default());
+        assert!(controller.is_font_loaded("static/ClearSans-Regular.ttf"));
+        let mut menu = Menu::new();
+        menu.add(Menu::text(40.0, true, "Ça — привет, 世界"));
+        game_state.menu = menu;
+        // every prefix gets drawn on the way
+        for _ in 0..300 {
+            frame(&mut game_state, &mut window);
+        }
+        match &game_state.menu.items[0].item_type {
+            MenuItemType::AppearingText { text, text_idx, .. } => {
+                assert_eq!(*text_idx, text.len())
+            }
+            _ => unreachable!(),
+        }
+        assert!(game_state.current_finished);
+    }
+
+    #[test]
+    fn test_headless_zoom_and_pan() {
+        let (mut window, controller, mut game_state, _data_dir) = skip_to_play();
+
+        controller.scroll_wheel(2.0);
+        frame(&mut game_state, &mut window);
+        assert!((game_state.zoom - ZOOM_STEP * ZOOM_STEP).abs() < 1e-5);
+        assert!(controller.get_camera_zoom() > 1.0);
+        controller.scroll_wheel(-100.0);
+        frame(&mut game_state, &mut window);
+        assert_eq!(game_state.zoom, ZOOM_MIN);
+
+        let before = created(&game_state);
+        controller.set_mouse_xy(100.0, 100.0);
+        controller.press_mouse_button(MouseButton::Right);
+        controller.set_mouse_xy(150.0, 100.0);
+        controller.release_mouse_button(MouseButton::Right);
+        frame(&mut game_state, &mut window);
+        assert_eq!(created(&game_state), before);
+        assert!(game_state.pan.x < 0.0);
+
+        // pressed, moved and released in separate frames
+        let pan = game_state.pan;
+        controller.press_mouse_button(MouseButton::Middle);
+        frame(&mut game_state, &mut window);
+        for x in [130.0, 110.0] {
+            controller.set_mouse_xy(x, 100.0);
+            frame(&mut game_state, &mut window);
+        }
+        controller.release_mouse_button(MouseButton::Middle);
+        frame(&mut game_state, &mut window);
+        assert!(game_state.pan.x > pan.x + 10.0);
+        assert_eq!(game_state.pan.y, pan.y);
+        assert!(game_state.drag.is_none());
+
+        controller.press_mouse_button(MouseButton::Right);
+        controller.release_mouse_button(MouseButton::Right);
+        game_state.update(&mut window).unwrap();
+        assert!(created(&game_state) > before);
+    }
+
+    #[test]
+    fn test_headless_right_click_bound_to_create() {
+        let (mut window, controller, mut game_state, _data_dir) = skip_to_play();
+        game_state
+            .settings
+            .bindings
+            .toggle(Action::Create, Binding::Mouse(MouseButton::Right));
+        let before = created(&game_state);
+        let pan = game_state.pan;
+        controller.set_mouse_xy(100.0, 100.0);
+        controller.press_mouse_button(MouseButton::Right);
+        frame(&mut game_state, &mut window);
+        controller.set_mouse_xy(150.0, 100.0);
+        controller.release_mouse_button(MouseButton::Right);
+        frame(&mut game_state, &mut window);
+        assert!(created(&game_state) > before);
+        assert!(game_state.drag.is_none());
+        assert_eq!(game_state.pan, pan);
+    }
+
+    #[test]
+    fn test_headless_touch() {
+        let (mut window, controller, mut game_state, _data_dir) = skip_to_play();
+
+        let start = game_state.move_to;
+        controller.touch(0, 700.0, 500.0);
+        frame(&mut game_state, &mut window);
+        controller.lift_touch(0);
+        for _ in 0..60 {
+            frame(&mut game_state, &mut window);
+        }
+        assert!(game_state.move_to.x > start.x);
+
+        let before = created(&game_state);
+        for _ in 0..2 {
+            controller.touch(1, 300.0, 300.0);
+            frame(&mut game_state, &mut window);
+            controller.lift_touch(1);
+            frame(&mut game_state, &mut window);
+        }
+        assert!(created(&game_state) > before);
+
+        controller.touch(2, 300.0, 300.0);
+        controller.touch(3, 400.0, 300.0);
+        frame(&mut game_state, &mut window);
+        controller.touch(2, 250.0, 320.0);
+        controller.touch(3, 450.0, 320.0);
+        frame(&mut game_state, &mut window);
+        assert!(game_state.pan.y < 0.0);
+        assert!((game_state.zoom - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_headless_resized_window() {
+        for scale_mode in [ScaleMode::Letterbox, ScaleMode::Expand] {
+            let (mut window, controller, mut game_state, _data_dir) = headless(Default::default());
+            game_state.settings.scale_mode = scale_mode;
+            game_state.apply_settings(&mut window).unwrap();
+            controller.set_dimensions(1200.0, 600.0);
+            frame(&mut game_state, &mut window);
+
+            // the start button stays centered either way
+            controller.click(600.0, 200.0);
+            frame(&mut game_state, &mut window);
+            assert_eq!(game_state.state, 1, "{scale_mode:?}");
+        }
+    }
+
+    #[test]
+    fn test_headless_launch_options() {
+        let data_dir = TestDataDir::new();
+        let options = LaunchOptions {
+            seed: Some(45),
+            skip_intro: true,
+            ..data_dir.options()
+        };
+        let mut sessions = Vec::new();
+        for _ in 0..2 {
+            let (gi, _controller) = HeadlessGame::new_boxed(800, 600);
+            let mut window = Window::new(gi);
+            let mut game_state = GameState::with_options(&mut window, &options)
+                .expect("GameState should load headless");
+            game_state.update(&mut window).unwrap();
+            assert_eq!(game_state.state, 10);
+            game_state.planets.push(Planet::new(
+                Circle::new(1.0, 2.0, 3.0),
+                Color::from_rgba(4, 5, 6, 255),
+            ));
+            sessions.push((
+                game_state.joining_particles.particle_system.color,
+                game_state.save_data(),
+            ));
+        }
+        assert_eq!(sessions[0], sessions[1]);
+
+        let path = std::env::temp_dir().join(format!("ld45_load_{}.bin", std::process::id()));
+        let save_data = sessions.pop().unwrap().1;
+        std::fs::write(
+            &path,
+            save_slots::encode_snapshot(
+                2,
+                &save_data.metadata(0, 0, 0, 0.0),
+                &to_bytes(&save_data),
+            ),
+        )
+        .unwrap();
+        let (gi, _controller) = HeadlessGame::new_boxed(800, 600);
+        let mut window = Window::new(gi);
+        let game_state = GameState::with_options(
+            &mut window,
+            &LaunchOptions {
+                load: Some(path.clone()),
+                ..data_dir.options()
+            },
+        )
+        .expect("Should load the snapshot given on the command line");
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(game_state.current_slot, 2);
+        assert_eq!(game_state.planets, save_data.planets);
+    }
+
+    #[test]
+    fn test_headless_cancel_rebind() {
+        let (mut window, controller, mut game_state, _data_dir) = headless(Default::default());
+        game_state.update(&mut window).unwrap();
+        game_state.open_settings();
+        game_state
+            .settings_clicked(&mut window, SETTINGS_ITEM_COUNT)
+            .unwrap();
+        game_state.controls_clicked(Action::Save as usize);
+        frame(&mut game_state, &mut window);
+        assert_eq!(controller.get_exit_key(), None);
+
+        controller.hold_key(Key::LeftShift);
+        frame(&mut game_state, &mut window);
+        assert_eq!(game_state.rebind_action, Some(Action::Save));
+
+        assert!(game_state.menu.laid_out.is_some());
+        controller.hold_key(Key::Escape);
+        game_state.update(&mut window).unwrap();
+        assert_eq!(game_state.rebind_action, None);
+        // the button's text changed back, so it is measured again
+        assert!(game_state.menu.laid_out.is_none());
+        assert_eq!(game_state.settings.bindings, InputMap::default());
+        assert_eq!(controller.get_exit_key(), Some(Key::Escape));
+    }
+
+    #[test]
+    fn test_reduced_particles() {
+        let circle = Circle::new(0.0, 0.0, 1.0);
+        let mut full = ExplConvParticleSystem::new(1.0, circle, Color::WHITE, 1.0);
+        full.activate(31, 10.0, false);
+        let mut reduced = ExplConvParticleSystem::new(1.0, circle, Color::WHITE, 1.0);
+        reduced.activate(31, 10.0, true);
+        assert_eq!(full.particles.len(), 31);
+        assert_eq!(reduced.particles.len(), 16);
+    }
 
     #[test]
     fn test_de_serialize_particle() {
@@ -2849,9 +4036,9 @@ mod tests {
         particle.r = 11.0;
         particle.lifetime = 12.0;
         particle.life_timer = 13.0;
-        let bytes = particle.serialize();
+        let bytes = to_bytes(&particle);
         let (des_particle, size) =
-            Particle::deserialize(&bytes, 0).expect("Should be able to deserialize Particle!");
+            from_bytes::<Particle>(&bytes).expect("Should be able to deserialize Particle!");
         assert_eq!(particle, des_particle);
         assert_eq!(bytes.len(), size);
 
@@ -2870,9 +4057,9 @@ mod tests {
         particle.r = 24.0;
         particle.lifetime = 25.0;
         particle.life_timer = 26.0;
-        let bytes = particle.serialize();
+        let bytes = to_bytes(&particle);
         let (des_particle, size) =
-            Particle::deserialize(&bytes, 0).expect("Should be able to deserialize Particle!");
+            from_bytes::<Particle>(&bytes).expect("Should be able to deserialize Particle!");
         assert_eq!(particle, des_particle);
         assert_eq!(bytes.len(), size);
     }
@@ -2934,8 +4121,8 @@ mod tests {
         ps.opacity = 43.0;
         ps.vel_multiplier = 44.0;
 
-        let bytes = ps.serialize();
-        let (des_ps, size) = ParticleSystem::deserialize(&bytes, 0)
+        let bytes = to_bytes(&ps);
+        let (des_ps, size) = from_bytes::<ParticleSystem>(&bytes)
             .expect("Should be able to deserialize ParticleSystem!");
         assert_eq!(ps, des_ps);
         assert_eq!(bytes.len(), size);
@@ -2995,8 +4182,8 @@ mod tests {
         ps.opacity = 87.0;
         ps.vel_multiplier = 88.0;
 
-        let bytes = ps.serialize();
-        let (des_ps, size) = ParticleSystem::deserialize(&bytes, 0)
+        let bytes = to_bytes(&ps);
+        let (des_ps, size) = from_bytes::<ParticleSystem>(&bytes)
             .expect("Should be able to deserialize ParticleSystem!");
         assert_eq!(ps, des_ps);
         assert_eq!(bytes.len(), size);
@@ -3059,19 +4246,39 @@ mod tests {
         ps.opacity = 43.0;
         ps.vel_multiplier = 44.0;
 
-        let mut rps = RotatingParticleSystem::default();
-        rps.particle_system = ps;
-        rps.r = 45.0;
-        rps.velr = 46.0;
-        rps.offset = 47.0;
+        let rps = RotatingParticleSystem {
+            particle_system: ps,
+            r: 45.0,
+            velr: 46.0,
+            offset: 47.0,
+        };
 
-        let bytes = rps.serialize();
-        let (des_rps, size) = RotatingParticleSystem::deserialize(&bytes, 0)
+        let bytes = to_bytes(&rps);
+        let (des_rps, size) = from_bytes::<RotatingParticleSystem>(&bytes)
             .expect("Should be able to deserialize RotatingParticleSystem!");
         assert_eq!(rps, des_rps);
         assert_eq!(bytes.len(), size);
     }
 
+    #[test]
+    fn test_de_serialize_expl_conv_particle_system() {
+        let mut expl_conv_ps = ExplConvParticleSystem::new(
+            1.0,
+            Circle::new(2.0, 3.0, 4.0),
+            Color::from_rgba(5, 6, 7, 8),
+            9.0,
+        );
+        expl_conv_ps.activate(2, 10.0, false);
+        expl_conv_ps.particles[1].circle.x = 11.0;
+        expl_conv_ps.life_timer = 12.0;
+
+        let bytes = to_bytes(&expl_conv_ps);
+        let (des_expl_conv_ps, size) = from_bytes::<ExplConvParticleSystem>(&bytes)
+            .expect("Should be able to deserialize ExplConvParticleSystem!");
+        assert_eq!(expl_conv_ps, des_expl_conv_ps);
+        assert_eq!(bytes.len(), size);
+    }
+
     #[test]
     fn test_de_serialize_planet() {
         let mut planet = Planet::default();
@@ -3137,23 +4344,25 @@ mod tests {
         planet.particle_system.opacity = 50.0;
         planet.particle_system.vel_multiplier = 51.0;
 
-        let mut rps = RotatingParticleSystem::default();
-        rps.particle_system = planet.particle_system.clone();
-        rps.r = 52.0;
-        rps.velr = 53.0;
-        rps.offset = 54.0;
+        let rps = RotatingParticleSystem {
+            particle_system: planet.particle_system.clone(),
+            r: 52.0,
+            velr: 53.0,
+            offset: 54.0,
+        };
         planet.moons.push(rps);
 
-        let mut rps = RotatingParticleSystem::default();
-        rps.particle_system = ParticleSystem::default();
-        rps.r = 55.0;
-        rps.velr = 56.0;
-        rps.offset = 57.0;
+        let rps = RotatingParticleSystem {
+            particle_system: ParticleSystem::default(),
+            r: 55.0,
+            velr: 56.0,
+            offset: 57.0,
+        };
         planet.moons.push(rps);
 
-        let bytes = planet.serialize();
+        let bytes = to_bytes(&planet);
         let (des_planet, size) =
-            Planet::deserialize(&bytes, 0).expect("Should be able to deserialize Planet!");
+            from_bytes::<Planet>(&bytes).expect("Should be able to deserialize Planet!");
         assert_eq!(planet, des_planet);
         assert_eq!(bytes.len(), size);
     }
@@ -3223,9 +4432,9 @@ mod tests {
         star.velr = 49.0;
         star.r = 50.0;
 
-        let bytes = star.serialize();
+        let bytes = to_bytes(&star);
         let (des_star, size) =
-            Star::deserialize(&bytes, 0).expect("Should be able to deserialize Star!");
+            from_bytes::<Star>(&bytes).expect("Should be able to deserialize Star!");
         assert_eq!(star, des_star);
         assert_eq!(bytes.len(), size);
     }
@@ -3254,9 +4463,9 @@ mod tests {
         fish.tail_rect.w = 19.0;
         fish.tail_rect.h = 20.0;
 
-        let bytes = fish.serialize();
+        let bytes = to_bytes(&fish);
         let (des_fish, size) =
-            Fish::deserialize(&bytes, 0).expect("Should be able to deserialize Fish!");
+            from_bytes::<Fish>(&bytes).expect("Should be able to deserialize Fish!");
         assert_eq!(fish, des_fish);
         assert_eq!(bytes.len(), size);
     }
@@ -3327,18 +4536,20 @@ mod tests {
         planet.particle_system.opacity = 50.0;
         planet.particle_system.vel_multiplier = 51.0;
 
-        let mut rps = RotatingParticleSystem::default();
-        rps.particle_system = planet.particle_system.clone();
-        rps.r = 52.0;
-        rps.velr = 53.0;
-        rps.offset = 54.0;
+        let rps = RotatingParticleSystem {
+            particle_system: planet.particle_system.clone(),
+            r: 52.0,
+            velr: 53.0,
+            offset: 54.0,
+        };
         planet.moons.push(rps);
 
-        let mut rps = RotatingParticleSystem::default();
-        rps.particle_system = ParticleSystem::default();
-        rps.r = 55.0;
-        rps.velr = 56.0;
-        rps.offset = 57.0;
+        let rps = RotatingParticleSystem {
+            particle_system: ParticleSystem::default(),
+            r: 55.0,
+            velr: 56.0,
+            offset: 57.0,
+        };
         planet.moons.push(rps);
 
         save_data.planets.push(planet.clone());
@@ -3537,10 +4748,182 @@ mod tests {
         save_data.joining_particles.velr = 46.0;
         save_data.joining_particles.offset = 47.0;
 
-        let bytes = save_data.serialize();
+        let mut expl_conv_ps = ExplConvParticleSystem::new(
+            48.0,
+            Circle::new(49.0, 50.0, 51.0),
+            Color::from_rgba(52, 53, 54, 55),
+            56.0,
+        );
+        expl_conv_ps.activate(3, 57.0, false);
+        expl_conv_ps.life_timer = 58.0;
+        save_data.scene = Some(SceneData {
+            expl_conv_p_systems: vec![expl_conv_ps],
+            player_r: 59.0,
+            player_particles: save_data.joining_particles.particle_system.clone(),
+            move_to: Vector::new(60.0, 61.0),
+            camera_view: Vector::new(62.0, 63.0),
+            state: 9,
+            music_on: true,
+        });
+
+        let bytes = to_bytes(&save_data);
         let (des_save_data, size) =
-            SaveData::deserialize(&bytes).expect("Should be able to deserialize SaveData!");
+            from_bytes::<SaveData>(&bytes).expect("Should be able to deserialize SaveData!");
         assert_eq!(save_data, des_save_data);
         assert_eq!(bytes.len(), size);
     }
+
+    #[test]
+    fn test_de_serialize_save_data_v1_migration() {
+        let mut save_data = SaveData::default();
+        let mut planet = Planet {
+            circle: Circle::new(1.0, 2.0, 3.0),
+            color: Color::from_rgba(4, 5, 6, 7),
+            ..Default::default()
+        };
+        let mut rps = RotatingParticleSystem::default();
+        rps.particle_system.particles.push(Particle::default());
+        rps.r = 8.0;
+        planet.moons.push(rps.clone());
+        planet.moons.push(rps);
+        save_data.planets.push(planet);
+        save_data.stars.push(Star::default());
+        save_data.fishes.push(Fish::default());
+        save_data.fishes.push(Fish::default());
+        save_data.player = Rectangle::new(9.0, 10.0, 11.0, 12.0);
+        save_data
+            .joining_particles
+            .particle_system
+            .particles
+            .push(Particle::default());
+
+        let bytes = to_bytes(&save_data);
+        assert_eq!(
+            bytes[SAVE_DATA_IDENTIFIER.len()..(SAVE_DATA_IDENTIFIER.len() + 4)],
+            SAVE_DATA_VERSION.to_be_bytes()
+        );
+
+        for len_size in [4, 8] {
+            let mut w = Writer::with_len_size(len_size);
+            w.write_bytes(&SAVE_DATA_IDENTIFIER);
+            encode_legacy_body(&save_data, &mut w);
+            let v1_bytes = w.into_bytes();
+            let (des_save_data, size) = from_bytes::<SaveData>(&v1_bytes)
+                .expect("Should be able to deserialize version 1 SaveData!");
+            assert_eq!(save_data, des_save_data);
+            assert_eq!(v1_bytes.len(), size);
+        }
+
+        let mut w = Writer::new();
+        w.write_bytes(&SAVE_DATA_IDENTIFIER);
+        w.write(&2u32);
+        encode_legacy_body(&save_data, &mut w);
+        let v2_bytes = w.into_bytes();
+        let (des_save_data, size) = from_bytes::<SaveData>(&v2_bytes)
+            .expect("Should be able to deserialize version 2 SaveData!");
+        assert_eq!(save_data, des_save_data);
+        assert_eq!(v2_bytes.len(), size);
+
+        let mut w = Writer::new();
+        encode_legacy_body(&save_data, &mut w);
+        let body = w.into_bytes();
+        let mut w = Writer::new();
+        w.write_bytes(&SAVE_DATA_IDENTIFIER);
+        w.write(&3u32);
+        w.write_bytes(&body);
+        w.write(&crc32(&body));
+        let v3_bytes = w.into_bytes();
+        let (des_save_data, size) = from_bytes::<SaveData>(&v3_bytes)
+            .expect("Should be able to deserialize version 3 SaveData!");
+        assert_eq!(save_data, des_save_data);
+        assert_eq!(v3_bytes.len(), size);
+    }
+
+    // Body of saves before version 4, without particle systems of planets and
+    // stars and without the scene.
+    fn encode_legacy_body(save_data: &SaveData, w: &mut Writer) {
+        w.write_len(save_data.planets.len());
+        for planet in &save_data.planets {
+            w.write(&planet.circle);
+            w.write(&planet.color);
+            w.write(&planet.moons);
+        }
+        w.write_len(save_data.stars.len());
+        for star in &save_data.stars {
+            w.write(&star.color);
+            w.write(&star.velr);
+            w.write(&star.r);
+        }
+        w.write(&save_data.fishes);
+        w.write(&save_data.player);
+        w.write(&save_data.joining_particles);
+    }
+
+    fn checksummed_save_bytes(body: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::from(SAVE_DATA_IDENTIFIER);
+        bytes.extend(SAVE_DATA_VERSION.to_be_bytes());
+        bytes.extend(body);
+        bytes.extend(crc32(body).to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_de_serialize_save_data_truncated() {
+        let save_data = SaveData {
+            stars: vec![Star::default()],
+            scene: Some(SceneData {
+                state: 10,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let mut w = Writer::new();
+        save_data.encode_body(&mut w);
+        let mut body = w.into_bytes();
+        body.pop();
+        let bytes = checksummed_save_bytes(&body);
+        let err = from_bytes::<SaveData>(&bytes)
+            .expect_err("Truncated SaveData should fail to deserialize!");
+        assert_eq!(err.field, "scene.value.music_on");
+        assert_eq!(err.offset, SAVE_DATA_IDENTIFIER.len() + 4 + body.len());
+
+        let err = from_bytes::<SaveData>(&bytes[0..4])
+            .expect_err("SaveData without identifier should fail to deserialize!");
+        assert_eq!(err.offset, 0);
+    }
+
+    #[test]
+    fn test_de_serialize_save_data_corrupt() {
+        let mut save_data = SaveData::default();
+        save_data.fishes.push(Fish::default());
+
+        let mut bytes = to_bytes(&save_data);
+        bytes[SAVE_DATA_IDENTIFIER.len() + 4 + 10] ^= 0xFF;
+        let err = from_bytes::<SaveData>(&bytes)
+            .expect_err("Corrupt SaveData should fail to deserialize!");
+        assert_eq!(err.field, "checksum");
+        assert!(matches!(err.kind, DecodeErrorKind::ChecksumMismatch { .. }));
+
+        let mut body = Vec::new();
+        body.extend((MAX_SAVED_ENTITIES as u32 + 1).to_be_bytes());
+        body.extend([0; 64]);
+        let err = from_bytes::<SaveData>(&checksummed_save_bytes(&body))
+            .expect_err("SaveData with too many planets should fail to deserialize!");
+        assert_eq!(err.field, "planets.len");
+
+        let mut body = Vec::new();
+        body.extend(0u32.to_be_bytes());
+        body.extend(0u32.to_be_bytes());
+        body.extend(1u32.to_be_bytes());
+        body.extend(to_bytes(&Fish {
+            swim_v: f32::NAN,
+            ..Default::default()
+        }));
+        body.extend(to_bytes(&Rectangle::default()));
+        body.extend(to_bytes(&RotatingParticleSystem::default()));
+        let err = from_bytes::<SaveData>(&checksummed_save_bytes(&body))
+            .expect_err("SaveData with NaN should fail to deserialize!");
+        assert_eq!(err.field, "fishes[0].swim_v");
+    }
 }
@@ -0,0 +1,570 @@
+use std::io::Result as IOResult;
+#[cfg(not(target_family = "wasm"))]
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::faux_quicksilver::Color;
+use crate::serialization::{Decode, DecodeError, DecodeErrorKind, Encode, Reader, Writer};
+
+pub const SAVE_SLOT_COUNT: usize = 3;
+pub const AUTOSAVE_SNAPSHOT_COUNT: usize = 3;
+#[cfg(not(target_family = "wasm"))]
+const SLOT_BACKUP_COUNT: usize = 3;
+
+#[cfg(not(target_family = "wasm"))]
+const SAVE_FILENAME: &str = "LudumDare45_OneAndAll_SaveFile.bin";
+
+const SLOT_IDENTIFIER: [u8; 8] = [0x53, 0x4C, 0x4F, 0x54, b'V', b'e', b'r', SLOT_VERSION];
+const SLOT_VERSION: u8 = 2;
+const SNAPSHOT_IDENTIFIER: [u8; 8] = [0x41, 0x55, 0x54, 0x4F, b'V', b'e', b'r', 1];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlotMetadata {
+    // Seconds since the unix epoch, 0 if unknown.
+    pub created: u64,
+    pub modified: u64,
+    // Counts up with every save and autosave, see `RecoveryScan`.
+    pub sequence: u64,
+    pub planet_count: u32,
+    pub star_count: u32,
+    pub fish_count: u32,
+    pub virtue_color: Color,
+    // Seconds.
+    pub play_time: f32,
+}
+
+impl SlotMetadata {
+    pub fn describe(&self) -> String {
+        format!(
+            "{} planets, {} stars, {} fish\nPlayed {}, saved {}",
+            self.planet_count,
+            self.star_count,
+            self.fish_count,
+            format_play_time(self.play_time),
+            if self.modified == 0 {
+                String::from("at an unknown time")
+            } else {
+                format_timestamp(self.modified)
+            }
+        )
+    }
+}
+
+impl Encode for SlotMetadata {
+    fn encode(&self, w: &mut Writer) {
+        w.write(&self.created);
+        w.write(&self.modified);
+        w.write(&self.sequence);
+        w.write(&self.planet_count);
+        w.write(&self.star_count);
+        w.write(&self.fish_count);
+        w.write(&self.virtue_color);
+        w.write(&self.play_time);
+    }
+}
+
+impl SlotMetadata {
+    fn decode_versioned(r: &mut Reader, version: u8) -> Result<Self, DecodeError> {
+        Ok(Self {
+            created: r.read("created")?,
+            modified: r.read("modified")?,
+            // Version 1 has no sequence, so any autosave is newer.
+            sequence: if version >= 2 { r.read("sequence")? } else { 0 },
+            planet_count: r.read("planet_count")?,
+            star_count: r.read("star_count")?,
+            fish_count: r.read("fish_count")?,
+            virtue_color: r.read("virtue_color")?,
+            play_time: r.read("play_time")?,
+        })
+    }
+}
+
+impl Decode for SlotMetadata {
+    const MIN_SIZE: usize = 3 * u64::MIN_SIZE + 3 * u32::MIN_SIZE + Color::MIN_SIZE + f32::MIN_SIZE;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Self::decode_versioned(r, SLOT_VERSION)
+    }
+}
+
+pub fn encode_slot(metadata: &SlotMetadata, save_bytes: &[u8]) -> Vec<u8> {
+    let mut w = Writer::new();
+    w.write_bytes(&SLOT_IDENTIFIER);
+    w.write(metadata);
+    w.write_bytes(save_bytes);
+    w.into_bytes()
+}
+
+/// Splits slot data into its metadata and the save data bytes. Saves written
+/// before slots existed have no metadata.
+pub fn decode_slot(data: &[u8]) -> Result<(Option<SlotMetadata>, &[u8]), DecodeError> {
+    let id_len = SLOT_IDENTIFIER.len();
+    let version = match data.get(..id_len) {
+        Some(id) if id[..id_len - 1] == SLOT_IDENTIFIER[..id_len - 1] => id[id_len - 1],
+        _ => return Ok((None, data)),
+    };
+    let mut r = Reader::new(data);
+    if !(1..=SLOT_VERSION).contains(&version) {
+        return Err(r.error(DecodeErrorKind::InvalidIdentifier));
+    }
+    r.read_bytes(id_len)?;
+    let metadata =
+        SlotMetadata::decode_versioned(&mut r, version).map_err(|e| e.in_field("metadata"))?;
+    Ok((Some(metadata), r.peek_remaining()))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub index: usize,
+    pub slot: usize,
+    pub metadata: SlotMetadata,
+    // Data in the format written to a slot.
+    pub slot_data: Vec<u8>,
+}
+
+pub fn encode_snapshot(slot: usize, metadata: &SlotMetadata, save_bytes: &[u8]) -> Vec<u8> {
+    let mut w = Writer::new();
+    w.write_bytes(&SNAPSHOT_IDENTIFIER);
+    w.write(&(slot as u32));
+    w.write_bytes(&encode_slot(metadata, save_bytes));
+    w.into_bytes()
+}
+
+pub fn decode_snapshot(index: usize, data: &[u8]) -> Result<Snapshot, DecodeError> {
+    let mut r = Reader::new(data);
+    r.expect_identifier(&SNAPSHOT_IDENTIFIER)?;
+    let slot_offset = r.get_offset();
+    let slot = r.read::<u32>("slot")? as usize;
+    if slot >= SAVE_SLOT_COUNT {
+        return Err(DecodeError::new(
+            slot_offset,
+            DecodeErrorKind::InvalidValue(format!("no slot {slot}")),
+        )
+        .in_field("slot"));
+    }
+    let slot_data = r.peek_remaining();
+    let metadata = match decode_slot(slot_data)? {
+        (Some(metadata), _) => metadata,
+        (None, _) => {
+            return Err(r
+                .error(DecodeErrorKind::InvalidIdentifier)
+                .in_field("metadata"))
+        }
+    };
+    Ok(Snapshot {
+        index,
+        slot,
+        metadata,
+        slot_data: slot_data.to_vec(),
+    })
+}
+
+/// Finds the newest autosave snapshot and checks whether its slot was saved
+/// since. Every save and autosave takes the next `sequence`, so unlike
+/// timestamps the order is exact however close together they were written.
+pub struct RecoveryScan {
+    snapshot_recvs: Vec<(usize, Receiver<Vec<u8>>)>,
+    slot_recvs: Vec<(usize, Receiver<Vec<u8>>)>,
+    newest: Option<Snapshot>,
+    slot_sequences: [u64; SAVE_SLOT_COUNT],
+    restore: Option<Snapshot>,
+    is_done: bool,
+}
+
+impl RecoveryScan {
+    pub fn new(
+        snapshot_recvs: Vec<(usize, Receiver<Vec<u8>>)>,
+        slot_recvs: Vec<(usize, Receiver<Vec<u8>>)>,
+    ) -> Self {
+        Self {
+            snapshot_recvs,
+            slot_recvs,
+            newest: None,
+            slot_sequences: [0; SAVE_SLOT_COUNT],
+            restore: None,
+            is_done: false,
+        }
+    }
+
+    pub fn start(storage: &Storage) -> Self {
+        Self::new(
+            (0..AUTOSAVE_SNAPSHOT_COUNT)
+                .filter_map(|index| read_snapshot(storage, index).ok().map(|rx| (index, rx)))
+                .collect(),
+            (0..SAVE_SLOT_COUNT)
+                .filter_map(|slot| read_slot(storage, slot).ok().map(|rx| (slot, rx)))
+                .collect(),
+        )
+    }
+
+    /// Returns true once the scan has finished. `slot_metadata` extracts the
+    /// metadata from a slot's data.
+    pub fn poll(&mut self, slot_metadata: impl Fn(&[u8]) -> Option<SlotMetadata>) -> bool {
+        if self.is_done {
+            return true;
+        }
+
+        let newest = &mut self.newest;
+        self.snapshot_recvs
+            .retain(|(index, rx)| match rx.try_recv() {
+                Ok(data) => {
+                    if let Ok(snapshot) = decode_snapshot(*index, &data) {
+                        if newest
+                            .as_ref()
+                            .is_none_or(|n| snapshot.metadata.sequence > n.metadata.sequence)
+                        {
+                            *newest = Some(snapshot);
+                        }
+                    }
+                    false
+                }
+                Err(TryRecvError::Empty) => true,
+                Err(TryRecvError::Disconnected) => false,
+            });
+        let slot_sequences = &mut self.slot_sequences;
+        self.slot_recvs.retain(|(slot, rx)| match rx.try_recv() {
+            Ok(data) => {
+                slot_sequences[*slot] = slot_metadata(&data).map(|m| m.sequence).unwrap_or(0);
+                false
+            }
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => false,
+        });
+        if !self.snapshot_recvs.is_empty() || !self.slot_recvs.is_empty() {
+            return false;
+        }
+
+        if let Some(newest) = &self.newest {
+            if newest.metadata.sequence > self.slot_sequences[newest.slot] {
+                self.restore = Some(newest.clone());
+            }
+        }
+        self.is_done = true;
+        true
+    }
+
+    /// The snapshot index to write next, overwriting the oldest.
+    pub fn next_index(&self) -> usize {
+        self.newest
+            .as_ref()
+            .map(|n| (n.index + 1) % AUTOSAVE_SNAPSHOT_COUNT)
+            .unwrap_or(0)
+    }
+
+    /// The highest sequence of any save or snapshot found.
+    pub fn last_sequence(&self) -> u64 {
+        self.slot_sequences
+            .iter()
+            .copied()
+            .chain(self.newest.as_ref().map(|n| n.metadata.sequence))
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn take_restore(&mut self) -> Option<Snapshot> {
+        self.restore.take()
+    }
+}
+
+pub fn now_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Formats seconds since the unix epoch as "YYYY-MM-DD HH:MM" in UTC.
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let secs_of_day = timestamp % 86400;
+
+    // Civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60
+    )
+}
+
+pub fn format_play_time(seconds: f32) -> String {
+    let total = seconds.max(0.0) as u64;
+    format!(
+        "{}:{:02}:{:02}",
+        total / 3600,
+        total % 3600 / 60,
+        total % 60
+    )
+}
+
+/// Where saves and settings are kept, a data directory on native and
+/// IndexedDB on wasm.
+#[derive(Clone, Debug)]
+pub struct Storage {
+    #[cfg(not(target_family = "wasm"))]
+    dir: PathBuf,
+}
+
+impl Storage {
+    /// `dir` is used instead of the platform data directory on native.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn open(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir: crate::data_dir::open(dir),
+        }
+    }
+
+    #[cfg(target_family = "wasm")]
+    pub fn open(_dir: Option<PathBuf>) -> Self {
+        Self {}
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    pub fn path(&self, filename: &str) -> PathBuf {
+        self.dir.join(filename)
+    }
+
+    /// Saves used to be written to the current directory, so they are moved
+    /// into the data directory.
+    #[cfg(not(target_family = "wasm"))]
+    pub fn migrate_saves(&self) {
+        let filenames: Vec<String> = (0..SAVE_SLOT_COUNT)
+            .map(slot_filename)
+            .chain((0..AUTOSAVE_SNAPSHOT_COUNT).map(snapshot_filename))
+            .collect();
+        if let Err(e) = crate::data_dir::migrate(Path::new("."), &self.dir, &filenames) {
+            eprintln!(
+                "WARNING: Failed to migrate saves to {}: {e}",
+                self.dir.display()
+            );
+        }
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn slot_filename(slot: usize) -> String {
+    // The first slot keeps the name used before slots existed.
+    if slot == 0 {
+        String::from(SAVE_FILENAME)
+    } else {
+        format!("LudumDare45_OneAndAll_SaveFile_{}.bin", slot + 1)
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn snapshot_filename(index: usize) -> String {
+    format!("LudumDare45_OneAndAll_Autosave_{}.bin", index + 1)
+}
+
+#[cfg(target_family = "wasm")]
+fn snapshot_key(index: usize) -> String {
+    format!("autosave_{}", index + 1)
+}
+
+#[cfg(target_family = "wasm")]
+fn slot_key(slot: usize) -> String {
+    // The first slot keeps the key used before slots existed.
+    if slot == 0 {
+        String::from("savedata")
+    } else {
+        format!("savedata_{}", slot + 1)
+    }
+}
+
+/// Keeps the slot's previous saves as backups next to it.
+#[cfg(not(target_family = "wasm"))]
+pub fn write_slot(storage: &Storage, slot: usize, data: &[u8]) -> IOResult<()> {
+    let path = storage.path(&slot_filename(slot));
+    crate::data_dir::rotate_backups(&path, SLOT_BACKUP_COUNT)?;
+    crate::data_dir::write_atomic(&path, data)
+}
+
+// IndexedDB replaces the value in a single transaction, so an interrupted save
+// leaves the previous one intact.
+#[cfg(target_family = "wasm")]
+pub fn write_slot(_storage: &Storage, slot: usize, data: &[u8]) -> IOResult<()> {
+    crate::wasm_helpers::save_data(&slot_key(slot), data)
+}
+
+/// Reads a slot's data. An empty Vec is received if the slot is empty or
+/// could not be loaded.
+#[cfg(not(target_family = "wasm"))]
+pub fn read_slot(storage: &Storage, slot: usize) -> IOResult<Receiver<Vec<u8>>> {
+    crate::data_dir::read_file(&storage.path(&slot_filename(slot)))
+}
+
+#[cfg(target_family = "wasm")]
+pub fn read_slot(_storage: &Storage, slot: usize) -> IOResult<Receiver<Vec<u8>>> {
+    crate::wasm_helpers::load_data(&slot_key(slot))
+}
+
+/// Writes a snapshot without blocking, the result is received once done.
+#[cfg(not(target_family = "wasm"))]
+pub fn write_snapshot(
+    storage: &Storage,
+    index: usize,
+    data: Vec<u8>,
+) -> Receiver<Result<(), String>> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let path = storage.path(&snapshot_filename(index));
+    std::thread::spawn(move || {
+        tx.send(crate::data_dir::write_atomic(&path, &data).map_err(|e| e.to_string()))
+            .ok();
+    });
+    rx
+}
+
+#[cfg(target_family = "wasm")]
+pub fn write_snapshot(
+    _storage: &Storage,
+    index: usize,
+    data: Vec<u8>,
+) -> Receiver<Result<(), String>> {
+    // IndexedDB stores asynchronously already.
+    let (tx, rx) = std::sync::mpsc::channel();
+    tx.send(crate::wasm_helpers::save_data(&snapshot_key(index), &data).map_err(|e| e.to_string()))
+        .ok();
+    rx
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub fn read_snapshot(storage: &Storage, index: usize) -> IOResult<Receiver<Vec<u8>>> {
+    crate::data_dir::read_file(&storage.path(&snapshot_filename(index)))
+}
+
+#[cfg(target_family = "wasm")]
+pub fn read_snapshot(_storage: &Storage, index: usize) -> IOResult<Receiver<Vec<u8>>> {
+    crate::wasm_helpers::load_data(&snapshot_key(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(modified: u64, sequence: u64) -> SlotMetadata {
+        SlotMetadata {
+            created: 1,
+            modified,
+            sequence,
+            planet_count: 3,
+            star_count: 4,
+            fish_count: 5,
+            virtue_color: Color::from_rgba(6, 7, 8, 9),
+            play_time: 10.0,
+        }
+    }
+
+    #[test]
+    fn test_encode_decode_slot() {
+        let metadata = metadata(2, 7);
+        let bytes = encode_slot(&metadata, &[11, 12, 13]);
+        let (des_metadata, save_bytes) =
+            decode_slot(&bytes).expect("Should be able to decode slot!");
+        assert_eq!(des_metadata, Some(metadata.clone()));
+        assert_eq!(save_bytes, [11, 12, 13]);
+
+        let (des_metadata, save_bytes) =
+            decode_slot(&[1, 2, 3]).expect("Should be able to decode legacy slot!");
+        assert_eq!(des_metadata, None);
+        assert_eq!(save_bytes, [1, 2, 3]);
+
+        // version 1 has no sequence
+        let mut w = Writer::new();
+        w.write_bytes(&SLOT_IDENTIFIER[..SLOT_IDENTIFIER.len() - 1]);
+        w.write(&1u8);
+        w.write(&metadata.created);
+        w.write(&metadata.modified);
+        w.write(&metadata.planet_count);
+        w.write(&metadata.star_count);
+        w.write(&metadata.fish_count);
+        w.write(&metadata.virtue_color);
+        w.write(&metadata.play_time);
+        w.write_bytes(&[11]);
+        let bytes = w.into_bytes();
+        let (des_metadata, save_bytes) =
+            decode_slot(&bytes).expect("Should be able to decode version 1 slot!");
+        assert_eq!(
+            des_metadata,
+            Some(SlotMetadata {
+                sequence: 0,
+                ..metadata
+            })
+        );
+        assert_eq!(save_bytes, [11]);
+    }
+
+    fn recvs(data: Vec<Vec<u8>>) -> Vec<(usize, Receiver<Vec<u8>>)> {
+        data.into_iter()
+            .enumerate()
+            .map(|(index, data)| {
+                let (tx, rx) = std::sync::mpsc::channel();
+                tx.send(data).unwrap();
+                (index, rx)
+            })
+            .collect()
+    }
+
+    fn slot_metadata(data: &[u8]) -> Option<SlotMetadata> {
+        decode_slot(data).ok()?.0
+    }
+
+    #[test]
+    fn test_recovery_scan() {
+        // saved in the same second, only the sequence tells them apart
+        let snapshots = vec![
+            encode_snapshot(1, &metadata(20, 4), &[1, 2, 3]),
+            encode_snapshot(2, &metadata(20, 6), &[1, 2, 3]),
+            vec![1, 2, 3],
+        ];
+        let slots = vec![
+            Vec::new(),
+            encode_slot(&metadata(20, 7), &[]),
+            encode_slot(&metadata(20, 5), &[]),
+        ];
+        let mut scan = RecoveryScan::new(recvs(snapshots), recvs(slots));
+        assert!(scan.poll(slot_metadata));
+        assert_eq!(scan.next_index(), 2);
+        assert_eq!(scan.last_sequence(), 7);
+        let snapshot = scan.take_restore().expect("Snapshot should be newer");
+        assert_eq!(snapshot.slot, 2);
+        assert_eq!(snapshot.metadata.sequence, 6);
+        let (_, save_bytes) = decode_slot(&snapshot.slot_data).unwrap();
+        assert_eq!(save_bytes, [1, 2, 3]);
+
+        // the slot was saved manually after the autosave
+        let snapshots = vec![encode_snapshot(0, &metadata(20, 8), &[])];
+        let slots = vec![encode_slot(&metadata(20, 9), &[])];
+        let mut scan = RecoveryScan::new(recvs(snapshots), recvs(slots));
+        assert!(scan.poll(slot_metadata));
+        assert_eq!(scan.next_index(), 1);
+        assert_eq!(scan.last_sequence(), 9);
+        assert!(scan.take_restore().is_none());
+
+        // still waiting for a slot
+        let (_tx, rx) = std::sync::mpsc::channel();
+        let mut scan = RecoveryScan::new(Vec::new(), vec![(0, rx)]);
+        assert!(!scan.poll(slot_metadata));
+    }
+
+    #[test]
+    fn test_format_times() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1_700_000_000), "2023-11-14 22:13");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00");
+        assert_eq!(format_play_time(3725.5), "1:02:05");
+    }
+}
@@ -0,0 +1,575 @@
+use std::fmt;
+
+// Byte width of collection lengths unless a Reader/Writer is told otherwise.
+pub const DEFAULT_LEN_SIZE: usize = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeErrorKind {
+    UnexpectedEnd { needed: usize, remaining: usize },
+    InvalidIdentifier,
+    InvalidValue(String),
+    TooManyElements { count: usize, max: usize },
+    ChecksumMismatch { expected: u32, actual: u32 },
+    TrailingBytes(usize),
+}
+
+impl fmt::Display for DecodeErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeErrorKind::UnexpectedEnd { needed, remaining } => write!(
+                f,
+                "unexpected end of data (needed {needed} bytes, {remaining} remaining)"
+            ),
+            DecodeErrorKind::InvalidIdentifier => write!(f, "invalid identifier"),
+            DecodeErrorKind::InvalidValue(s) => write!(f, "invalid value ({s})"),
+            DecodeErrorKind::TooManyElements { count, max } => {
+                write!(f, "too many elements ({count}, at most {max} allowed)")
+            }
+            DecodeErrorKind::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum mismatch (expected {expected:08x}, got {actual:08x})"
+            ),
+            DecodeErrorKind::TrailingBytes(count) => write!(f, "{count} unexpected trailing bytes"),
+        }
+    }
+}
+
+/// Error returned when decoding fails.
+///
+/// `field` is the path to the value that failed (e.g.
+/// `planets[2].moons[0].offset`) and `offset` is the byte offset at which
+/// reading that value started.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    pub field: String,
+    pub offset: usize,
+    pub kind: DecodeErrorKind,
+}
+
+impl DecodeError {
+    pub fn new(offset: usize, kind: DecodeErrorKind) -> Self {
+        Self {
+            field: String::new(),
+            offset,
+            kind,
+        }
+    }
+
+    pub fn in_field(mut self, name: &str) -> Self {
+        if self.field.is_empty() {
+            self.field = name.to_owned();
+        } else if self.field.starts_with('[') {
+            self.field = format!("{name}{}", self.field);
+        } else {
+            self.field = format!("{name}.{}", self.field);
+        }
+        self
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.field.is_empty() {
+            write!(
+                f,
+                "Failed to decode at byte offset {}: {}",
+                self.offset, self.kind
+            )
+        } else {
+            write!(
+                f,
+                "Failed to decode \"{}\" at byte offset {}: {}",
+                self.field, self.offset, self.kind
+            )
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+pub struct Writer {
+    bytes: Vec<u8>,
+    len_size: usize,
+}
+
+impl Default for Writer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Writer {
+    pub fn new() -> Self {
+        Self::with_len_size(DEFAULT_LEN_SIZE)
+    }
+
+    pub fn with_len_size(len_size: usize) -> Self {
+        Self {
+            bytes: Vec::new(),
+            len_size,
+        }
+    }
+
+    pub fn write<T: Encode + ?Sized>(&mut self, value: &T) {
+        value.encode(self);
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn write_len(&mut self, len: usize) {
+        if self.len_size == 8 {
+            self.write_bytes(&(len as u64).to_be_bytes());
+        } else {
+            let len = u32::try_from(len).expect("Lengths must fit in 4 bytes");
+            self.write_bytes(&len.to_be_bytes());
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[derive(Clone)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+    len_size: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self::with_len_size(data, DEFAULT_LEN_SIZE)
+    }
+
+    pub fn with_len_size(data: &'a [u8], len_size: usize) -> Self {
+        Self {
+            data,
+            offset: 0,
+            len_size,
+        }
+    }
+
+    /// Returns a reader over the same data positioned at `offset`.
+    pub fn fork_at(&self, offset: usize, len_size: usize) -> Self {
+        Self {
+            data: self.data,
+            offset,
+            len_size,
+        }
+    }
+
+    pub fn get_offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.offset
+    }
+
+    pub fn error(&self, kind: DecodeErrorKind) -> DecodeError {
+        DecodeError::new(self.offset, kind)
+    }
+
+    pub fn read<T: Decode>(&mut self, field: &str) -> Result<T, DecodeError> {
+        T::decode(self).map_err(|e| e.in_field(field))
+    }
+
+    /// Returns a reader that stops `len` bytes past the current offset.
+    pub fn limit(&self, len: usize) -> Self {
+        Self {
+            data: &self.data[..(self.offset + len).min(self.data.len())],
+            offset: self.offset,
+            len_size: self.len_size,
+        }
+    }
+
+    pub fn peek_remaining(&self) -> &'a [u8] {
+        &self.data[self.offset..]
+    }
+
+    pub fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], DecodeError> {
+        if self.remaining() < count {
+            return Err(self.error(DecodeErrorKind::UnexpectedEnd {
+                needed: count,
+                remaining: self.remaining(),
+            }));
+        }
+        let bytes = &self.data[self.offset..(self.offset + count)];
+        self.offset += count;
+        Ok(bytes)
+    }
+
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    pub fn read_len(&mut self) -> Result<usize, DecodeError> {
+        let offset = self.offset;
+        if self.len_size == 8 {
+            usize::try_from(u64::from_be_bytes(self.read_array()?)).map_err(|_| {
+                DecodeError::new(
+                    offset,
+                    DecodeErrorKind::InvalidValue(String::from("length too large")),
+                )
+            })
+        } else {
+            Ok(u32::from_be_bytes(self.read_array()?) as usize)
+        }
+    }
+
+    /// Reads a `Vec<T>`, failing if its length prefix exceeds `max_len`.
+    pub fn read_vec<T: Decode>(
+        &mut self,
+        field: &str,
+        max_len: usize,
+    ) -> Result<Vec<T>, DecodeError> {
+        decode_vec(self, max_len).map_err(|e| e.in_field(field))
+    }
+
+    pub fn expect_identifier(&mut self, identifier: &[u8]) -> Result<(), DecodeError> {
+        let offset = self.offset;
+        if self.read_bytes(identifier.len())? != identifier {
+            return Err(DecodeError::new(offset, DecodeErrorKind::InvalidIdentifier));
+        }
+        Ok(())
+    }
+
+    pub fn expect_end(&self) -> Result<(), DecodeError> {
+        if self.remaining() != 0 {
+            return Err(self.error(DecodeErrorKind::TrailingBytes(self.remaining())));
+        }
+        Ok(())
+    }
+}
+
+pub trait Encode {
+    fn encode(&self, w: &mut Writer);
+}
+
+pub trait Decode: Sized {
+    /// Lower bound on the encoded size, used to reject length prefixes that
+    /// could not possibly fit in the remaining data.
+    const MIN_SIZE: usize;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError>;
+}
+
+pub fn to_bytes<T: Encode + ?Sized>(value: &T) -> Vec<u8> {
+    let mut w = Writer::new();
+    w.write(value);
+    w.into_bytes()
+}
+
+/// Decodes a `T` from the start of `data`, returning it with the number of
+/// bytes read.
+pub fn from_bytes<T: Decode>(data: &[u8]) -> Result<(T, usize), DecodeError> {
+    let mut r = Reader::new(data);
+    let value = T::decode(&mut r)?;
+    Ok((value, r.get_offset()))
+}
+
+/// CRC-32 (IEEE 802.3), as used by zlib and PNG.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+impl Encode for u8 {
+    fn encode(&self, w: &mut Writer) {
+        w.write_bytes(&[*self]);
+    }
+}
+
+impl Decode for u8 {
+    const MIN_SIZE: usize = 1;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(r.read_bytes(1)?[0])
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, w: &mut Writer) {
+        w.write_bytes(&[if *self { 1 } else { 0 }]);
+    }
+}
+
+impl Decode for bool {
+    const MIN_SIZE: usize = 1;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        Ok(r.read_bytes(1)?[0] != 0)
+    }
+}
+
+macro_rules! impl_be_bytes {
+    ($($t:ty),*) => {
+        $(
+            impl Encode for $t {
+                fn encode(&self, w: &mut Writer) {
+                    w.write_bytes(&self.to_be_bytes());
+                }
+            }
+
+            impl Decode for $t {
+                const MIN_SIZE: usize = std::mem::size_of::<$t>();
+
+                fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+                    Ok(<$t>::from_be_bytes(r.read_array()?))
+                }
+            }
+        )*
+    };
+}
+
+impl_be_bytes!(u16, u32, u64, i32, i64);
+
+macro_rules! impl_be_bytes_finite {
+    ($($t:ty),*) => {
+        $(
+            impl Encode for $t {
+                fn encode(&self, w: &mut Writer) {
+                    w.write_bytes(&self.to_be_bytes());
+                }
+            }
+
+            impl Decode for $t {
+                const MIN_SIZE: usize = std::mem::size_of::<$t>();
+
+                fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+                    let offset = r.get_offset();
+                    let value = <$t>::from_be_bytes(r.read_array()?);
+                    if !value.is_finite() {
+                        return Err(DecodeError::new(
+                            offset,
+                            DecodeErrorKind::InvalidValue(format!("{value} is not finite")),
+                        ));
+                    }
+                    Ok(value)
+                }
+            }
+        )*
+    };
+}
+
+impl_be_bytes_finite!(f32, f64);
+
+impl<T: Encode> Encode for [T] {
+    fn encode(&self, w: &mut Writer) {
+        w.write_len(self.len());
+        for item in self {
+            item.encode(w);
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, w: &mut Writer) {
+        self.as_slice().encode(w);
+    }
+}
+
+fn decode_vec<T: Decode>(r: &mut Reader, max_len: usize) -> Result<Vec<T>, DecodeError> {
+    let offset = r.get_offset();
+    let len = r.read_len().map_err(|e| e.in_field("len"))?;
+    if len > max_len {
+        return Err(DecodeError::new(
+            offset,
+            DecodeErrorKind::TooManyElements {
+                count: len,
+                max: max_len,
+            },
+        )
+        .in_field("len"));
+    }
+    let needed = len.saturating_mul(T::MIN_SIZE);
+    if needed > r.remaining() {
+        return Err(DecodeError::new(
+            offset,
+            DecodeErrorKind::UnexpectedEnd {
+                needed,
+                remaining: r.remaining(),
+            },
+        )
+        .in_field("len"));
+    }
+
+    let mut v = Vec::with_capacity(len);
+    for i in 0..len {
+        v.push(T::decode(r).map_err(|e| e.in_field(&format!("[{i}]")))?);
+    }
+    Ok(v)
+}
+
+impl<T: Decode> Decode for Vec<T> {
+    const MIN_SIZE: usize = DEFAULT_LEN_SIZE;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        decode_vec(r, usize::MAX)
+    }
+}
+
+impl Encode for String {
+    fn encode(&self, w: &mut Writer) {
+        self.as_bytes().encode(w);
+    }
+}
+
+impl Decode for String {
+    const MIN_SIZE: usize = DEFAULT_LEN_SIZE;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        let offset = r.get_offset();
+        let bytes: Vec<u8> = Vec::decode(r)?;
+        String::from_utf8(bytes).map_err(|_| {
+            DecodeError::new(
+                offset,
+                DecodeErrorKind::InvalidValue(String::from("not valid UTF-8")),
+            )
+        })
+    }
+}
+
+impl<T: Encode> Encode for Option<T> {
+    fn encode(&self, w: &mut Writer) {
+        match self {
+            Some(value) => {
+                w.write(&true);
+                w.write(value);
+            }
+            None => w.write(&false),
+        }
+    }
+}
+
+impl<T: Decode> Decode for Option<T> {
+    const MIN_SIZE: usize = 1;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        if r.read::<bool>("is_some")? {
+            Ok(Some(r.read("value")?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_de_serialize_primitives() {
+        let mut w = Writer::new();
+        w.write(&7u8);
+        w.write(&true);
+        w.write(&0x1234_5678u32);
+        w.write(&-1.5f32);
+        w.write(&vec![1u32, 2, 3]);
+        w.write(&String::from("one and all"));
+        w.write(&Some(9u64));
+        let bytes = w.into_bytes();
+
+        let mut r = Reader::new(&bytes);
+        assert_eq!(r.read::<u8>("a"), Ok(7));
+        assert_eq!(r.read::<bool>("b"), Ok(true));
+        assert_eq!(r.read::<u32>("c"), Ok(0x1234_5678));
+        assert_eq!(r.read::<f32>("d"), Ok(-1.5));
+        assert_eq!(r.read::<Vec<u32>>("e"), Ok(vec![1, 2, 3]));
+        assert_eq!(r.read::<String>("f"), Ok(String::from("one and all")));
+        assert_eq!(r.read::<Option<u64>>("g"), Ok(Some(9)));
+        assert_eq!(r.expect_end(), Ok(()));
+    }
+
+    #[test]
+    fn test_len_size() {
+        let mut w = Writer::with_len_size(8);
+        w.write(&vec![5u8, 6]);
+        let bytes = w.into_bytes();
+        assert_eq!(bytes, [0, 0, 0, 0, 0, 0, 0, 2, 5, 6]);
+
+        assert_eq!(
+            Reader::with_len_size(&bytes, 8).read::<Vec<u8>>("v"),
+            Ok(vec![5, 6])
+        );
+        let mut r = Reader::new(&bytes);
+        assert_eq!(r.read::<Vec<u8>>("v"), Ok(vec![]));
+        assert!(r.expect_end().is_err());
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    #[should_panic(expected = "Lengths must fit in 4 bytes")]
+    fn test_len_too_long() {
+        Writer::new().write_len(u32::MAX as usize + 1);
+    }
+
+    #[test]
+    fn test_decode_error_field_and_offset() {
+        let bytes = [0, 0, 0, 1, 0, 0, 0, 9];
+        let err = from_bytes::<Vec<Vec<f32>>>(&bytes).unwrap_err();
+        assert_eq!(err.field, "[0].len");
+        assert_eq!(err.offset, 4);
+
+        let bytes = to_bytes(&vec![1.0f32, f32::NAN]);
+        let mut r = Reader::new(&bytes);
+        let err = r.read::<Vec<f32>>("values").unwrap_err();
+        assert_eq!(err.field, "values[1]");
+        assert_eq!(err.offset, 8);
+        assert_eq!(
+            err.to_string(),
+            "Failed to decode \"values[1]\" at byte offset 8: invalid value (NaN is not finite)"
+        );
+    }
+
+    #[test]
+    fn test_hostile_lengths() {
+        let mut bytes = to_bytes(&u32::MAX);
+        bytes.extend_from_slice(&[0; 16]);
+
+        let err = from_bytes::<Vec<u32>>(&bytes).unwrap_err();
+        assert_eq!(err.field, "len");
+        assert_eq!(
+            err.kind,
+            DecodeErrorKind::UnexpectedEnd {
+                needed: (u32::MAX as usize).saturating_mul(4),
+                remaining: 16
+            }
+        );
+
+        let bytes = to_bytes(&vec![1u8, 2, 3]);
+        let err = Reader::new(&bytes).read_vec::<u8>("v", 2).unwrap_err();
+        assert_eq!(err.field, "v.len");
+        assert_eq!(
+            err.kind,
+            DecodeErrorKind::TooManyElements { count: 3, max: 2 }
+        );
+        assert_eq!(
+            Reader::new(&bytes).read_vec::<u8>("v", 3),
+            Ok(vec![1, 2, 3])
+        );
+
+        assert!(from_bytes::<f64>(&to_bytes(&f64::INFINITY)).is_err());
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
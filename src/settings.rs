@@ -0,0 +1,300 @@
+use std::io::Result as IOResult;
+use std::sync::mpsc::Receiver;
+
+use crate::agnostic_interface::ScaleMode;
+use crate::input::InputMap;
+use crate::save_slots::Storage;
+use crate::serialization::{
+    to_bytes, Decode, DecodeError, DecodeErrorKind, Encode, Reader, Writer,
+};
+
+#[cfg(not(target_family = "wasm"))]
+const SETTINGS_FILENAME: &str = "LudumDare45_OneAndAll_Settings.bin";
+#[cfg(target_family = "wasm")]
+const SETTINGS_KEY: &str = "settings";
+
+const SETTINGS_IDENTIFIER: [u8; 8] = [0x53, 0x45, 0x54, 0x54, b'V', b'e', b'r', 1];
+const SETTINGS_VERSION: u32 = 4;
+
+const VOLUME_STEPS: [f32; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+const TEXT_RATES: [(f32, &str); 4] = [
+    (0.15, "Slow"),
+    (0.1, "Normal"),
+    (0.05, "Fast"),
+    (0.02, "Very fast"),
+];
+const DOUBLE_CLICK_TIMES: [f32; 4] = [0.25, 0.35, 0.5, 0.7];
+
+// Items of the settings menu, in order.
+pub const SETTINGS_ITEM_COUNT: usize = 8;
+pub const FULLSCREEN_ITEM: usize = 5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    // Seconds per character of appearing text.
+    pub text_rate: f32,
+    pub double_click_time: f32,
+    pub fullscreen: bool,
+    pub reduced_particles: bool,
+    pub bindings: InputMap,
+    pub scale_mode: ScaleMode,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+            text_rate: 0.1,
+            double_click_time: 0.35,
+            fullscreen: false,
+            reduced_particles: false,
+            bindings: InputMap::default(),
+            scale_mode: ScaleMode::Letterbox,
+        }
+    }
+}
+
+fn next_step(steps: &[f32], current: f32) -> f32 {
+    steps
+        .iter()
+        .copied()
+        .find(|step| *step > current + 0.001)
+        .unwrap_or(steps[0])
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "On"
+    } else {
+        "Off"
+    }
+}
+
+impl Settings {
+    pub fn item_text(&self, idx: usize) -> String {
+        match idx {
+            0 => format!("Master volume: {:.0}%", self.master_volume * 100.0),
+            1 => format!("Music volume: {:.0}%", self.music_volume * 100.0),
+            2 => format!("Sound effects volume: {:.0}%", self.sfx_volume * 100.0),
+            3 => format!(
+                "Text speed: {}",
+                TEXT_RATES
+                    .iter()
+                    .find(|(rate, _)| (rate - self.text_rate).abs() < 0.001)
+                    .map(|(_, name)| *name)
+                    .unwrap_or("Custom")
+            ),
+            4 => format!("Double-click time: {:.2}s", self.double_click_time),
+            FULLSCREEN_ITEM => format!("Fullscreen: {}", on_off(self.fullscreen)),
+            6 => format!("Reduced particles: {}", on_off(self.reduced_particles)),
+            7 => format!(
+                "Scaling: {}",
+                match self.scale_mode {
+                    ScaleMode::Letterbox => "Letterbox",
+                    ScaleMode::Expand => "Fill window",
+                }
+            ),
+            _ => String::new(),
+        }
+    }
+
+    /// Changes the setting of a menu item to its next value.
+    pub fn cycle(&mut self, idx: usize) {
+        match idx {
+            0 => self.master_volume = next_step(&VOLUME_STEPS, self.master_volume),
+            1 => self.music_volume = next_step(&VOLUME_STEPS, self.music_volume),
+            2 => self.sfx_volume = next_step(&VOLUME_STEPS, self.sfx_volume),
+            3 => {
+                let idx = TEXT_RATES
+                    .iter()
+                    .position(|(rate, _)| *rate < self.text_rate - 0.001)
+                    .unwrap_or(0);
+                self.text_rate = TEXT_RATES[idx].0;
+            }
+            4 => self.double_click_time = next_step(&DOUBLE_CLICK_TIMES, self.double_click_time),
+            FULLSCREEN_ITEM => self.fullscreen = !self.fullscreen,
+            6 => self.reduced_particles = !self.reduced_particles,
+            7 => {
+                self.scale_mode = match self.scale_mode {
+                    ScaleMode::Letterbox => ScaleMode::Expand,
+                    ScaleMode::Expand => ScaleMode::Letterbox,
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+impl Encode for Settings {
+    fn encode(&self, w: &mut Writer) {
+        w.write_bytes(&SETTINGS_IDENTIFIER);
+        w.write(&SETTINGS_VERSION);
+        w.write(&self.master_volume);
+        w.write(&self.music_volume);
+        w.write(&self.sfx_volume);
+        w.write(&self.text_rate);
+        w.write(&self.double_click_time);
+        w.write(&self.fullscreen);
+        w.write(&self.reduced_particles);
+        w.write(&self.bindings);
+        w.write(&match self.scale_mode {
+            ScaleMode::Letterbox => 0u8,
+            ScaleMode::Expand => 1u8,
+        });
+    }
+}
+
+fn read_in_range(
+    r: &mut Reader,
+    field: &str,
+    range: std::ops::RangeInclusive<f32>,
+) -> Result<f32, DecodeError> {
+    let offset = r.get_offset();
+    let value: f32 = r.read(field)?;
+    if !range.contains(&value) {
+        return Err(DecodeError::new(
+            offset,
+            DecodeErrorKind::InvalidValue(format!("{value} is not in {range:?}")),
+        )
+        .in_field(field));
+    }
+    Ok(value)
+}
+
+impl Decode for Settings {
+    const MIN_SIZE: usize = SETTINGS_IDENTIFIER.len() + u32::MIN_SIZE;
+
+    fn decode(r: &mut Reader) -> Result<Self, DecodeError> {
+        r.expect_identifier(&SETTINGS_IDENTIFIER)?;
+        let version_offset = r.get_offset();
+        let version: u32 = r.read("version")?;
+        if version == 0 || version > SETTINGS_VERSION {
+            return Err(DecodeError::new(
+                version_offset,
+                DecodeErrorKind::InvalidValue(format!("unknown version {version}")),
+            )
+            .in_field("version"));
+        }
+
+        Ok(Self {
+            master_volume: read_in_range(r, "master_volume", 0.0..=1.0)?,
+            music_volume: read_in_range(r, "music_volume", 0.0..=1.0)?,
+            sfx_volume: read_in_range(r, "sfx_volume", 0.0..=1.0)?,
+            text_rate: read_in_range(r, "text_rate", 0.0..=1.0)?,
+            double_click_time: read_in_range(r, "double_click_time", 0.05..=2.0)?,
+            fullscreen: r.read("fullscreen")?,
+            reduced_particles: r.read("reduced_particles")?,
+            // Version 1 had no rebindable controls, version 2 no gamepads.
+            bindings: if version >= 2 {
+                let mut bindings: InputMap = r.read("bindings")?;
+                if version == 2 {
+                    bindings.add_default_gamepad_bindings();
+                }
+                bindings
+            } else {
+                InputMap::default()
+            },
+            scale_mode: if version >= 4 {
+                let offset = r.get_offset();
+                match r.read::<u8>("scale_mode")? {
+                    0 => ScaleMode::Letterbox,
+                    1 => ScaleMode::Expand,
+                    mode => {
+                        return Err(DecodeError::new(
+                            offset,
+                            DecodeErrorKind::InvalidValue(format!("{mode} is not a scale mode")),
+                        )
+                        .in_field("scale_mode"))
+                    }
+                }
+            } else {
+                ScaleMode::Letterbox
+            },
+        })
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub fn write_settings(storage: &Storage, settings: &Settings) -> IOResult<()> {
+    crate::data_dir::write_atomic(&storage.path(SETTINGS_FILENAME), &to_bytes(settings))
+}
+
+#[cfg(target_family = "wasm")]
+pub fn write_settings(_storage: &Storage, settings: &Settings) -> IOResult<()> {
+    crate::wasm_helpers::save_data(SETTINGS_KEY, &to_bytes(settings))
+}
+
+/// Receives an empty Vec if no settings were saved yet.
+#[cfg(not(target_family = "wasm"))]
+pub fn read_settings(storage: &Storage) -> IOResult<Receiver<Vec<u8>>> {
+    crate::data_dir::read_file(&storage.path(SETTINGS_FILENAME))
+}
+
+#[cfg(target_family = "wasm")]
+pub fn read_settings(_storage: &Storage) -> IOResult<Receiver<Vec<u8>>> {
+    crate::wasm_helpers::load_data(SETTINGS_KEY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialization::from_bytes;
+
+    #[test]
+    fn test_de_serialize_settings() {
+        let settings = Settings {
+            master_volume: 0.5,
+            music_volume: 0.25,
+            sfx_volume: 0.0,
+            text_rate: 0.05,
+            double_click_time: 0.5,
+            fullscreen: true,
+            reduced_particles: true,
+            bindings: InputMap::default(),
+            scale_mode: ScaleMode::Expand,
+        };
+        let bytes = to_bytes(&settings);
+        let (des_settings, size) =
+            from_bytes::<Settings>(&bytes).expect("Should be able to deserialize Settings!");
+        assert_eq!(settings, des_settings);
+        assert_eq!(bytes.len(), size);
+
+        let mut bytes = to_bytes(&Settings {
+            master_volume: 2.0,
+            ..Default::default()
+        });
+        let err = from_bytes::<Settings>(&bytes).expect_err("Volume above 1 should be rejected");
+        assert_eq!(err.field, "master_volume");
+        bytes[8..12].copy_from_slice(&5u32.to_be_bytes());
+        let err = from_bytes::<Settings>(&bytes).expect_err("Unknown version should be rejected");
+        assert_eq!(err.field, "version");
+    }
+
+    #[test]
+    fn test_cycle_settings() {
+        let mut settings = Settings::default();
+        settings.cycle(0);
+        assert_eq!(settings.master_volume, 0.0);
+        settings.cycle(0);
+        assert_eq!(settings.item_text(0), "Master volume: 25%");
+
+        assert_eq!(settings.item_text(3), "Text speed: Normal");
+        settings.cycle(3);
+        settings.cycle(3);
+        assert_eq!(settings.item_text(3), "Text speed: Very fast");
+        settings.cycle(3);
+        assert_eq!(settings.item_text(3), "Text speed: Slow");
+
+        settings.cycle(4);
+        assert_eq!(settings.double_click_time, 0.5);
+        settings.cycle(5);
+        assert_eq!(settings.item_text(5), "Fullscreen: On");
+        settings.cycle(7);
+        assert_eq!(settings.item_text(7), "Scaling: Fill window");
+    }
+}
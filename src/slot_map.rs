@@ -0,0 +1,177 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Refers to a value in a `SlotMap<T>`. Handles only work with the map that
+/// made them.
+pub struct Handle<T> {
+    map: u32,
+    index: u32,
+    generation: u32,
+    _marker: PhantomData<fn() -> T>,
+}
+
+// Derives would require `T` to implement the traits as well.
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map && self.index == other.index && self.generation == other.generation
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> Hash for Handle<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.map.hash(state);
+        self.index.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Handle({}:{}v{})", self.map, self.index, self.generation)
+    }
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// A `Vec` of values addressed by `Handle`s, so lookups are a bounds and
+/// generation check. Removing a value bumps the generation of its slot, so
+/// handles to it stop working even once the slot is reused.
+pub struct SlotMap<T> {
+    // Unique to every map, so handles from other maps are rejected.
+    id: u32,
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+}
+
+impl<T> Default for SlotMap<T> {
+    fn default() -> Self {
+        static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+}
+
+impl<T> SlotMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, value: T) -> Handle<T> {
+        let index = match self.free.pop() {
+            Some(index) => {
+                self.slots[index as usize].value = Some(value);
+                index
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(value),
+                });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        Handle {
+            map: self.id,
+            index,
+            generation: self.slots[index as usize].generation,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn remove(&mut self, handle: Handle<T>) -> Option<T> {
+        let slot = self.slot_mut(handle)?;
+        let value = slot.value.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+        Some(value)
+    }
+
+    pub fn get(&self, handle: Handle<T>) -> Option<&T> {
+        if handle.map != self.id {
+            return None;
+        }
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)?
+            .value
+            .as_ref()
+    }
+
+    pub fn get_mut(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        self.slot_mut(handle)?.value.as_mut()
+    }
+
+    fn slot_mut(&mut self, handle: Handle<T>) -> Option<&mut Slot<T>> {
+        if handle.map != self.id {
+            return None;
+        }
+        self.slots
+            .get_mut(handle.index as usize)
+            .filter(|slot| slot.generation == handle.generation)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_get() {
+        let mut map = SlotMap::new();
+        let a = map.insert("a");
+        let b = map.insert("b");
+        assert_ne!(a, b);
+        assert_eq!(map.get(a), Some(&"a"));
+        *map.get_mut(b).unwrap() = "B";
+        assert_eq!(map.get(b), Some(&"B"));
+        assert_eq!(map.iter_mut().count(), 2);
+
+        // handles from another map don't work, even for a slot that exists
+        let mut other = SlotMap::new();
+        let c = other.insert("x");
+        assert_eq!(map.get(c), None);
+        assert_eq!(map.get_mut(c), None);
+        assert_eq!(map.remove(c), None);
+        assert_eq!(map.get(a), Some(&"a"));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut map = SlotMap::new();
+        let a = map.insert("a");
+        let b = map.insert("b");
+        assert_eq!(map.remove(a), Some("a"));
+        assert_eq!(map.remove(a), None);
+        assert_eq!(map.get(a), None);
+
+        // the slot is reused, but the old handle stays stale
+        let c = map.insert("c");
+        assert_ne!(a, c);
+        assert_eq!(map.get(a), None);
+        assert_eq!(map.get(c), Some(&"c"));
+        assert_eq!(map.get(b), Some(&"b"));
+        assert_eq!(map.iter_mut().count(), 2);
+    }
+}
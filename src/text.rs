@@ -0,0 +1,146 @@
+/// Splits `s` into lines no wider than `max_width`, as measured by `width`.
+/// Lines break at newlines and between words, a word too wide for a line of
+/// its own is broken between characters.
+pub fn wrap(
+    s: &str,
+    max_width: f32,
+    width: &mut dyn FnMut(&str) -> Result<f32, String>,
+) -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
+    for paragraph in s.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() {
+                let joined = format!("{line} {word}");
+                if width(&joined)? <= max_width {
+                    line = joined;
+                    continue;
+                }
+                lines.push(std::mem::take(&mut line));
+            }
+            if width(word)? <= max_width {
+                line.push_str(word);
+                continue;
+            }
+            for c in word.chars() {
+                line.push(c);
+                // a line always keeps at least one character
+                if line.chars().count() > 1 && width(&line)? > max_width {
+                    line.pop();
+                    lines.push(std::mem::replace(&mut line, c.to_string()));
+                }
+            }
+        }
+        lines.push(line);
+    }
+    Ok(lines)
+}
+
+/// The first `count` characters of `lines` that aren't whitespace, with the
+/// lines joined by newlines. Text revealed a bit at a time this way keeps the
+/// line breaks of the full text instead of rewrapping as it grows.
+pub fn reveal(lines: &[String], count: usize) -> String {
+    let mut revealed = String::new();
+    let mut left = count;
+    for (i, line) in lines.iter().enumerate() {
+        if left == 0 {
+            break;
+        }
+        if i > 0 {
+            revealed.push('\n');
+        }
+        for c in line.chars() {
+            if left == 0 {
+                break;
+            }
+            if !c.is_whitespace() {
+                left -= 1;
+            }
+            revealed.push(c);
+        }
+    }
+    revealed
+}
+
+/// Splits `s` into runs of characters that `font_for` puts in the same font.
+pub fn split_runs<'a>(
+    s: &'a str,
+    font_for: &mut dyn FnMut(char) -> usize,
+) -> Vec<(usize, &'a str)> {
+    let mut runs: Vec<(usize, &str)> = Vec::new();
+    let mut start = 0;
+    let mut current = None;
+    for (i, c) in s.char_indices() {
+        let font = font_for(c);
+        if let Some(prev) = current.filter(|prev| *prev != font) {
+            runs.push((prev, &s[start..i]));
+            start = i;
+        }
+        current = Some(font);
+    }
+    if let Some(font) = current {
+        runs.push((font, &s[start..]));
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wrap_chars(s: &str, max_width: f32) -> Vec<String> {
+        wrap(s, max_width, &mut |line| Ok(line.chars().count() as f32)).unwrap()
+    }
+
+    #[test]
+    fn test_wrap_words() {
+        assert_eq!(
+            wrap_chars("Nothing is, and everything is nothing.", 16.0),
+            vec!["Nothing is, and", "everything is", "nothing."]
+        );
+        assert_eq!(
+            wrap_chars("  spaces   collapse ", 100.0),
+            vec!["spaces collapse"]
+        );
+        assert_eq!(
+            wrap_chars("first line\n\nafter a blank line", 100.0),
+            vec!["first line", "", "after a blank line"]
+        );
+        assert_eq!(wrap_chars("", 10.0), vec![""]);
+    }
+
+    #[test]
+    fn test_wrap_long_words() {
+        assert_eq!(
+            wrap_chars("a Determination", 5.0),
+            vec!["a", "Deter", "minat", "ion"]
+        );
+        assert_eq!(wrap_chars("éèêëē", 2.0), vec!["éè", "êë", "ē"]);
+        // nothing fits, every character gets its own line
+        assert_eq!(wrap_chars("ab c", 0.5), vec!["a", "b", "c"]);
+        assert_eq!(
+            wrap("x", 1.0, &mut |_| Err("font not loaded".to_string())),
+            Err("font not loaded".to_string())
+        );
+    }
+
+    #[test]
+    fn test_reveal() {
+        let lines = wrap_chars("Nothing is, and everything is nothing.", 16.0);
+        assert_eq!(reveal(&lines, 0), "");
+        assert_eq!(reveal(&lines, 9), "Nothing is");
+        assert_eq!(reveal(&lines, 15), "Nothing is, and\nev");
+        assert_eq!(reveal(&lines, 100), lines.join("\n"));
+    }
+
+    #[test]
+    fn test_split_runs() {
+        let mut font_for = |c: char| if c.is_ascii() { 0 } else { 1 };
+        assert_eq!(
+            split_runs("Привет, world — hi", &mut font_for),
+            vec![(1, "Привет"), (0, ", world "), (1, "—"), (0, " hi")]
+        );
+        assert_eq!(split_runs("ascii", &mut font_for), vec![(0, "ascii")]);
+        assert_eq!(split_runs("", &mut font_for), vec![]);
+    }
+}
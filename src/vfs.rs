@@ -0,0 +1,244 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use crate::serialization::{crc32, DecodeError, DecodeErrorKind, Reader, Writer, DEFAULT_LEN_SIZE};
+
+const PACK_IDENTIFIER: [u8; 8] = [0x50, 0x41, 0x43, 0x4B, b'V', b'e', b'r', 1];
+const PACK_CHECKSUM_SIZE: usize = 4;
+
+// Generated by build.rs, empty unless built with the `embed-assets` feature.
+include!(concat!(env!("OUT_DIR"), "/embedded_assets.rs"));
+
+/// Where the files below a mount point come from.
+pub enum Source {
+    Dir(PathBuf),
+    /// By path relative to the mount point, with `/` separators.
+    Files(HashMap<String, Cow<'static, [u8]>>),
+}
+
+// Paths of files in memory are the same on every platform.
+fn file_key(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn read_dir_files(
+    dir: &Path,
+    prefix: &Path,
+    files: &mut Vec<(String, Vec<u8>)>,
+) -> Result<(), String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read \"{}\": {e}", dir.display()))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Failed to read \"{}\": {e}", dir.display()))?
+            .path();
+        let name = prefix.join(path.file_name().unwrap_or_default());
+        if path.is_dir() {
+            read_dir_files(&path, &name, files)?;
+        } else {
+            let data = std::fs::read(&path)
+                .map_err(|e| format!("Failed to read \"{}\": {e}", path.display()))?;
+            files.push((file_key(&name), data));
+        }
+    }
+    Ok(())
+}
+
+impl Source {
+    /// The assets built into the binary, if it was built with the
+    /// `embed-assets` feature.
+    pub fn embedded() -> Option<Self> {
+        if EMBEDDED_ASSETS.is_empty() {
+            return None;
+        }
+        Some(Source::Files(
+            EMBEDDED_ASSETS
+                .iter()
+                .map(|(path, data)| (path.to_string(), Cow::Borrowed(*data)))
+                .collect(),
+        ))
+    }
+
+    pub fn read_pack(data: &[u8]) -> Result<Self, String> {
+        decode_pack(data)
+            .map(Source::Files)
+            .map_err(|e| format!("Failed to read pack: {e}"))
+    }
+}
+
+fn decode_pack(data: &[u8]) -> Result<HashMap<String, Cow<'static, [u8]>>, DecodeError> {
+    let mut r = Reader::new(data);
+    r.expect_identifier(&PACK_IDENTIFIER)?;
+    let body_len = r
+        .remaining()
+        .checked_sub(PACK_CHECKSUM_SIZE)
+        .ok_or_else(|| {
+            r.error(DecodeErrorKind::UnexpectedEnd {
+                needed: PACK_CHECKSUM_SIZE,
+                remaining: r.remaining(),
+            })
+            .in_field("checksum")
+        })?;
+    let body_end = r.get_offset() + body_len;
+    let expected: u32 = r.fork_at(body_end, DEFAULT_LEN_SIZE).read("checksum")?;
+    let actual = crc32(&data[..body_end]);
+    if expected != actual {
+        return Err(DecodeError::new(
+            body_end,
+            DecodeErrorKind::ChecksumMismatch { expected, actual },
+        )
+        .in_field("checksum"));
+    }
+
+    let mut r = r.limit(body_len);
+    let mut files = HashMap::new();
+    for i in 0..r.read_len().map_err(|e| e.in_field("files"))? {
+        let field = format!("files[{i}]");
+        let path: String = r.read("path").map_err(|e| e.in_field(&field))?;
+        let data = r
+            .read_len()
+            .and_then(|len| r.read_bytes(len))
+            .map_err(|e| e.in_field("data").in_field(&field))?;
+        files.insert(path, Cow::Owned(data.to_vec()));
+    }
+    r.expect_end()?;
+    Ok(files)
+}
+
+/// Packs every file below `dir` into one archive for `Source::read_pack`.
+pub fn write_pack(dir: &Path) -> Result<Vec<u8>, String> {
+    let mut files = Vec::new();
+    read_dir_files(dir, Path::new(""), &mut files)?;
+    files.sort();
+
+    let mut w = Writer::new();
+    w.write_bytes(&PACK_IDENTIFIER);
+    w.write_len(files.len());
+    for (path, data) in &files {
+        w.write(path);
+        w.write_len(data.len());
+        w.write_bytes(data);
+    }
+    let mut bytes = w.into_bytes();
+    let checksum = crc32(&bytes);
+    bytes.extend(checksum.to_be_bytes());
+    Ok(bytes)
+}
+
+/// Serves files by path from the sources mounted at the start of it. Sources
+/// mounted later are searched first, by default there is only the working
+/// directory.
+pub struct Vfs {
+    mounts: Vec<(PathBuf, Source)>,
+}
+
+impl Default for Vfs {
+    fn default() -> Self {
+        Self {
+            mounts: vec![(PathBuf::new(), Source::Dir(PathBuf::new()))],
+        }
+    }
+}
+
+impl Vfs {
+    pub fn mount(&mut self, at: impl Into<PathBuf>, source: Source) {
+        self.mounts.push((at.into(), source));
+    }
+
+    fn find(&self, path: &Path) -> Option<Cow<'_, [u8]>> {
+        for (at, source) in self.mounts.iter().rev() {
+            let Ok(rel) = path.strip_prefix(at) else {
+                continue;
+            };
+            match source {
+                Source::Dir(dir) => {
+                    if let Ok(data) = std::fs::read(dir.join(rel)) {
+                        return Some(Cow::Owned(data));
+                    }
+                }
+                Source::Files(files) => {
+                    if let Some(data) = files.get(&file_key(rel)) {
+                        return Some(Cow::Borrowed(data));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    pub fn exists(&self, path: &Path) -> bool {
+        self.mounts.iter().rev().any(|(at, source)| {
+            path.strip_prefix(at).is_ok_and(|rel| match source {
+                Source::Dir(dir) => dir.join(rel).is_file(),
+                Source::Files(files) => files.contains_key(&file_key(rel)),
+            })
+        })
+    }
+
+    pub fn read(&self, path: &Path) -> Result<Cow<'_, [u8]>, String> {
+        self.find(path)
+            .ok_or_else(|| format!("File \"{}\" does not exist!", path.display()))
+    }
+
+    pub fn read_to_string(&self, path: &Path) -> Result<String, String> {
+        String::from_utf8(self.read(path)?.into_owned())
+            .map_err(|_| format!("File \"{}\" is not valid UTF-8!", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack() {
+        let pack = write_pack(Path::new("static")).unwrap();
+        let mut vfs = Vfs { mounts: Vec::new() };
+        vfs.mount("assets", Source::read_pack(&pack).unwrap());
+        assert!(vfs.exists(Path::new("assets/star.png")));
+        assert!(!vfs.exists(Path::new("static/star.png")));
+        assert_eq!(
+            vfs.read(Path::new("assets/./star.png")).unwrap(),
+            std::fs::read("static/star.png").unwrap()
+        );
+        assert_eq!(
+            vfs.read(Path::new("assets/missing.png")),
+            Err(String::from("File \"assets/missing.png\" does not exist!"))
+        );
+
+        let mut corrupt = pack.clone();
+        corrupt[20] ^= 1;
+        assert!(matches!(
+            decode_pack(&corrupt),
+            Err(DecodeError {
+                kind: DecodeErrorKind::ChecksumMismatch { .. },
+                ..
+            })
+        ));
+        assert!(Source::read_pack(&pack[..pack.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_mount_order() {
+        let mut vfs = Vfs::default();
+        assert!(vfs.exists(Path::new("static/manifest.txt")));
+        let files = HashMap::from([(
+            String::from("manifest.txt"),
+            Cow::Borrowed(&b"image star star.png"[..]),
+        )]);
+        vfs.mount("static", Source::Files(files));
+        assert_eq!(
+            vfs.read_to_string(Path::new("static/manifest.txt")),
+            Ok(String::from("image star star.png"))
+        );
+        // other files are still found in the directory
+        assert!(vfs.exists(Path::new("static/fish.png")));
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// Seconds between two scans of the directory.
+const POLL_INTERVAL: f32 = 0.5;
+
+fn scan(dir: &Path, mtimes: &mut HashMap<PathBuf, SystemTime>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            scan(&path, mtimes);
+        } else if let Ok(mtime) = entry.metadata().and_then(|metadata| metadata.modified()) {
+            mtimes.insert(path, mtime);
+        }
+    }
+}
+
+/// Finds the files in a directory that changed by polling their modification
+/// times.
+pub struct Watcher {
+    dir: PathBuf,
+    mtimes: HashMap<PathBuf, SystemTime>,
+    elapsed: f32,
+}
+
+impl Watcher {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        let mut mtimes = HashMap::new();
+        scan(&dir, &mut mtimes);
+        Self {
+            dir,
+            mtimes,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Files changed or added since the last scan, paths start with the
+    /// watched directory. Deleted files are not reported.
+    pub fn poll(&mut self, dt: f32) -> Vec<PathBuf> {
+        self.elapsed += dt;
+        if self.elapsed < POLL_INTERVAL {
+            return Vec::new();
+        }
+        self.elapsed = 0.0;
+
+        let mut mtimes = HashMap::new();
+        scan(&self.dir, &mut mtimes);
+        let mut changed: Vec<PathBuf> = mtimes
+            .iter()
+            .filter(|(path, mtime)| self.mtimes.get(*path) != Some(mtime))
+            .map(|(path, _)| path.clone())
+            .collect();
+        changed.sort();
+        self.mtimes = mtimes;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::time::Duration;
+
+    #[test]
+    fn test_poll() {
+        let dir = std::env::temp_dir().join(format!("ld45_watcher_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("shaders")).unwrap();
+        std::fs::write(dir.join("fish.png"), b"fish").unwrap();
+        std::fs::write(dir.join("shaders/simple.fs"), b"void main() {}").unwrap();
+
+        let mut watcher = Watcher::new(&dir);
+        assert_eq!(watcher.poll(1.0), Vec::<PathBuf>::new());
+
+        let later = SystemTime::now() + Duration::from_secs(10);
+        File::options()
+            .write(true)
+            .open(dir.join("shaders/simple.fs"))
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        std::fs::write(dir.join("star.png"), b"star").unwrap();
+        // not scanned again yet
+        assert_eq!(watcher.poll(0.1), Vec::<PathBuf>::new());
+        assert_eq!(
+            watcher.poll(POLL_INTERVAL),
+            vec![dir.join("shaders/simple.fs"), dir.join("star.png")]
+        );
+        assert_eq!(watcher.poll(POLL_INTERVAL), Vec::<PathBuf>::new());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
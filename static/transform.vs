@@ -16,13 +16,24 @@ uniform mat4 mvp;
 
 // custom
 uniform vec2 camera;
+uniform float zoom;
+uniform float rotation;
+uniform vec2 center;
 uniform vec2 origin;
 uniform mat4 transform;
 
+// Must match CameraView::world_to_screen().
+vec2 to_screen(vec2 world) {
+  vec2 p = (world - camera) * zoom - center;
+  float s = sin(-rotation);
+  float c = cos(-rotation);
+  return vec2(p.x * c - p.y * s, p.x * s + p.y * c) + center;
+}
+
 void main() {
   fragTexCoord = vertexTexCoord;
   fragColor = vertexColor;
 
   vec4 pos = transform * vec4((vertexPosition - vec3(origin, 0.0)), 0.0) + vec4(origin, 0.0, 0.0);
-  gl_Position = mvp * vec4(vec2(pos) - camera, 0.0, 1.0);
+  gl_Position = mvp * vec4(to_screen(vec2(pos)), 0.0, 1.0);
 }
@@ -25,9 +25,9 @@ void ld45_load_result_err(void *usr) {
     ld45_load_rust_handler(usr, NULL, 0);
 }
 
-void ld45_save_async(void *data, int length) {
+void ld45_save_async(const char *key, void *data, int length) {
     emscripten_idb_async_store("ld45_oneandall_db",
-                               "savedata",
+                               key,
                                data,
                                length,
                                NULL,
@@ -35,9 +35,9 @@ void ld45_save_async(void *data, int length) {
                                ld45_saved_result_err);
 }
 
-void ld45_load_async(void *usr) {
+void ld45_load_async(const char *key, void *usr) {
     emscripten_idb_async_load("ld45_oneandall_db",
-                              "savedata",
+                              key,
                               usr,
                               ld45_load_result_ok,
                               ld45_load_result_err);